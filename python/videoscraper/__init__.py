@@ -30,16 +30,20 @@ from videoscraper._core import (
     
     # HTTP Client
     PyHttpClient as HttpClient,
+    PyResponse as Response,
+    Probe,
     
     # Downloader
     PyDownloadManager as DownloadManager,
     DownloadProgress,
     DownloadResult,
+    ChecksumAlgorithm,
     
     # Extractor
     PyVideoExtractor as VideoExtractor,
     VideoInfo,
     VideoFormat,
+    SubtitleTrack,
     ExtractionResult,
     
     # Storage
@@ -52,6 +56,29 @@ from videoscraper._core import (
     JobStatus,
     PipelineStats,
     VideoFilter,
+    ProgressStream,
+    DedupEntry,
+    
+    # Exceptions
+    ScraperError,
+    HttpError,
+    InvalidUrlError,
+    ParseError,
+    ScraperIOError,
+    RateLimitError,
+    DownloadError,
+    ExtractionError,
+    StorageError,
+    ConfigError,
+    PipelineError,
+    RequestTimeoutError,
+    FileSizeError,
+    InvalidFormatError,
+    NotFoundError,
+    AccessDeniedError,
+    VerificationError,
+    CircuitOpenError,
+    CancelledError,
     
     # Convenience functions
     create_pipeline,
@@ -74,11 +101,15 @@ __all__ = [
     
     # Core components
     "HttpClient",
+    "Response",
+    "Probe",
     "DownloadManager",
     "DownloadProgress",
     "DownloadResult",
+    "ChecksumAlgorithm",
     "VideoExtractor",
     "VideoInfo",
+    "SubtitleTrack",
     "VideoFormat",
     "ExtractionResult",
     "Storage",
@@ -90,6 +121,29 @@ __all__ = [
     "JobStatus",
     "PipelineStats",
     "VideoFilter",
+    "ProgressStream",
+    "DedupEntry",
+    
+    # Exceptions
+    "ScraperError",
+    "HttpError",
+    "InvalidUrlError",
+    "ParseError",
+    "ScraperIOError",
+    "RateLimitError",
+    "DownloadError",
+    "ExtractionError",
+    "StorageError",
+    "ConfigError",
+    "PipelineError",
+    "RequestTimeoutError",
+    "FileSizeError",
+    "InvalidFormatError",
+    "NotFoundError",
+    "AccessDeniedError",
+    "VerificationError",
+    "CircuitOpenError",
+    "CancelledError",
     
     # High-level API
     "Scraper",
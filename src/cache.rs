@@ -0,0 +1,287 @@
+//! On-disk HTTP response cache keyed by request method and URL
+
+use crate::error::Result;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::{Method, Response, ResponseBuilderExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tracing::debug;
+use url::Url;
+
+/// Response extension marking a response that was served from the cache
+#[derive(Debug, Clone, Copy)]
+pub struct FromCache;
+
+/// Whether a response was served from the cache rather than the network
+pub fn is_from_cache(response: &Response) -> bool {
+    response.extensions().get::<FromCache>().is_some()
+}
+
+/// A response body plus the metadata needed to replay it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub stored_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub body: Bytes,
+}
+
+impl CachedResponse {
+    /// Read a response fully so it can be cached and replayed. A body that
+    /// grows past `limit` bytes (0 = unlimited) isn't buffered further: it
+    /// comes back as `Err`, a response streaming the bytes read so far
+    /// followed by the rest.
+    pub async fn read(
+        mut response: Response,
+        limit: u64,
+    ) -> Result<std::result::Result<Self, Response>> {
+        let url = response.url().to_string();
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.as_str().to_string(), v.to_string()))
+            })
+            .collect();
+
+        let mut body = BytesMut::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if limit > 0 && body.len() as u64 > limit {
+                return Ok(Err(rejoin(body.freeze(), response)));
+            }
+        }
+
+        Ok(Ok(Self {
+            url,
+            status,
+            headers,
+            stored_at: Utc::now(),
+            body: body.freeze(),
+        }))
+    }
+
+    /// Whether this entry is younger than the given TTL
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = Utc::now() - self.stored_at;
+        age.to_std().map(|age| age < ttl).unwrap_or(true)
+    }
+
+    /// Look up a header value (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Validators to revalidate this entry with a conditional request
+    pub fn conditional_headers(&self) -> Option<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        if let Some(value) = self.header("etag").and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = self
+            .header("last-modified")
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+
+        (!headers.is_empty()).then_some(headers)
+    }
+
+    /// Rebuild a `reqwest::Response` serving the cached body
+    pub fn into_response(self) -> Response {
+        self.build_response(true)
+    }
+
+    /// Rebuild the response this entry was just read from, not marked as
+    /// served from the cache
+    pub fn into_fetched_response(self) -> Response {
+        self.build_response(false)
+    }
+
+    fn build_response(self, from_cache: bool) -> Response {
+        let mut builder = http::Response::builder().status(self.status);
+        if from_cache {
+            builder = builder.extension(FromCache);
+        }
+        if let Ok(url) = Url::parse(&self.url) {
+            builder = builder.url(url);
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        // Status and headers came from a real response, so this cannot fail
+        let response = builder
+            .body(self.body)
+            .expect("cached response has valid parts");
+        Response::from(response)
+    }
+}
+
+/// A response whose first `read` bytes were already taken off `rest`,
+/// streaming them again before the remainder of the body
+fn rejoin(read: Bytes, rest: Response) -> Response {
+    let builder = crate::client::response_builder(&rest);
+    let body = futures::stream::once(async move { Ok(read) }).chain(rest.bytes_stream());
+    // Status and headers came from a real response, so this cannot fail
+    let response = builder
+        .body(reqwest::Body::wrap_stream(body))
+        .expect("response parts are valid");
+    Response::from(response)
+}
+
+/// Disk-backed response cache
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// Total size `store` trims the cache to, 0 for unbounded
+    max_bytes: u64,
+    /// Bytes on disk, counted on the first `store`
+    size: Mutex<Option<u64>>,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl AsRef<Path>, ttl_secs: u64, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            ttl: Duration::from_secs(ttl_secs),
+            max_bytes,
+            size: Mutex::new(None),
+        }
+    }
+
+    /// How long entries are served without hitting the network
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn entry_paths(&self, method: &Method, url: &str) -> (PathBuf, PathBuf) {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_str().as_bytes());
+        hasher.update(b" ");
+        hasher.update(url.as_bytes());
+        let key = hex::encode(hasher.finalize());
+
+        (
+            self.dir.join(format!("{}.json", key)),
+            self.dir.join(format!("{}.body", key)),
+        )
+    }
+
+    /// Load an entry regardless of age; callers decide on freshness
+    pub async fn lookup(&self, method: &Method, url: &str) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.entry_paths(method, url);
+        let meta = fs::read_to_string(&meta_path).await.ok()?;
+        let mut entry: CachedResponse = serde_json::from_str(&meta).ok()?;
+        entry.body = Bytes::from(fs::read(&body_path).await.ok()?);
+        Some(entry)
+    }
+
+    /// Store an entry, writing the body before the metadata that marks it
+    /// complete, then evict the oldest entries if the cache grew too large
+    pub async fn store(&self, method: &Method, url: &str, entry: &CachedResponse) -> Result<()> {
+        let (meta_path, body_path) = self.entry_paths(method, url);
+        let meta = serde_json::to_string(entry)?;
+
+        let mut size = self.size.lock().await;
+        fs::create_dir_all(&self.dir).await?;
+        let mut total = match *size {
+            Some(total) => total,
+            None => self.disk_usage().await?,
+        };
+        // A replaced entry no longer counts
+        for path in [&meta_path, &body_path] {
+            if let Ok(old) = fs::metadata(path).await {
+                total = total.saturating_sub(old.len());
+            }
+        }
+
+        fs::write(&body_path, &entry.body).await?;
+        fs::write(&meta_path, &meta).await?;
+        total += entry.body.len() as u64 + meta.len() as u64;
+
+        if self.max_bytes > 0 && total > self.max_bytes {
+            total = self.evict(total).await?;
+        }
+        *size = Some(total);
+        Ok(())
+    }
+
+    /// Bytes taken by every file in the cache directory
+    async fn disk_usage(&self) -> Result<u64> {
+        let mut total = 0;
+        let mut dir = fs::read_dir(&self.dir).await?;
+        while let Some(file) = dir.next_entry().await? {
+            total += file.metadata().await?.len();
+        }
+        Ok(total)
+    }
+
+    /// Remove entries, least recently stored first, until `total` bytes fit
+    /// in `max_bytes`. Returns the bytes left.
+    async fn evict(&self, mut total: u64) -> Result<u64> {
+        // Unreadable metadata sorts first, as it can't be served anyway
+        let mut entries: Vec<(Option<DateTime<Utc>>, PathBuf)> = Vec::new();
+        let mut dir = fs::read_dir(&self.dir).await?;
+        while let Some(file) = dir.next_entry().await? {
+            let path = file.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let stored_at = fs::read_to_string(&path)
+                    .await
+                    .ok()
+                    .and_then(|meta| serde_json::from_str::<CachedResponse>(&meta).ok())
+                    .map(|entry| entry.stored_at);
+                entries.push((stored_at, path));
+            }
+        }
+        entries.sort();
+
+        for (_, meta_path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            // The metadata goes first, so a half-removed entry reads as a miss
+            for path in [meta_path.clone(), meta_path.with_extension("body")] {
+                if let Ok(file) = fs::metadata(&path).await {
+                    fs::remove_file(&path).await?;
+                    total = total.saturating_sub(file.len());
+                }
+            }
+            debug!("Evicted {:?} from the response cache", meta_path);
+        }
+        Ok(total)
+    }
+
+    /// Remove the entry for a request, if any
+    pub async fn invalidate(&self, method: &Method, url: &str) -> Result<()> {
+        let (meta_path, body_path) = self.entry_paths(method, url);
+        let mut size = self.size.lock().await;
+        for path in [meta_path, body_path] {
+            let len = fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+            match fs::remove_file(&path).await {
+                Ok(()) => *size = size.map(|total| total.saturating_sub(len)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+//! Checksums computed over downloads as the bytes arrive
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// A checksum a download can be verified against
+#[pyclass]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    /// What S3 reports as the ETag of a single-part upload
+    Md5,
+    Crc32,
+    /// CRC-32C (Castagnoli), as used by GCS and S3 checksums
+    Crc32c,
+}
+
+impl ChecksumAlgorithm {
+    /// Lowercase name, used as the key in `DownloadResult.checksums`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Md5 => "md5",
+            Self::Crc32 => "crc32",
+            Self::Crc32c => "crc32c",
+        }
+    }
+}
+
+#[pymethods]
+impl ChecksumAlgorithm {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.name()
+    }
+}
+
+/// Running checksums of a download. SHA-256 is always computed, since resume
+/// and `expected_sha256` rely on it; the other algorithms only when asked for.
+#[derive(Clone)]
+pub struct Checksums {
+    sha256: Sha256,
+    md5: Option<md5::Context>,
+    crc32: Option<crc_fast::Digest>,
+    crc32c: Option<crc_fast::Digest>,
+}
+
+/// Finished checksums, as lowercase hex
+pub struct Digests {
+    pub sha256: String,
+    /// Every computed checksum, keyed by `ChecksumAlgorithm::name`
+    pub all: HashMap<String, String>,
+}
+
+impl Checksums {
+    pub fn new(algorithms: &[ChecksumAlgorithm]) -> Self {
+        let wants = |algorithm| algorithms.contains(&algorithm);
+        Self {
+            sha256: Sha256::new(),
+            md5: wants(ChecksumAlgorithm::Md5).then(md5::Context::new),
+            crc32: wants(ChecksumAlgorithm::Crc32)
+                .then(|| crc_fast::Digest::new(crc_fast::CrcAlgorithm::Crc32IsoHdlc)),
+            crc32c: wants(ChecksumAlgorithm::Crc32c)
+                .then(|| crc_fast::Digest::new(crc_fast::CrcAlgorithm::Crc32Iscsi)),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.sha256.update(data);
+        if let Some(md5) = &mut self.md5 {
+            md5.consume(data);
+        }
+        for crc in [&mut self.crc32, &mut self.crc32c].into_iter().flatten() {
+            crc.update(data);
+        }
+    }
+
+    /// SHA-256 of the bytes so far, without finishing the other checksums
+    pub fn sha256_hex(&self) -> String {
+        hex::encode(self.sha256.clone().finalize())
+    }
+
+    pub fn finalize(self) -> Digests {
+        let sha256 = hex::encode(self.sha256.finalize());
+        let mut all = HashMap::from([("sha256".to_string(), sha256.clone())]);
+        if let Some(md5) = self.md5 {
+            all.insert("md5".to_string(), format!("{:x}", md5.compute()));
+        }
+        // CRCs are written big-endian, the byte order S3 and GCS encode them in
+        if let Some(crc) = self.crc32 {
+            all.insert("crc32".to_string(), format!("{:08x}", crc.finalize()));
+        }
+        if let Some(crc) = self.crc32c {
+            all.insert("crc32c".to_string(), format!("{:08x}", crc.finalize()));
+        }
+        Digests { sha256, all }
+    }
+}
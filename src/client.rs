@@ -1,19 +1,32 @@
 //! High-performance HTTP client with connection pooling and rate limiting
 
+use crate::cache::{self, CachedResponse, ResponseCache};
 use crate::config::ScraperConfig;
 use crate::error::{Result, ScraperError};
+use crate::robots::RobotsRules;
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use dashmap::DashMap;
+use futures::StreamExt;
 use governor::{Quota, RateLimiter};
 use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use reqwest::{
-    header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, RANGE, USER_AGENT},
-    Client, Response, StatusCode,
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_ENCODING, ACCEPT_LANGUAGE,
+        CONTENT_LENGTH, CONTENT_TYPE, IF_RANGE, RANGE, USER_AGENT,
+    },
+    Client, Method, Response, ResponseBuilderExt, StatusCode,
 };
+use std::collections::HashMap;
 use std::num::NonZeroU32;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::time::sleep;
-use tracing::{debug, info, warn};
+use tracing::{debug, warn};
 use url::Url;
 
 type DomainRateLimiter = RateLimiter<
@@ -22,11 +35,132 @@ type DomainRateLimiter = RateLimiter<
     governor::clock::DefaultClock,
 >;
 
+/// Upper bound on how long a Retry-After header can make us wait
+const MAX_RETRY_AFTER_SECS: u64 = 3600;
+
+/// Parse a Retry-After header value in either delta-seconds or HTTP-date form
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    let value = value.trim();
+
+    let secs = match value.parse::<i64>() {
+        Ok(delta) => delta,
+        Err(_) => {
+            // IMF-fixdate, e.g. "Wed, 21 Oct 2015 07:28:00 GMT". The weekday is
+            // redundant and servers get it wrong often enough to not trust it.
+            let (_, date) = value.split_once(", ")?;
+            let date = NaiveDateTime::parse_from_str(date, "%d %b %Y %H:%M:%S GMT").ok()?;
+            (date.and_utc() - now).num_seconds()
+        }
+    };
+
+    Some(secs.clamp(0, MAX_RETRY_AFTER_SECS as i64) as u64)
+}
+
+/// How long parsed robots.txt rules are reused before fetching them again
+const ROBOTS_TTL_SECS: u64 = 3600;
+
+/// Read the CA certificates in the PEM bundle at `path`
+fn load_ca_certs(path: &str) -> Result<Vec<reqwest::Certificate>> {
+    let invalid = |problem: String| {
+        ScraperError::ConfigError(format!("Invalid ca_cert_path {:?}: {}", path, problem))
+    };
+    let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no PEM certificates found".to_string()));
+    }
+    Ok(certs)
+}
+
+/// Follow up to `max_redirects` hops. `Policy::limited` counts the original
+/// URL among the previous ones, which would allow one hop fewer.
+fn redirect_policy(max_redirects: usize) -> reqwest::redirect::Policy {
+    if max_redirects == 0 {
+        return reqwest::redirect::Policy::none();
+    }
+
+    reqwest::redirect::Policy::custom(move |attempt| {
+        // `previous` holds the original URL plus each hop taken so far
+        if attempt.previous().len() > max_redirects {
+            attempt.error(format!("more than {} redirects", max_redirects))
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Consecutive connection errors before a proxy is benched
+const PROXY_BENCH_THRESHOLD: u32 = 3;
+
+/// How long a benched proxy is skipped
+const PROXY_BENCH_SECS: u64 = 60;
+
+/// A reqwest client bound to one proxy (or none), with health tracking
+struct ProxyRoute {
+    proxy_url: Option<String>,
+    client: Client,
+    consecutive_failures: AtomicU32,
+    benched_until: Mutex<Option<Instant>>,
+}
+
+impl ProxyRoute {
+    fn is_benched(&self) -> bool {
+        self.benched_until
+            .lock()
+            .unwrap()
+            .map(|until| Instant::now() < until)
+            .unwrap_or(false)
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+    }
+
+    fn record_connection_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.proxy_url.is_some() && failures >= PROXY_BENCH_THRESHOLD {
+            warn!(
+                "Proxy {} failed {} times in a row, benching for {}s",
+                self.proxy_url.as_deref().unwrap_or_default(),
+                failures,
+                PROXY_BENCH_SECS
+            );
+            *self.benched_until.lock().unwrap() =
+                Some(Instant::now() + Duration::from_secs(PROXY_BENCH_SECS));
+            self.consecutive_failures.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Circuit breaker state for one host
+enum Circuit {
+    /// Requests go through; `failures` in a row since `since`
+    Closed { failures: u32, since: Instant },
+    /// Requests fail fast until `until`
+    Open { until: Instant },
+    /// A single probe request has been let through since `probe_started`
+    HalfOpen { probe_started: Instant },
+}
+
 /// HTTP client with automatic rate limiting and connection pooling
 pub struct HttpClient {
-    client: Client,
+    routes: Vec<ProxyRoute>,
+    route_cursors: DashMap<String, AtomicUsize>,
     config: ScraperConfig,
     rate_limiters: Arc<DashMap<String, Arc<DomainRateLimiter>>>,
+    domain_semaphores: Arc<DashMap<String, Arc<Semaphore>>>,
+    retry_rng: Mutex<StdRng>,
+    cache: Option<ResponseCache>,
+    /// Parsed robots.txt per origin, one set of rules per user agent we
+    /// send, with when it was fetched
+    robots: DashMap<String, (Instant, Arc<Vec<RobotsRules>>)>,
+    /// Earliest time of the next request per origin under a `Crawl-delay`
+    crawl_slots: DashMap<String, Instant>,
+    /// Circuit breaker per domain; domains without failures have no entry
+    circuits: DashMap<String, Circuit>,
+    /// Rotated over in place of the default `User-Agent` when non-empty
+    user_agents: Vec<HeaderValue>,
+    user_agent_cursor: AtomicUsize,
 }
 
 impl HttpClient {
@@ -41,35 +175,168 @@ impl HttpClient {
         if config.enable_compression {
             headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip, deflate, br"));
         }
+        if let Some(ref accept_language) = config.accept_language {
+            let value = HeaderValue::from_str(accept_language).map_err(|_| {
+                ScraperError::ConfigError(format!("Invalid accept_language: {:?}", accept_language))
+            })?;
+            headers.insert(ACCEPT_LANGUAGE, value);
+        }
+        for (name, value) in &config.extra_headers {
+            let invalid =
+                || ScraperError::ConfigError(format!("Invalid header {}: {:?}", name, value));
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+
+        // `proxy_url` is shorthand for a single-entry pool
+        let mut proxy_urls = config.proxy_urls.clone();
+        if let Some(ref proxy_url) = config.proxy_url {
+            if !proxy_urls.contains(proxy_url) {
+                proxy_urls.push(proxy_url.clone());
+            }
+        }
+
+        if config.danger_accept_invalid_certs {
+            warn!(
+                "TLS certificate verification is DISABLED (danger_accept_invalid_certs); \
+                 responses can be intercepted or forged"
+            );
+        }
+        let ca_certs = match config.ca_cert_path {
+            Some(ref path) => load_ca_certs(path)?,
+            None => Vec::new(),
+        };
+
+        let routes = if proxy_urls.is_empty() {
+            vec![Self::build_route(config, &headers, &ca_certs, None)?]
+        } else {
+            proxy_urls
+                .iter()
+                .map(|url| Self::build_route(config, &headers, &ca_certs, Some(url)))
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        Ok(Self {
+            routes,
+            route_cursors: DashMap::new(),
+            config: config.clone(),
+            rate_limiters: Arc::new(DashMap::new()),
+            domain_semaphores: Arc::new(DashMap::new()),
+            retry_rng: Mutex::new(StdRng::from_entropy()),
+            cache: config.enable_caching.then(|| {
+                ResponseCache::new(
+                    &config.cache_dir,
+                    config.cache_ttl_secs,
+                    config.cache_max_bytes,
+                )
+            }),
+            robots: DashMap::new(),
+            crawl_slots: DashMap::new(),
+            circuits: DashMap::new(),
+            user_agents: config
+                .user_agents
+                .iter()
+                .map(|user_agent| {
+                    HeaderValue::from_str(user_agent).map_err(|_| {
+                        ScraperError::ConfigError(format!(
+                            "Invalid user agent: {:?}",
+                            user_agent
+                        ))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            user_agent_cursor: AtomicUsize::new(0),
+        })
+    }
 
+    fn build_route(
+        config: &ScraperConfig,
+        headers: &HeaderMap,
+        ca_certs: &[reqwest::Certificate],
+        proxy_url: Option<&str>,
+    ) -> Result<ProxyRoute> {
         let mut builder = Client::builder()
-            .default_headers(headers)
+            .default_headers(headers.clone())
             .timeout(Duration::from_secs(config.request_timeout_secs))
-            .connect_timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
             .pool_max_idle_per_host(config.pool_size_per_host)
             .pool_idle_timeout(Duration::from_secs(config.idle_timeout_secs))
             .tcp_keepalive(Duration::from_secs(60))
             .tcp_nodelay(true)
             .gzip(config.enable_compression)
             .brotli(config.enable_compression)
-            .deflate(config.enable_compression);
+            .deflate(config.enable_compression)
+            .redirect(redirect_policy(config.max_redirects))
+            .danger_accept_invalid_certs(config.danger_accept_invalid_certs);
+        for cert in ca_certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
 
-        if let Some(ref proxy_url) = config.proxy_url {
+        if let Some(proxy_url) = proxy_url {
             let proxy = reqwest::Proxy::all(proxy_url).map_err(|e| {
                 ScraperError::ConfigError(format!("Invalid proxy URL: {}", e))
             })?;
             builder = builder.proxy(proxy);
         }
 
-        let client = builder.build()?;
-
-        Ok(Self {
-            client,
-            config: config.clone(),
-            rate_limiters: Arc::new(DashMap::new()),
+        Ok(ProxyRoute {
+            proxy_url: proxy_url.map(|s| s.to_string()),
+            client: builder.build()?,
+            consecutive_failures: AtomicU32::new(0),
+            benched_until: Mutex::new(None),
         })
     }
 
+    /// Pick the next route for a domain, round-robin, skipping benched proxies
+    fn next_route(&self, url: &str) -> &ProxyRoute {
+        if self.routes.len() == 1 {
+            return &self.routes[0];
+        }
+
+        let domain = Self::domain_of(url).unwrap_or_default();
+        let start = self
+            .route_cursors
+            .entry(domain)
+            .or_insert_with(|| AtomicUsize::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+
+        (0..self.routes.len())
+            .map(|offset| &self.routes[(start + offset) % self.routes.len()])
+            .find(|route| !route.is_benched())
+            // Every proxy is benched: keep rotating rather than failing outright
+            .unwrap_or(&self.routes[start % self.routes.len()])
+    }
+
+    /// The next user agent from `user_agents`, round-robin, or `None` to send
+    /// the client's default
+    fn next_user_agent(&self) -> Option<HeaderValue> {
+        if self.user_agents.is_empty() {
+            return None;
+        }
+        let index = self.user_agent_cursor.fetch_add(1, Ordering::Relaxed);
+        Some(self.user_agents[index % self.user_agents.len()].clone())
+    }
+
+    /// Reseed the retry jitter RNG so backoff sequences are reproducible
+    pub fn set_retry_seed(&self, seed: u64) {
+        *self.retry_rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+    }
+
+    /// Delay before the next retry: exponential backoff, optionally with full jitter
+    pub(crate) fn retry_delay(&self, attempt: u32) -> Duration {
+        let base_delay = Duration::from_millis(self.config.retry_delay_ms);
+        let delay = base_delay * 2u32.pow(attempt.saturating_sub(1));
+
+        if !self.config.retry_jitter {
+            return delay;
+        }
+
+        let max_ms = delay.as_millis() as u64;
+        Duration::from_millis(self.retry_rng.lock().unwrap().gen_range(0..=max_ms))
+    }
+
     /// Get or create a rate limiter for a domain
     fn get_rate_limiter(&self, domain: &str) -> Arc<DomainRateLimiter> {
         if let Some(limiter) = self.rate_limiters.get(domain) {
@@ -90,15 +357,124 @@ impl HttpClient {
         limiter
     }
 
-    /// Extract domain from URL for rate limiting
-    fn get_domain(url: &str) -> Result<String> {
+    /// Every domain with a rate limiter so far and the requests per second
+    /// it is held to, sorted by domain
+    pub fn rate_limiter_status(&self) -> Vec<(String, f64)> {
+        let rate = self.config.rate_limit_per_second;
+        let mut status: Vec<(String, f64)> = self
+            .rate_limiters
+            .iter()
+            .map(|entry| (entry.key().clone(), rate))
+            .collect();
+        status.sort_by(|a, b| a.0.cmp(&b.0));
+        status
+    }
+
+    /// Acquire a slot in the per-domain concurrency limit (0 = unlimited)
+    async fn acquire_domain_permit(&self, domain: &str) -> Result<Option<OwnedSemaphorePermit>> {
+        let limit = self.config.max_requests_per_domain;
+        if limit == 0 {
+            return Ok(None);
+        }
+
+        let semaphore = self
+            .domain_semaphores
+            .entry(domain.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+
+        let permit = semaphore.acquire_owned().await.map_err(|_| {
+            ScraperError::PipelineError(format!("Request limiter closed for {}", domain))
+        })?;
+        Ok(Some(permit))
+    }
+
+    /// The host a URL's requests are rate limited and counted under
+    pub fn domain_of(url: &str) -> Result<String> {
         let parsed = Url::parse(url)?;
         Ok(parsed.host_str().unwrap_or("unknown").to_string())
     }
 
+    /// Fail fast while the circuit for `domain` is open. Once the cooldown
+    /// has passed, let one probe request through and hold back the rest
+    /// until it reports back.
+    fn check_circuit(&self, domain: &str) -> Result<()> {
+        if self.config.circuit_breaker_threshold == 0 {
+            return Ok(());
+        }
+        let Some(mut circuit) = self.circuits.get_mut(domain) else {
+            return Ok(());
+        };
+
+        let cooldown = Duration::from_secs(self.config.circuit_breaker_cooldown_secs);
+        let reopens_at = match *circuit {
+            Circuit::Closed { .. } => return Ok(()),
+            Circuit::Open { until } => until,
+            // A probe that never reported back (e.g. its caller was
+            // cancelled) frees the slot after another cooldown
+            Circuit::HalfOpen { probe_started } => probe_started + cooldown,
+        };
+
+        let now = Instant::now();
+        if now < reopens_at {
+            return Err(ScraperError::CircuitOpen {
+                domain: domain.to_string(),
+                retry_after_secs: (reopens_at - now).as_secs_f64().ceil() as u64,
+            });
+        }
+
+        debug!("Circuit for {} half-open, sending a probe", domain);
+        *circuit = Circuit::HalfOpen { probe_started: now };
+        Ok(())
+    }
+
+    /// Feed the result of a request to `domain` into its circuit breaker.
+    /// Server errors and connection failures count against the host; any
+    /// other response closes the circuit.
+    fn record_outcome(&self, domain: &str, failed: bool) {
+        let threshold = self.config.circuit_breaker_threshold;
+        if threshold == 0 {
+            return;
+        }
+        if !failed {
+            self.circuits.remove(domain);
+            return;
+        }
+
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.circuit_breaker_window_secs);
+        let mut circuit = self
+            .circuits
+            .entry(domain.to_string())
+            .or_insert(Circuit::Closed { failures: 0, since: now });
+
+        let open = match *circuit {
+            Circuit::Closed { failures, since } => {
+                let (failures, since) = if now.duration_since(since) > window {
+                    (1, now)
+                } else {
+                    (failures + 1, since)
+                };
+                *circuit = Circuit::Closed { failures, since };
+                failures >= threshold
+            }
+            // The probe failed, so the host is still down
+            Circuit::HalfOpen { .. } => true,
+            Circuit::Open { .. } => false,
+        };
+
+        if open {
+            let cooldown = self.config.circuit_breaker_cooldown_secs;
+            warn!("Opening circuit for {} for {}s after repeated failures", domain, cooldown);
+            *circuit = Circuit::Open {
+                until: now + Duration::from_secs(cooldown),
+            };
+        }
+    }
+
     /// Wait for rate limit if needed
     async fn wait_for_rate_limit(&self, url: &str) -> Result<()> {
-        let domain = Self::get_domain(url)?;
+        let domain = Self::domain_of(url)?;
         let limiter = self.get_rate_limiter(&domain);
         
         // Wait until we can make a request
@@ -106,9 +482,150 @@ impl HttpClient {
         Ok(())
     }
 
-    /// Perform a GET request with automatic retries
+    /// When `respect_robots_txt` is set, refuse URLs the site's robots.txt
+    /// disallows for any user agent we send, since a rotated one may carry
+    /// the request, and space requests by the longest `Crawl-delay`
+    async fn check_robots(&self, url: &str) -> Result<()> {
+        if !self.config.respect_robots_txt {
+            return Ok(());
+        }
+
+        let parsed = Url::parse(url)?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.path() == "/robots.txt" {
+            return Ok(());
+        }
+
+        let origin = parsed.origin().ascii_serialization();
+        let rules = self.robots_rules(&parsed, &origin).await?;
+
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        if !rules.iter().all(|rules| rules.is_allowed(&path)) {
+            return Err(ScraperError::AccessDenied(format!(
+                "{} is disallowed by robots.txt",
+                url
+            )));
+        }
+
+        if let Some(delay) = rules.iter().filter_map(RobotsRules::crawl_delay).max() {
+            let wait = {
+                let now = Instant::now();
+                let mut next = self.crawl_slots.entry(origin).or_insert(now);
+                let at = (*next).max(now);
+                *next = at + delay;
+                at - now
+            };
+            if !wait.is_zero() {
+                debug!("Crawl-delay: waiting {:?} before {}", wait, url);
+                sleep(wait).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cached robots.txt rules for an origin, fetched when missing or expired
+    async fn robots_rules(&self, url: &Url, origin: &str) -> Result<Arc<Vec<RobotsRules>>> {
+        if let Some(entry) = self.robots.get(origin) {
+            if entry.0.elapsed() < Duration::from_secs(ROBOTS_TTL_SECS) {
+                return Ok(entry.1.clone());
+            }
+        }
+
+        let robots_url = url.join("/robots.txt")?;
+        self.wait_for_rate_limit(robots_url.as_str()).await?;
+        let response = self
+            .next_route(robots_url.as_str())
+            .client
+            .get(robots_url.clone())
+            .send()
+            .await?;
+
+        let status = response.status();
+        let rules = if status.is_success() {
+            let text = response.text().await?;
+            let agents = if self.config.user_agents.is_empty() {
+                std::slice::from_ref(&self.config.user_agent)
+            } else {
+                &self.config.user_agents[..]
+            };
+            agents.iter().map(|agent| RobotsRules::parse(&text, agent)).collect()
+        } else if status.is_client_error() {
+            // A missing or forbidden robots.txt places no restrictions
+            vec![RobotsRules::allow_all()]
+        } else {
+            // Unreachable rules mean nothing may be crawled; not cached, so the
+            // next request tries again
+            warn!("Could not read {}: HTTP {}", robots_url, status);
+            return Err(ScraperError::HttpError(response.error_for_status().unwrap_err()));
+        };
+
+        let rules = Arc::new(rules);
+        self.robots.insert(origin.to_string(), (Instant::now(), rules.clone()));
+        Ok(rules)
+    }
+
+    /// Perform a GET request with automatic retries.
+    ///
+    /// When caching is enabled, successful responses are buffered and stored
+    /// on disk, and fresh cache hits skip both the network and the rate
+    /// limiter. Stale entries are revalidated with `If-None-Match` /
+    /// `If-Modified-Since` and served from disk on a 304. Use
+    /// `get_with_headers` for large bodies that should stream.
     pub async fn get(&self, url: &str) -> Result<Response> {
-        self.get_with_headers(url, None).await
+        let cache = match self.cache {
+            Some(ref cache) => cache,
+            None => return self.get_with_headers(url, None).await,
+        };
+
+        let cached = cache.lookup(&Method::GET, url).await;
+        let mut validators = None;
+        if let Some(ref entry) = cached {
+            if entry.is_fresh(cache.ttl()) {
+                debug!("Cache hit: {}", url);
+                return Ok(entry.clone().into_response());
+            }
+            validators = entry.conditional_headers();
+        }
+
+        let response = self.get_with_headers(url, validators).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = cached {
+                debug!("Not modified, serving cached copy: {}", url);
+                entry.stored_at = chrono::Utc::now();
+                if let Err(e) = cache.store(&Method::GET, url, &entry).await {
+                    warn!("Failed to refresh cache entry for {}: {}", url, e);
+                }
+                return Ok(entry.into_response());
+            }
+        }
+
+        // Bodies too large to buffer as a page are streamed uncached, whether
+        // the length is declared or only found out while reading
+        let limit = self.config.max_page_bytes;
+        let oversized = limit > 0 && body_length(&response).is_some_and(|len| len > limit);
+        if response.status() != StatusCode::OK || oversized {
+            return Ok(response);
+        }
+
+        let entry = match CachedResponse::read(response, limit).await? {
+            Ok(entry) => entry,
+            Err(uncached) => return Ok(uncached),
+        };
+        if let Err(e) = cache.store(&Method::GET, url, &entry).await {
+            warn!("Failed to cache response for {}: {}", url, e);
+        }
+        Ok(entry.into_fetched_response())
+    }
+
+    /// Drop any cached response for a URL
+    pub async fn invalidate_cache(&self, url: &str) -> Result<()> {
+        match self.cache {
+            Some(ref cache) => cache.invalidate(&Method::GET, url).await,
+            None => Ok(()),
+        }
     }
 
     /// Perform a GET request with custom headers
@@ -117,27 +634,66 @@ impl HttpClient {
         url: &str,
         headers: Option<HeaderMap>,
     ) -> Result<Response> {
+        self.send_with_retry(Method::GET, url, headers, None).await
+    }
+
+    /// Perform a POST request with automatic retries
+    pub async fn post(
+        &self,
+        url: &str,
+        body: Bytes,
+        headers: Option<HeaderMap>,
+    ) -> Result<Response> {
+        self.send_with_retry(Method::POST, url, headers, Some(body)).await
+    }
+
+    /// Send a request through the rate limiter, retrying on 429 and 5xx
+    async fn send_with_retry(
+        &self,
+        method: Method,
+        url: &str,
+        headers: Option<HeaderMap>,
+        body: Option<Bytes>,
+    ) -> Result<Response> {
+        self.check_robots(url).await?;
+
+        // Held across retries and then by the response until its body is read
+        let domain = Self::domain_of(url)?;
+        let permit = self.acquire_domain_permit(&domain).await?;
+
         self.wait_for_rate_limit(url).await?;
 
         let mut attempt = 0;
         let max_retries = self.config.max_retries;
-        let base_delay = Duration::from_millis(self.config.retry_delay_ms);
 
         loop {
             attempt += 1;
-            debug!("HTTP GET attempt {}/{}: {}", attempt, max_retries, url);
+            // Stop retrying as soon as the host's circuit opens
+            self.check_circuit(&domain)?;
+            debug!("HTTP {} attempt {}/{}: {}", method, attempt, max_retries, url);
 
-            let mut request = self.client.get(url);
+            let route = self.next_route(url);
+            let mut request = route.client.request(method.clone(), url);
+            if let Some(user_agent) = self.next_user_agent() {
+                request = request.header(USER_AGENT, user_agent);
+            }
+            // Caller headers come last so an explicit User-Agent still wins
             if let Some(ref h) = headers {
                 request = request.headers(h.clone());
             }
+            if let Some(ref b) = body {
+                request = request.body(b.clone());
+            }
 
             match request.send().await {
                 Ok(response) => {
+                    route.record_success();
                     let status = response.status();
+                    self.record_outcome(&domain, status.is_server_error());
                     
-                    if status.is_success() || status == StatusCode::PARTIAL_CONTENT {
-                        return Ok(response);
+                    // 3xx only reaches us when it wasn't followed (e.g. 304 Not Modified)
+                    if status.is_success() || status.is_redirection() {
+                        return Ok(hold_permit(response, permit));
                     }
 
                     if status == StatusCode::TOO_MANY_REQUESTS {
@@ -146,7 +702,7 @@ impl HttpClient {
                             .headers()
                             .get("Retry-After")
                             .and_then(|v| v.to_str().ok())
-                            .and_then(|s| s.parse::<u64>().ok())
+                            .and_then(|s| parse_retry_after(s, Utc::now()))
                             .unwrap_or(60);
 
                         warn!(
@@ -174,7 +730,7 @@ impl HttpClient {
 
                     // Retry on server errors
                     if status.is_server_error() && attempt < max_retries {
-                        let delay = base_delay * 2u32.pow(attempt - 1);
+                        let delay = self.retry_delay(attempt);
                         warn!(
                             "Server error {} on {}, retrying in {:?}",
                             status, url, delay
@@ -188,6 +744,11 @@ impl HttpClient {
                     ));
                 }
                 Err(e) => {
+                    if e.is_connect() || e.is_timeout() {
+                        route.record_connection_failure();
+                        self.record_outcome(&domain, true);
+                    }
+
                     if attempt >= max_retries {
                         return Err(ScraperError::DownloadFailed {
                             attempts: attempt,
@@ -195,7 +756,7 @@ impl HttpClient {
                         });
                     }
 
-                    let delay = base_delay * 2u32.pow(attempt - 1);
+                    let delay = self.retry_delay(attempt);
                     warn!("Request failed: {}, retrying in {:?}", e, delay);
                     sleep(delay).await;
                 }
@@ -203,8 +764,16 @@ impl HttpClient {
         }
     }
 
-    /// Perform a range request for partial content
-    pub async fn get_range(&self, url: &str, start: u64, end: Option<u64>) -> Result<Response> {
+    /// Perform a range request for partial content. With `if_range` (an
+    /// ETag or HTTP date), a server whose copy no longer matches replies 200
+    /// with the whole file instead of 206.
+    pub async fn get_range(
+        &self,
+        url: &str,
+        start: u64,
+        end: Option<u64>,
+        if_range: Option<&str>,
+    ) -> Result<Response> {
         self.wait_for_rate_limit(url).await?;
 
         let range_header = match end {
@@ -214,48 +783,243 @@ impl HttpClient {
 
         let mut headers = HeaderMap::new();
         headers.insert(RANGE, HeaderValue::from_str(&range_header).unwrap());
+        if let Some(value) = if_range.and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_RANGE, value);
+        }
 
         self.get_with_headers(url, Some(headers)).await
     }
 
     /// Get content length without downloading
     pub async fn get_content_length(&self, url: &str) -> Result<Option<u64>> {
-        self.wait_for_rate_limit(url).await?;
+        Ok(self.probe(url).await?.content_length)
+    }
 
-        let response = self.client.head(url).send().await?;
+    /// Get the `Content-Type` reported by a HEAD request, if any
+    pub async fn get_content_type(&self, url: &str) -> Result<Option<String>> {
+        Ok(self.probe(url).await?.content_type)
+    }
+
+    /// Check if server supports range requests
+    pub async fn supports_range_requests(&self, url: &str) -> Result<bool> {
+        Ok(self.probe(url).await?.accepts_ranges)
+    }
+
+    /// Learn what a download needs to know about a URL from a single HEAD
+    /// request. An unsuccessful response yields an empty `Probe`.
+    pub async fn probe(&self, url: &str) -> Result<Probe> {
+        let response = self.head(url).await?;
 
         if !response.status().is_success() {
-            return Ok(None);
+            return Ok(Probe::default());
         }
 
-        let length = response
-            .headers()
-            .get("content-length")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|s| s.parse::<u64>().ok());
-
-        Ok(length)
+        let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+        Ok(Probe {
+            content_length: header("content-length").and_then(|s| s.parse::<u64>().ok()),
+            accepts_ranges: header("accept-ranges").is_some_and(|s| s != "none"),
+            content_type: header("content-type").map(|s| s.to_string()),
+            etag: header("etag").map(|s| s.to_string()),
+        })
     }
 
-    /// Check if server supports range requests
-    pub async fn supports_range_requests(&self, url: &str) -> Result<bool> {
+    /// Send a HEAD request, subject to robots.txt, rate limiting and the
+    /// circuit breaker but without retries
+    async fn head(&self, url: &str) -> Result<Response> {
+        self.check_robots(url).await?;
+        let domain = Self::domain_of(url)?;
+        let _permit = self.acquire_domain_permit(&domain).await?;
         self.wait_for_rate_limit(url).await?;
 
-        let response = self.client.head(url).send().await?;
+        self.check_circuit(&domain)?;
+        let mut request = self.next_route(url).client.head(url);
+        if let Some(user_agent) = self.next_user_agent() {
+            request = request.header(USER_AGENT, user_agent);
+        }
+        let result = request.send().await;
+        match &result {
+            Ok(response) => self.record_outcome(&domain, response.status().is_server_error()),
+            Err(e) if e.is_connect() || e.is_timeout() => self.record_outcome(&domain, true),
+            Err(_) => {}
+        }
+        Ok(result?)
+    }
+
+    /// Read a page body as text, failing once it exceeds `max_page_bytes`
+    /// rather than buffering it whole
+    pub async fn read_page(&self, response: Response) -> Result<String> {
+        let body = read_body_limited(response, self.config.max_page_bytes).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// The configuration this client was built with
+    pub fn config(&self) -> &ScraperConfig {
+        &self.config
+    }
+
+    /// Get the underlying reqwest client (the first one when using a proxy pool)
+    pub fn inner(&self) -> &Client {
+        &self.routes[0].client
+    }
+}
+
+/// A builder for a copy of `response` with a new body, keeping its status,
+/// version, headers and URL
+pub(crate) fn response_builder(response: &Response) -> http::response::Builder {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(response.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    builder
+}
 
-        let accept_ranges = response
+/// `response` holding `permit` until its body is read to the end or
+/// dropped, so a streamed body counts against `max_requests_per_domain`
+/// for as long as it is being transferred
+fn hold_permit(response: Response, permit: Option<OwnedSemaphorePermit>) -> Response {
+    let Some(permit) = permit else {
+        return response;
+    };
+    let builder = response_builder(&response);
+    let body = response.bytes_stream().map(move |chunk| {
+        let _held = &permit;
+        chunk
+    });
+    // Status and headers came from a real response, so this cannot fail
+    let response = builder
+        .body(reqwest::Body::wrap_stream(body))
+        .expect("response parts are valid");
+    Response::from(response)
+}
+
+/// The body length a response declared. Unlike `Response::content_length`
+/// this survives rebuilding the response around a new body stream, and it is
+/// absent when reqwest decompresses the body.
+pub fn body_length(response: &Response) -> Option<u64> {
+    response.content_length().or_else(|| {
+        response
             .headers()
-            .get("accept-ranges")
+            .get(CONTENT_LENGTH)
             .and_then(|v| v.to_str().ok())
-            .map(|s| s != "none")
-            .unwrap_or(false);
+            .and_then(|v| v.parse().ok())
+    })
+}
 
-        Ok(accept_ranges)
+/// Read a response body, failing as soon as it grows past `limit` bytes
+/// (0 = unlimited). A `Content-Length` over the limit fails before reading.
+pub async fn read_body_limited(mut response: Response, limit: u64) -> Result<Bytes> {
+    if limit == 0 {
+        return Ok(response.bytes().await?);
     }
 
-    /// Get the underlying reqwest client
-    pub fn inner(&self) -> &Client {
-        &self.client
+    let too_large = |url: &Url| {
+        ScraperError::InvalidFormat(format!(
+            "{} is larger than the {} byte page limit",
+            url, limit
+        ))
+    };
+    if body_length(&response).is_some_and(|len| len > limit) {
+        return Err(too_large(response.url()));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() as u64 + chunk.len() as u64 > limit {
+            return Err(too_large(response.url()));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(body))
+}
+
+/// What a HEAD request reported about a URL
+#[pyclass]
+#[derive(Debug, Clone, Default)]
+pub struct Probe {
+    #[pyo3(get)]
+    pub content_length: Option<u64>,
+    /// Whether the server advertises byte range support
+    #[pyo3(get)]
+    pub accepts_ranges: bool,
+    #[pyo3(get)]
+    pub content_type: Option<String>,
+    #[pyo3(get)]
+    pub etag: Option<String>,
+}
+
+#[pymethods]
+impl Probe {
+    fn __repr__(&self) -> String {
+        format!(
+            "Probe(content_length={:?}, accepts_ranges={}, content_type={:?})",
+            self.content_length, self.accepts_ranges, self.content_type
+        )
+    }
+}
+
+/// HTTP response exposed to Python with status and headers
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct PyResponse {
+    #[pyo3(get)]
+    pub url: String,
+    #[pyo3(get)]
+    pub status: u16,
+    #[pyo3(get)]
+    pub headers: HashMap<String, String>,
+    #[pyo3(get)]
+    pub from_cache: bool,
+    body: Bytes,
+}
+
+impl PyResponse {
+    /// Read a response fully into a Python-facing response object
+    pub async fn from_response(response: Response) -> Result<Self> {
+        let url = response.url().to_string();
+        let status = response.status().as_u16();
+        let from_cache = cache::is_from_cache(&response);
+
+        let mut headers: HashMap<String, String> = HashMap::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+            headers
+                .entry(name.as_str().to_string())
+                .and_modify(|existing| {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                })
+                .or_insert(value);
+        }
+
+        let body = response.bytes().await?;
+
+        Ok(Self {
+            url,
+            status,
+            headers,
+            from_cache,
+            body,
+        })
+    }
+}
+
+#[pymethods]
+impl PyResponse {
+    fn __repr__(&self) -> String {
+        format!("Response(url={}, status={})", self.url, self.status)
+    }
+
+    /// Decode the response body as text
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+
+    /// Get the raw response body
+    pub fn bytes(&self) -> Vec<u8> {
+        self.body.to_vec()
     }
 }
 
@@ -272,9 +1036,7 @@ impl PyHttpClient {
     #[pyo3(signature = (config=None))]
     pub fn new(config: Option<&ScraperConfig>) -> PyResult<Self> {
         let config = config.cloned().unwrap_or_default();
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+        let runtime = crate::runtime::shared_runtime(config.worker_threads)?;
 
         let client = HttpClient::new(&config).map_err(|e| {
             pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create client: {}", e))
@@ -282,7 +1044,7 @@ impl PyHttpClient {
 
         Ok(Self {
             inner: Arc::new(client),
-            runtime: Arc::new(runtime),
+            runtime,
         })
     }
 
@@ -292,13 +1054,9 @@ impl PyHttpClient {
         let url = url.to_string();
 
         self.runtime.block_on(async move {
-            let response = client.get(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })?;
+            let response = client.get(&url).await.map_err(PyErr::from)?;
 
-            response.text().await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            response.text().await.map_err(|e| ScraperError::from(e).into())
         })
     }
 
@@ -308,13 +1066,47 @@ impl PyHttpClient {
         let url = url.to_string();
 
         self.runtime.block_on(async move {
-            let response = client.get(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })?;
+            let response = client.get(&url).await.map_err(PyErr::from)?;
 
-            response.bytes().await.map(|b| b.to_vec()).map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            response.bytes().await.map(|b| b.to_vec()).map_err(|e| ScraperError::from(e).into())
+        })
+    }
+
+    /// Fetch URL and return the full response including status and headers
+    pub fn get_response(&self, url: &str) -> PyResult<PyResponse> {
+        let client = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move {
+            let response = client.get(&url).await.map_err(PyErr::from)?;
+
+            PyResponse::from_response(response).await.map_err(PyErr::from)
+        })
+    }
+
+    /// POST a JSON body to a URL and return the response body as string
+    pub fn post_json(&self, url: &str, body_str: &str) -> PyResult<String> {
+        let client = self.inner.clone();
+        let url = url.to_string();
+        let body = Bytes::from(body_str.to_string());
+
+        self.runtime.block_on(async move {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+            let response = client.post(&url, body, Some(headers)).await.map_err(PyErr::from)?;
+
+            response.text().await.map_err(|e| ScraperError::from(e).into())
+        })
+    }
+
+    /// Remove a URL from the response cache
+    pub fn invalidate_cache(&self, url: &str) -> PyResult<()> {
+        let client = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move {
+            client.invalidate_cache(&url).await.map_err(PyErr::from)
         })
     }
 
@@ -324,9 +1116,7 @@ impl PyHttpClient {
         let url = url.to_string();
 
         self.runtime.block_on(async move {
-            client.get_content_length(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            client.get_content_length(&url).await.map_err(PyErr::from)
         })
     }
 
@@ -336,10 +1126,27 @@ impl PyHttpClient {
         let url = url.to_string();
 
         self.runtime.block_on(async move {
-            client.supports_range_requests(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            client.supports_range_requests(&url).await.map_err(PyErr::from)
         })
     }
+
+    /// Length, range support, type and ETag of a URL from one HEAD request
+    pub fn probe(&self, url: &str) -> PyResult<Probe> {
+        let client = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move { client.probe(&url).await.map_err(PyErr::from) })
+    }
+
+    /// `(domain, requests_per_second)` for every domain requested so far
+    pub fn rate_limiter_status(&self) -> Vec<(String, f64)> {
+        self.inner.rate_limiter_status()
+    }
+
+    /// The host a URL's requests are rate limited under
+    #[staticmethod]
+    pub fn domain_of(url: &str) -> PyResult<String> {
+        HttpClient::domain_of(url).map_err(PyErr::from)
+    }
 }
 
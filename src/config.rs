@@ -1,18 +1,25 @@
 //! Configuration types for the video scraper system
 
+use crate::checksum::ChecksumAlgorithm;
+use crate::error::{Result, ScraperError};
+use crate::template;
 use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
 
 /// Global scraper configuration
 #[pyclass]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScraperConfig {
     /// Maximum concurrent downloads
     #[pyo3(get, set)]
     pub max_concurrent_downloads: usize,
 
-    /// Maximum concurrent requests per domain
+    /// Maximum concurrent requests per domain (0 = unlimited)
     #[pyo3(get, set)]
     pub max_requests_per_domain: usize,
 
@@ -20,14 +27,46 @@ pub struct ScraperConfig {
     #[pyo3(get, set)]
     pub request_timeout_secs: u64,
 
+    /// TCP connect timeout in seconds
+    #[pyo3(get, set)]
+    pub connect_timeout_secs: u64,
+
+    /// Redirects followed per request (0 = return the 3xx response as is)
+    #[pyo3(get, set)]
+    pub max_redirects: usize,
+
+    /// Abort a transfer if no bytes arrive for this many seconds (0 = disabled)
+    #[pyo3(get, set)]
+    pub read_idle_timeout_secs: u64,
+
     /// Download chunk size in bytes (default: 8MB)
     #[pyo3(get, set)]
     pub chunk_size_bytes: usize,
 
+    /// Concurrent range connections used for a single file
+    #[pyo3(get, set)]
+    pub download_connections: usize,
+
     /// Enable resume for interrupted downloads
     #[pyo3(get, set)]
     pub enable_resume: bool,
 
+    /// Save resume state every this many chunks (0 = only on the time interval)
+    #[pyo3(get, set)]
+    pub state_save_interval_chunks: u32,
+
+    /// Also save resume state once this many seconds pass since the last save (0 = disabled)
+    #[pyo3(get, set)]
+    pub state_save_interval_secs: u64,
+
+    /// Reserve the full size of a download on disk before writing, when known
+    #[pyo3(get, set)]
+    pub preallocate: bool,
+
+    /// Free space a download of known size must leave on disk, or it fails up front
+    #[pyo3(get, set)]
+    pub disk_space_margin_bytes: u64,
+
     /// Maximum retry attempts
     #[pyo3(get, set)]
     pub max_retries: u32,
@@ -36,11 +75,75 @@ pub struct ScraperConfig {
     #[pyo3(get, set)]
     pub retry_delay_ms: u64,
 
+    /// Randomize retry delays (full jitter) to avoid synchronized retries
+    #[pyo3(get, set)]
+    pub retry_jitter: bool,
+
+    /// Consecutive failed requests to a host that open its circuit breaker (0 = disabled)
+    #[pyo3(get, set)]
+    pub circuit_breaker_threshold: u32,
+
+    /// Failures further apart than this many seconds don't count as consecutive
+    #[pyo3(get, set)]
+    pub circuit_breaker_window_secs: u64,
+
+    /// Seconds an open circuit fails requests fast before letting a probe through
+    #[pyo3(get, set)]
+    pub circuit_breaker_cooldown_secs: u64,
+
+    /// Times the pipeline retries a job stage after a transient error
+    #[pyo3(get, set)]
+    pub max_job_retries: u32,
+
+    /// Fail a pipeline job that runs longer than this many seconds, keeping its
+    /// partial download for resume (0 disables)
+    #[pyo3(get, set)]
+    pub job_timeout_secs: u64,
+
+    /// Follow links from pages without videos this many levels deep (0 disables crawling)
+    #[pyo3(get, set)]
+    pub max_crawl_depth: u32,
+
+    /// Only crawl links on the same host as the page they were found on
+    #[pyo3(get, set)]
+    pub crawl_same_host_only: bool,
+
+    /// JSON Lines file remembering finished downloads across runs. Jobs whose
+    /// page or video URL is in it are skipped.
+    #[pyo3(get, set)]
+    pub dedup_store_path: Option<String>,
+
+    /// Largest page body the extractor will read, in bytes (0 = unlimited)
+    #[pyo3(get, set)]
+    pub max_page_bytes: u64,
+
+    /// Longest `extract_from_url` may spend fetching and parsing a page,
+    /// apart from the download timeouts (0 = no limit)
+    #[pyo3(get, set)]
+    pub extraction_timeout_secs: u64,
+
+    /// Most sitemap files `extract_from_sitemap` fetches, counting those nested in an index
+    #[pyo3(get, set)]
+    pub max_sitemaps: usize,
+
     /// User agent string
     #[pyo3(get, set)]
     pub user_agent: String,
 
-    /// Respect robots.txt
+    /// User agents rotated round-robin across requests (empty = always `user_agent`)
+    #[pyo3(get, set)]
+    pub user_agents: Vec<String>,
+
+    /// `Accept-Language` sent with every request, e.g. `"de-DE,de;q=0.9"`
+    #[pyo3(get, set)]
+    pub accept_language: Option<String>,
+
+    /// Headers sent with every request, overriding the defaults of the same name
+    #[pyo3(get, set)]
+    pub extra_headers: HashMap<String, String>,
+
+    /// Refuse URLs robots.txt disallows for `user_agent`, or for any of
+    /// `user_agents` when rotating, and honor its Crawl-delay
     #[pyo3(get, set)]
     pub respect_robots_txt: bool,
 
@@ -56,27 +159,90 @@ pub struct ScraperConfig {
     #[pyo3(get, set)]
     pub cache_dir: String,
 
+    /// How long cached responses are served without revalidation, in seconds
+    #[pyo3(get, set)]
+    pub cache_ttl_secs: u64,
+
+    /// Size the response cache is trimmed to, oldest entries first (0 = unbounded)
+    #[pyo3(get, set)]
+    pub cache_max_bytes: u64,
+
     /// Verify downloaded content checksums
     #[pyo3(get, set)]
     pub verify_checksums: bool,
 
+    /// Checksum reported as `DownloadResult.checksum`
+    #[pyo3(get, set)]
+    pub checksum_algorithm: ChecksumAlgorithm,
+
+    /// More checksums to compute in the same pass, into `DownloadResult.checksums`
+    #[pyo3(get, set)]
+    pub extra_checksum_algorithms: Vec<ChecksumAlgorithm>,
+
     /// Maximum file size to download (0 = unlimited)
     #[pyo3(get, set)]
     pub max_file_size_bytes: u64,
 
+    /// Aggregate download bandwidth cap across all downloads (0 = unlimited)
+    #[pyo3(get, set)]
+    pub max_bandwidth_bytes_per_sec: u64,
+
+    /// ffmpeg binary that merges separate video and audio streams, looked up
+    /// on PATH unless given as a path (`ffmpeg` feature)
+    #[pyo3(get, set)]
+    pub ffmpeg_path: String,
+
+    /// Minimum time between chunks of one download, counting every
+    /// `chunk_size_bytes` written as a chunk (0 = no pause)
+    #[pyo3(get, set)]
+    pub min_chunk_interval_ms: u64,
+
     /// Minimum file size to download (filter small files)
     #[pyo3(get, set)]
     pub min_file_size_bytes: u64,
 
+    /// Issue a HEAD per extracted video to fill in its file size
+    #[pyo3(get, set)]
+    pub probe_sizes: bool,
+
     /// Allowed video formats
     #[pyo3(get, set)]
     pub allowed_formats: Vec<String>,
 
+    /// Query parameters ignored when deduplicating video URLs (`*` suffix matches a prefix)
+    #[pyo3(get, set)]
+    pub strip_query_params: Vec<String>,
+
+    /// Case-insensitive page text marking an anti-bot interstitial, reported as AccessDenied
+    #[pyo3(get, set)]
+    pub blocked_page_patterns: Vec<String>,
+
+    /// CSS selectors marking an anti-bot interstitial, reported as AccessDenied
+    #[pyo3(get, set)]
+    pub blocked_page_selectors: Vec<String>,
+
+    /// Case-insensitive page text marking a removed or missing video, reported as NotFound
+    #[pyo3(get, set)]
+    pub removed_page_patterns: Vec<String>,
+
     /// Proxy URL (optional)
     #[pyo3(get, set)]
     pub proxy_url: Option<String>,
 
-    /// Number of worker threads (0 = auto)
+    /// Pool of proxy URLs rotated round-robin per domain
+    #[pyo3(get, set)]
+    pub proxy_urls: Vec<String>,
+
+    /// Skip TLS certificate verification, e.g. for self-signed internal hosts (insecure)
+    #[pyo3(get, set)]
+    pub danger_accept_invalid_certs: bool,
+
+    /// PEM bundle of extra CA certificates to trust, e.g. for an internal CA
+    #[pyo3(get, set)]
+    pub ca_cert_path: Option<String>,
+
+    /// Worker threads for the shared runtime (0 = one per core). Only the
+    /// config of the first object created in the process takes effect.
     #[pyo3(get, set)]
     pub worker_threads: usize,
 
@@ -99,20 +265,50 @@ impl Default for ScraperConfig {
             max_concurrent_downloads: 32,
             max_requests_per_domain: 8,
             request_timeout_secs: 300,
+            connect_timeout_secs: 30,
+            max_redirects: 10,
+            read_idle_timeout_secs: 60,
             chunk_size_bytes: 8 * 1024 * 1024, // 8MB chunks
+            download_connections: 1,
             enable_resume: true,
+            state_save_interval_chunks: 10,
+            state_save_interval_secs: 0,
+            preallocate: true,
+            disk_space_margin_bytes: 64 * 1024 * 1024,
             max_retries: 5,
             retry_delay_ms: 1000,
-            user_agent: format!(
-                "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
-            ),
+            retry_jitter: true,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_window_secs: 60,
+            circuit_breaker_cooldown_secs: 30,
+            max_job_retries: 2,
+            job_timeout_secs: 0,
+            max_crawl_depth: 0,
+            crawl_same_host_only: true,
+            dedup_store_path: None,
+            max_page_bytes: 32 * 1024 * 1024,
+            extraction_timeout_secs: 60,
+            max_sitemaps: 100,
+            user_agent: "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
+                .to_string(),
+            user_agents: Vec::new(),
+            accept_language: None,
+            extra_headers: HashMap::new(),
             respect_robots_txt: true,
             rate_limit_per_second: 2.0,
             enable_caching: true,
             cache_dir: ".cache/videoscraper".to_string(),
+            cache_ttl_secs: 3600,
+            cache_max_bytes: 512 * 1024 * 1024,
             verify_checksums: true,
+            checksum_algorithm: ChecksumAlgorithm::Sha256,
+            extra_checksum_algorithms: Vec::new(),
             max_file_size_bytes: 0, // Unlimited
+            max_bandwidth_bytes_per_sec: 0,
+            ffmpeg_path: "ffmpeg".to_string(),
+            min_chunk_interval_ms: 0,
             min_file_size_bytes: 0,
+            probe_sizes: false,
             allowed_formats: vec![
                 "mp4".to_string(),
                 "webm".to_string(),
@@ -120,7 +316,14 @@ impl Default for ScraperConfig {
                 "m3u8".to_string(),
                 "ts".to_string(),
             ],
+            strip_query_params: default_strip_query_params(),
+            blocked_page_patterns: default_blocked_page_patterns(),
+            blocked_page_selectors: default_blocked_page_selectors(),
+            removed_page_patterns: default_removed_page_patterns(),
             proxy_url: None,
+            proxy_urls: Vec::new(),
+            danger_accept_invalid_certs: false,
+            ca_cert_path: None,
             worker_threads: 0, // Auto-detect
             enable_compression: true,
             pool_size_per_host: 16,
@@ -144,20 +347,50 @@ impl ScraperConfig {
             max_concurrent_downloads: 128,
             max_requests_per_domain: 16,
             request_timeout_secs: 600,
+            connect_timeout_secs: 10,
+            max_redirects: 10,
+            read_idle_timeout_secs: 30,
             chunk_size_bytes: 16 * 1024 * 1024, // 16MB chunks
+            download_connections: 1,
             enable_resume: true,
+            state_save_interval_chunks: 10,
+            state_save_interval_secs: 0,
+            preallocate: true,
+            disk_space_margin_bytes: 64 * 1024 * 1024,
             max_retries: 10,
             retry_delay_ms: 500,
-            user_agent: format!(
-                "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
-            ),
+            retry_jitter: true,
+            circuit_breaker_threshold: 10,
+            circuit_breaker_window_secs: 60,
+            circuit_breaker_cooldown_secs: 15,
+            max_job_retries: 2,
+            job_timeout_secs: 0,
+            max_crawl_depth: 0,
+            crawl_same_host_only: true,
+            dedup_store_path: None,
+            max_page_bytes: 32 * 1024 * 1024,
+            extraction_timeout_secs: 60,
+            max_sitemaps: 500,
+            user_agent: "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
+                .to_string(),
+            user_agents: Vec::new(),
+            accept_language: None,
+            extra_headers: HashMap::new(),
             respect_robots_txt: false,
             rate_limit_per_second: 50.0,
             enable_caching: true,
             cache_dir: ".cache/videoscraper".to_string(),
+            cache_ttl_secs: 3600,
+            cache_max_bytes: 2 * 1024 * 1024 * 1024,
             verify_checksums: true,
+            checksum_algorithm: ChecksumAlgorithm::Sha256,
+            extra_checksum_algorithms: Vec::new(),
             max_file_size_bytes: 0,
+            max_bandwidth_bytes_per_sec: 0,
+            ffmpeg_path: "ffmpeg".to_string(),
+            min_chunk_interval_ms: 0,
             min_file_size_bytes: 0,
+            probe_sizes: false,
             allowed_formats: vec![
                 "mp4".to_string(),
                 "webm".to_string(),
@@ -165,7 +398,14 @@ impl ScraperConfig {
                 "m3u8".to_string(),
                 "ts".to_string(),
             ],
+            strip_query_params: default_strip_query_params(),
+            blocked_page_patterns: default_blocked_page_patterns(),
+            blocked_page_selectors: default_blocked_page_selectors(),
+            removed_page_patterns: default_removed_page_patterns(),
             proxy_url: None,
+            proxy_urls: Vec::new(),
+            danger_accept_invalid_certs: false,
+            ca_cert_path: None,
             worker_threads: 0,
             enable_compression: true,
             pool_size_per_host: 32,
@@ -180,26 +420,63 @@ impl ScraperConfig {
             max_concurrent_downloads: 4,
             max_requests_per_domain: 2,
             request_timeout_secs: 120,
+            connect_timeout_secs: 60,
+            max_redirects: 10,
+            read_idle_timeout_secs: 120,
             chunk_size_bytes: 4 * 1024 * 1024, // 4MB chunks
+            download_connections: 1,
             enable_resume: true,
+            state_save_interval_chunks: 10,
+            state_save_interval_secs: 0,
+            preallocate: true,
+            disk_space_margin_bytes: 64 * 1024 * 1024,
             max_retries: 3,
             retry_delay_ms: 2000,
-            user_agent: format!(
-                "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
-            ),
+            retry_jitter: true,
+            circuit_breaker_threshold: 3,
+            circuit_breaker_window_secs: 120,
+            circuit_breaker_cooldown_secs: 60,
+            max_job_retries: 3,
+            job_timeout_secs: 0,
+            max_crawl_depth: 0,
+            crawl_same_host_only: true,
+            dedup_store_path: None,
+            max_page_bytes: 32 * 1024 * 1024,
+            extraction_timeout_secs: 120,
+            max_sitemaps: 20,
+            user_agent: "VideoScraper/0.1.0 (Rust/Python; +https://github.com/videoscraper)"
+                .to_string(),
+            user_agents: Vec::new(),
+            accept_language: None,
+            extra_headers: HashMap::new(),
             respect_robots_txt: true,
             rate_limit_per_second: 0.5,
             enable_caching: true,
             cache_dir: ".cache/videoscraper".to_string(),
+            cache_ttl_secs: 3600,
+            cache_max_bytes: 256 * 1024 * 1024,
             verify_checksums: true,
+            checksum_algorithm: ChecksumAlgorithm::Sha256,
+            extra_checksum_algorithms: Vec::new(),
             max_file_size_bytes: 0,
+            max_bandwidth_bytes_per_sec: 0,
+            ffmpeg_path: "ffmpeg".to_string(),
+            min_chunk_interval_ms: 0,
             min_file_size_bytes: 0,
+            probe_sizes: false,
             allowed_formats: vec![
                 "mp4".to_string(),
                 "webm".to_string(),
                 "mkv".to_string(),
             ],
+            strip_query_params: default_strip_query_params(),
+            blocked_page_patterns: default_blocked_page_patterns(),
+            blocked_page_selectors: default_blocked_page_selectors(),
+            removed_page_patterns: default_removed_page_patterns(),
             proxy_url: None,
+            proxy_urls: Vec::new(),
+            danger_accept_invalid_certs: false,
+            ca_cert_path: None,
             worker_threads: 0,
             enable_compression: true,
             pool_size_per_host: 8,
@@ -221,16 +498,375 @@ impl ScraperConfig {
             pyo3::exceptions::PyValueError::new_err(format!("Deserialization failed: {}", e))
         })
     }
+
+    /// Replace `user_agents` with the lines of a file, returning how many
+    /// were loaded
+    #[pyo3(name = "load_user_agents")]
+    fn py_load_user_agents(&mut self, path: &str) -> PyResult<usize> {
+        self.load_user_agents(path).map_err(PyErr::from)
+    }
+
+    /// Load from `VIDEOSCRAPER_*` environment variables over the defaults
+    #[staticmethod]
+    #[pyo3(name = "from_env")]
+    fn py_from_env() -> PyResult<Self> {
+        Self::from_env().map_err(PyErr::from)
+    }
+
+    /// Raise `ConfigError` listing every problem with this configuration
+    #[pyo3(name = "validate")]
+    fn py_validate(&self) -> PyResult<()> {
+        self.validate().map_err(|problems| invalid_config(problems).into())
+    }
+
+    /// Load from a TOML file, rejecting unknown keys
+    #[cfg(feature = "toml")]
+    #[staticmethod]
+    #[pyo3(name = "from_toml_file")]
+    fn py_from_toml_file(path: &str) -> PyResult<Self> {
+        Self::from_toml_file(path).map_err(PyErr::from)
+    }
+
+    /// Load from a YAML file, rejecting unknown keys
+    #[cfg(feature = "yaml")]
+    #[staticmethod]
+    #[pyo3(name = "from_yaml_file")]
+    fn py_from_yaml_file(path: &str) -> PyResult<Self> {
+        Self::from_yaml_file(path).map_err(PyErr::from)
+    }
+}
+
+impl ScraperConfig {
+    /// Overlay `VIDEOSCRAPER_*` environment variables onto the defaults.
+    ///
+    /// Every field can be set as `VIDEOSCRAPER_` plus its upper-cased name,
+    /// e.g. `VIDEOSCRAPER_MAX_CONCURRENT_DOWNLOADS=64`, and
+    /// `VIDEOSCRAPER_RATE_LIMIT` is short for `VIDEOSCRAPER_RATE_LIMIT_PER_SECOND`.
+    /// Lists are comma-separated and booleans accept true/false, 1/0 or yes/no.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|var| std::env::var(var).ok())
+    }
+
+    /// `from_env` reading variables through `lookup` instead of the process
+    /// environment
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        overlay_env(
+            &Self::default(),
+            "VIDEOSCRAPER_",
+            &[("RATE_LIMIT", "rate_limit_per_second")],
+            lookup,
+        )
+    }
+
+    /// Load from a TOML file. Missing keys keep their defaults, and keys
+    /// that aren't options are an error so typos don't go unnoticed.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self> {
+        load_config_file(path.as_ref(), parse_toml, unknown_keys::<Self>)
+    }
+
+    /// Load from a YAML file, with the same rules as `from_toml_file`
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self> {
+        load_config_file(path.as_ref(), parse_yaml, unknown_keys::<Self>)
+    }
+
+    /// Replace `user_agents` with one user agent per line of a file,
+    /// skipping blank lines and `#` comments. Returns how many were loaded.
+    pub fn load_user_agents(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let user_agents: Vec<String> = std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+
+        if user_agents.is_empty() {
+            return Err(ScraperError::ConfigError(format!(
+                "{}: no user agents found",
+                path.display()
+            )));
+        }
+        self.user_agents = user_agents;
+        Ok(self.user_agents.len())
+    }
+
+    /// Check for settings that would only fail once work is under way,
+    /// returning every problem found
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let mut require = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        require(self.max_concurrent_downloads > 0, "max_concurrent_downloads must be at least 1");
+        require(self.request_timeout_secs > 0, "request_timeout_secs must be positive");
+        require(self.connect_timeout_secs > 0, "connect_timeout_secs must be positive");
+        require(self.chunk_size_bytes > 0, "chunk_size_bytes must be positive");
+        require(
+            self.circuit_breaker_threshold == 0 || self.circuit_breaker_cooldown_secs > 0,
+            "circuit_breaker_cooldown_secs must be positive when the breaker is enabled",
+        );
+        require(self.download_connections > 0, "download_connections must be at least 1");
+        require(
+            self.rate_limit_per_second.is_finite() && self.rate_limit_per_second > 0.0,
+            "rate_limit_per_second must be a positive number",
+        );
+        require(
+            self.max_file_size_bytes == 0 || self.max_file_size_bytes >= self.min_file_size_bytes,
+            "max_file_size_bytes is below min_file_size_bytes",
+        );
+        require(
+            reqwest::header::HeaderValue::from_str(&self.user_agent).is_ok(),
+            "user_agent is not a valid header value",
+        );
+        for user_agent in &self.user_agents {
+            require(
+                reqwest::header::HeaderValue::from_str(user_agent).is_ok(),
+                &format!("user agent {:?} is not a valid header value", user_agent),
+            );
+        }
+        if let Some(ref accept_language) = self.accept_language {
+            require(
+                reqwest::header::HeaderValue::from_str(accept_language).is_ok(),
+                "accept_language is not a valid header value",
+            );
+        }
+        for (name, value) in &self.extra_headers {
+            require(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_ok(),
+                &format!("extra header name {:?} is not valid", name),
+            );
+            require(
+                reqwest::header::HeaderValue::from_str(value).is_ok(),
+                &format!("extra header {:?} has an invalid value", name),
+            );
+        }
+        require(
+            !self.enable_caching || !self.cache_dir.is_empty(),
+            "cache_dir is required when enable_caching is set",
+        );
+        if let Some(ref ca_cert_path) = self.ca_cert_path {
+            require(
+                Path::new(ca_cert_path).is_file(),
+                &format!("ca_cert_path {:?} is not a file", ca_cert_path),
+            );
+        }
+        for proxy in self.proxy_url.iter().chain(&self.proxy_urls) {
+            require(
+                reqwest::Proxy::all(proxy.as_str()).is_ok(),
+                &format!("proxy {:?} is not a valid URL", proxy),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/// Read and parse a config file, then deserialize it once `unknown` finds no
+/// keys that would otherwise be silently ignored
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn load_config_file<T: DeserializeOwned>(
+    path: &Path,
+    parse: fn(&str) -> std::result::Result<Value, String>,
+    unknown: fn(&Value, &str) -> Vec<String>,
+) -> Result<T> {
+    let invalid = |problem: String| {
+        ScraperError::ConfigError(format!("{}: {}", path.display(), problem))
+    };
+
+    let value = parse(&std::fs::read_to_string(path)?).map_err(invalid)?;
+    let unknown = unknown(&value, "");
+    if !unknown.is_empty() {
+        return Err(invalid(format!("unknown keys: {}", unknown.join(", "))));
+    }
+    serde_json::from_value(value).map_err(|e| invalid(e.to_string()))
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(text: &str) -> std::result::Result<Value, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(text: &str) -> std::result::Result<Value, String> {
+    serde_yaml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Keys of a table that aren't fields of `T`, prefixed with `path`. Values
+/// of the wrong shape are left for deserialization to report.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn unknown_keys<T: Serialize + Default>(value: &Value, path: &str) -> Vec<String> {
+    let (Value::Object(fields), Ok(Value::Object(known))) =
+        (value, serde_json::to_value(T::default()))
+    else {
+        return Vec::new();
+    };
+    fields
+        .keys()
+        .filter(|key| !known.contains_key(*key))
+        .map(|key| format!("{}{}", path, key))
+        .collect()
+}
+
+/// Unknown keys of a storage table, including those of fanout `backends`
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn storage_unknown_keys(value: &Value, path: &str) -> Vec<String> {
+    let mut unknown = unknown_keys::<StorageConfig>(value, path);
+    if let Some(Value::Array(backends)) = value.get("backends") {
+        for (i, backend) in backends.iter().enumerate() {
+            unknown.extend(storage_unknown_keys(
+                backend,
+                &format!("{}backends[{}].", path, i),
+            ));
+        }
+    }
+    unknown
+}
+
+/// A `ConfigError` listing the problems `validate` found
+pub fn invalid_config(problems: Vec<String>) -> ScraperError {
+    ScraperError::ConfigError(problems.join("; "))
+}
+
+/// Set the fields of `defaults` from `{prefix}{FIELD}` variables read through
+/// `lookup`, checking each variable on its own so errors name the one at fault.
+/// `aliases` maps extra variable suffixes to field names; the full field name
+/// wins when both are set.
+fn overlay_env<T: Serialize + DeserializeOwned>(
+    defaults: &T,
+    prefix: &str,
+    aliases: &[(&str, &str)],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<T> {
+    let Ok(Value::Object(fields)) = serde_json::to_value(defaults) else {
+        return Err(ScraperError::ConfigError("configuration is not a struct".to_string()));
+    };
+    let invalid = ScraperError::ConfigError;
+
+    let mut vars: Vec<(String, &str)> = aliases
+        .iter()
+        .map(|(alias, field)| (format!("{}{}", prefix, alias), *field))
+        .collect();
+    vars.extend(
+        fields
+            .keys()
+            .map(|field| (format!("{}{}", prefix, field.to_uppercase()), field.as_str())),
+    );
+
+    let mut merged = fields.clone();
+    for (var, field) in vars {
+        let Some(raw) = lookup(&var) else {
+            continue;
+        };
+        let value = parse_env_value(&fields[field], raw.trim())
+            .map_err(|expected| invalid(format!("{} must be {}, got {:?}", var, expected, raw)))?;
+
+        // Type-check this one variable against the struct
+        let mut single = fields.clone();
+        single.insert(field.to_string(), value.clone());
+        serde_json::from_value::<T>(Value::Object(single))
+            .map_err(|e| invalid(format!("{} is invalid: {}", var, e)))?;
+        merged.insert(field.to_string(), value);
+    }
+
+    serde_json::from_value(Value::Object(merged)).map_err(|e| invalid(e.to_string()))
+}
+
+/// Parse an environment value into the JSON shape of the field's default,
+/// or describe what was expected
+fn parse_env_value(default: &Value, raw: &str) -> std::result::Result<Value, &'static str> {
+    match default {
+        Value::Bool(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err("a boolean"),
+        },
+        Value::Number(n) if n.is_f64() => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or("a number"),
+        Value::Number(_) => raw
+            .parse::<u64>()
+            .map(|n| Value::Number(n.into()))
+            .map_err(|_| "a non-negative integer"),
+        Value::Array(_) => Ok(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+        Value::Object(_) => serde_json::from_str(raw).map_err(|_| "a JSON object"),
+        // Unset optional strings
+        Value::Null if raw.is_empty() => Ok(Value::Null),
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Tracking and player parameters that don't change which video a URL serves
+fn default_strip_query_params() -> Vec<String> {
+    ["utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "_ga", "autoplay"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+/// Text from common bot-check pages (Cloudflare, Imperva, DataDome, PerimeterX)
+fn default_blocked_page_patterns() -> Vec<String> {
+    strings(&[
+        "checking your browser",
+        "cf-browser-verification",
+        "challenge-platform",
+        "attention required! | cloudflare",
+        "please verify you are a human",
+        "_incapsula_resource",
+        "captcha-delivery.com",
+        "px-captcha",
+    ])
+}
+
+fn default_blocked_page_selectors() -> Vec<String> {
+    strings(&["#challenge-form", "#cf-wrapper", ".g-recaptcha", ".h-captcha"])
+}
+
+/// Text from "soft 404" pages served with a 200 status
+fn default_removed_page_patterns() -> Vec<String> {
+    strings(&[
+        "video unavailable",
+        "this video has been removed",
+        "this video is no longer available",
+        "this video is private",
+        "video not found",
+    ])
 }
 
 /// Storage backend configuration
 #[pyclass]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StorageConfig {
-    /// Storage backend type: "local", "s3", "gcs"
+    /// Storage backend type: "local", "s3", "gcs", "azure", or "fanout"
     #[pyo3(get, set)]
     pub backend: String,
 
+    /// Backends written to together when `backend` is "fanout"
+    #[pyo3(get, set)]
+    pub backends: Vec<StorageConfig>,
+
     /// Local storage path (for local backend)
     #[pyo3(get, set)]
     pub local_path: String,
@@ -255,10 +891,39 @@ pub struct StorageConfig {
     #[pyo3(get, set)]
     pub gcs_project: Option<String>,
 
+    /// Azure storage account name
+    #[pyo3(get, set)]
+    pub azure_account: Option<String>,
+
+    /// Azure blob container name
+    #[pyo3(get, set)]
+    pub azure_container: Option<String>,
+
+    /// Azure storage account access key
+    #[pyo3(get, set)]
+    pub azure_access_key: Option<String>,
+
+    /// Azure SAS token, used instead of an access key
+    #[pyo3(get, set)]
+    pub azure_sas_token: Option<String>,
+
     /// Key prefix for cloud storage
     #[pyo3(get, set)]
     pub key_prefix: String,
 
+    /// File name for each download, relative to `local_path` and `key_prefix`.
+    /// Placeholders: `{id}`, `{title}`, `{quality}`, `{host}`, `{ext}`,
+    /// `{format}`, `{width}`, `{height}`, `{year}`, `{month}`, `{day}` and
+    /// `{tag:NAME}` for a tag given to `add_url`.
+    #[pyo3(get, set)]
+    pub output_template: String,
+
+    /// Full storage key for each download, used in place of `key_prefix` and
+    /// `output_template` when set, e.g. `"{host}/{year}/{month}/{id}.{ext}"`.
+    /// Takes the same placeholders as `output_template`; jobs can override it.
+    #[pyo3(get, set)]
+    pub key_template: String,
+
     /// Enable multipart uploads for large files
     #[pyo3(get, set)]
     pub enable_multipart: bool,
@@ -270,22 +935,74 @@ pub struct StorageConfig {
     /// Part size for multipart uploads
     #[pyo3(get, set)]
     pub multipart_part_size_bytes: u64,
+
+    /// Times a cloud storage request is retried after a transient failure
+    /// such as throttling or a 5xx, with exponential backoff (0 to disable)
+    #[pyo3(get, set)]
+    pub storage_max_retries: u32,
+
+    /// Remove the local download once the pipeline has uploaded it (non-local backends)
+    #[pyo3(get, set)]
+    pub delete_local_after_upload: bool,
+
+    /// Download to `local_path` before uploading. When false, non-local
+    /// backends receive the bytes as they arrive (HLS is still staged).
+    #[pyo3(get, set)]
+    pub use_local_staging: bool,
+}
+
+/// Placeholders `StorageConfig::output_template` and `key_template` can use,
+/// besides `{tag:NAME}`
+pub const OUTPUT_TEMPLATE_FIELDS: &[&str] = &[
+    "id", "title", "quality", "host", "ext", "format", "width", "height", "year", "month", "day",
+];
+
+/// Problems with an output or key template, naming it `option` in messages
+pub fn template_problems(option: &str, template_str: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let unknown: Vec<&str> = template::placeholders(template_str)
+        .into_iter()
+        .filter(|name| !OUTPUT_TEMPLATE_FIELDS.contains(name))
+        .filter(|name| name.strip_prefix("tag:").is_none_or(str::is_empty))
+        .collect();
+    if !unknown.is_empty() {
+        problems.push(format!(
+            "{} has unknown placeholders: {{{}}}",
+            option,
+            unknown.join("}, {")
+        ));
+    }
+    let escapes = template_str.starts_with('/') || template_str.split('/').any(|part| part == "..");
+    if template_str.is_empty() || escapes {
+        problems.push(format!("{} must be a relative file path", option));
+    }
+    problems
 }
 
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
             backend: "local".to_string(),
+            backends: Vec::new(),
             local_path: "./downloads".to_string(),
             s3_bucket: None,
             s3_region: Some("us-east-1".to_string()),
             s3_endpoint: None,
             gcs_bucket: None,
             gcs_project: None,
+            azure_account: None,
+            azure_container: None,
+            azure_access_key: None,
+            azure_sas_token: None,
             key_prefix: "videos/".to_string(),
+            output_template: "{id}.{ext}".to_string(),
+            key_template: String::new(),
             enable_multipart: true,
             multipart_threshold_bytes: 100 * 1024 * 1024, // 100MB
             multipart_part_size_bytes: 64 * 1024 * 1024,  // 64MB parts
+            storage_max_retries: 3,
+            delete_local_after_upload: false,
+            use_local_staging: true,
         }
     }
 }
@@ -327,6 +1044,45 @@ impl StorageConfig {
         }
     }
 
+    /// Write to every given backend and read from the first that has a key
+    #[staticmethod]
+    pub fn fanout(backends: Vec<StorageConfig>) -> Self {
+        Self {
+            backend: "fanout".to_string(),
+            backends,
+            ..Default::default()
+        }
+    }
+
+    /// Load from `VIDEOSCRAPER_STORAGE_*` environment variables over the defaults
+    #[staticmethod]
+    #[pyo3(name = "from_env")]
+    fn py_from_env() -> PyResult<Self> {
+        Self::from_env().map_err(PyErr::from)
+    }
+
+    /// Raise `ConfigError` listing every problem with this configuration
+    #[pyo3(name = "validate")]
+    fn py_validate(&self) -> PyResult<()> {
+        self.validate().map_err(|problems| invalid_config(problems).into())
+    }
+
+    /// Load from a TOML file, rejecting unknown keys
+    #[cfg(feature = "toml")]
+    #[staticmethod]
+    #[pyo3(name = "from_toml_file")]
+    fn py_from_toml_file(path: &str) -> PyResult<Self> {
+        Self::from_toml_file(path).map_err(PyErr::from)
+    }
+
+    /// Load from a YAML file, rejecting unknown keys
+    #[cfg(feature = "yaml")]
+    #[staticmethod]
+    #[pyo3(name = "from_yaml_file")]
+    fn py_from_yaml_file(path: &str) -> PyResult<Self> {
+        Self::from_yaml_file(path).map_err(PyErr::from)
+    }
+
     /// Create GCS storage configuration
     #[staticmethod]
     #[pyo3(signature = (bucket, project=None, key_prefix=None))]
@@ -339,5 +1095,135 @@ impl StorageConfig {
             ..Default::default()
         }
     }
+
+    /// Create Azure Blob Storage configuration, authenticated with an
+    /// account access key or a SAS token
+    #[staticmethod]
+    #[pyo3(signature = (account, container, access_key=None, sas_token=None, key_prefix=None))]
+    pub fn azure(
+        account: &str,
+        container: &str,
+        access_key: Option<&str>,
+        sas_token: Option<&str>,
+        key_prefix: Option<&str>,
+    ) -> Self {
+        Self {
+            backend: "azure".to_string(),
+            azure_account: Some(account.to_string()),
+            azure_container: Some(container.to_string()),
+            azure_access_key: access_key.map(|s| s.to_string()),
+            azure_sas_token: sas_token.map(|s| s.to_string()),
+            key_prefix: key_prefix.unwrap_or("videos/").to_string(),
+            ..Default::default()
+        }
+    }
 }
 
+impl StorageConfig {
+    /// Overlay `VIDEOSCRAPER_STORAGE_*` environment variables onto the
+    /// defaults, e.g. `VIDEOSCRAPER_STORAGE_BACKEND=s3` and
+    /// `VIDEOSCRAPER_STORAGE_S3_BUCKET=videos`. Fanout `backends` can't be set
+    /// this way.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|var| std::env::var(var).ok())
+    }
+
+    /// `from_env` reading variables through `lookup` instead of the process
+    /// environment
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        overlay_env(&Self::default(), "VIDEOSCRAPER_STORAGE_", &[], lookup)
+    }
+
+    /// Load from a TOML file. Missing keys keep their defaults, and keys
+    /// that aren't options are an error so typos don't go unnoticed.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self> {
+        load_config_file(path.as_ref(), parse_toml, storage_unknown_keys)
+    }
+
+    /// Load from a YAML file, with the same rules as `from_toml_file`
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self> {
+        load_config_file(path.as_ref(), parse_yaml, storage_unknown_keys)
+    }
+
+    /// Check that the chosen backend has what it needs, returning every
+    /// problem found (including those of fanout children)
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let missing = |value: &Option<String>| value.as_deref().is_none_or(str::is_empty);
+
+        match self.backend.as_str() {
+            "local" if self.local_path.is_empty() => {
+                problems.push("local_path is required for the local backend".to_string())
+            }
+            "s3" if missing(&self.s3_bucket) => {
+                problems.push("s3_bucket is required for the s3 backend".to_string())
+            }
+            "gcs" => {
+                if missing(&self.gcs_bucket) {
+                    problems.push("gcs_bucket is required for the gcs backend".to_string());
+                }
+                if missing(&self.gcs_project) {
+                    problems.push("gcs_project is required for the gcs backend".to_string());
+                }
+            }
+            "azure" => {
+                if missing(&self.azure_account) {
+                    problems.push("azure_account is required for the azure backend".to_string());
+                }
+                if missing(&self.azure_container) {
+                    problems.push("azure_container is required for the azure backend".to_string());
+                }
+                match (missing(&self.azure_access_key), missing(&self.azure_sas_token)) {
+                    (true, true) => problems.push(
+                        "azure_access_key or azure_sas_token is required for the azure backend"
+                            .to_string(),
+                    ),
+                    (false, false) => problems.push(
+                        "set only one of azure_access_key and azure_sas_token".to_string(),
+                    ),
+                    _ => {}
+                }
+            }
+            "fanout" if self.backends.is_empty() => {
+                problems.push("the fanout backend needs at least one backend".to_string())
+            }
+            "local" | "s3" | "fanout" => {}
+            other => problems.push(format!(
+                "unknown backend {:?} (expected local, s3, gcs, azure or fanout)",
+                other
+            )),
+        }
+
+        if self.enable_multipart && self.backend != "local" {
+            if self.multipart_part_size_bytes < 5 * 1024 * 1024 {
+                problems.push("multipart_part_size_bytes must be at least 5MB".to_string());
+            }
+            if self.multipart_threshold_bytes == 0 {
+                problems.push("multipart_threshold_bytes must be positive".to_string());
+            }
+        }
+
+        problems.extend(template_problems("output_template", &self.output_template));
+        if !self.key_template.is_empty() {
+            problems.extend(template_problems("key_template", &self.key_template));
+        }
+
+        for (i, child) in self.backends.iter().enumerate() {
+            if let Err(child_problems) = child.validate() {
+                problems.extend(
+                    child_problems
+                        .into_iter()
+                        .map(|p| format!("backends[{}]: {}", i, p)),
+                );
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
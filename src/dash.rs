@@ -0,0 +1,308 @@
+//! DASH (mpd) manifest parsing
+
+use crate::error::{Result, ScraperError};
+use roxmltree::{Document, Node};
+use url::Url;
+
+/// A single `Representation` from an MPD manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Representation {
+    pub id: String,
+    pub bandwidth: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub codecs: Option<String>,
+    pub mime_type: Option<String>,
+    /// Media URL for `BaseURL` addressing, or the resolved base for templates
+    pub url: String,
+    /// Initialization segment from `SegmentTemplate@initialization`
+    pub initialization_url: Option<String>,
+    /// Media segments from `SegmentTemplate`, empty for single-file representations
+    pub segment_urls: Vec<String>,
+}
+
+impl Representation {
+    pub fn is_video(&self) -> bool {
+        self.content_type() == Some("video")
+    }
+
+    pub fn is_audio(&self) -> bool {
+        self.content_type() == Some("audio")
+    }
+
+    /// Whether the media is split into segments rather than a single file
+    pub fn is_segmented(&self) -> bool {
+        !self.segment_urls.is_empty()
+    }
+
+    fn content_type(&self) -> Option<&str> {
+        if let Some(mime) = &self.mime_type {
+            return mime.split('/').next();
+        }
+        // Without a MIME type, a resolution is the best hint of video
+        if self.width.is_some() || self.height.is_some() {
+            Some("video")
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the body looks like a DASH manifest
+pub fn is_mpd(text: &str) -> bool {
+    let head: String = text.chars().take(1024).collect();
+    head.contains("<MPD")
+}
+
+/// Parse every representation in an MPD, resolving URLs against `base_url`
+pub fn parse_mpd(text: &str, base_url: &str) -> Result<Vec<Representation>> {
+    let doc = Document::parse(text)
+        .map_err(|e| ScraperError::InvalidFormat(format!("Invalid MPD: {}", e)))?;
+    let mpd = doc.root_element();
+    if mpd.tag_name().name() != "MPD" {
+        return Err(ScraperError::InvalidFormat(
+            "Missing MPD root element".to_string(),
+        ));
+    }
+
+    let mpd_base = resolve_base(&Url::parse(base_url)?, mpd)?;
+    let total_duration = mpd
+        .attribute("mediaPresentationDuration")
+        .and_then(parse_duration);
+
+    let mut representations = Vec::new();
+
+    for period in children(mpd, "Period") {
+        let period_base = resolve_base(&mpd_base, period)?;
+        let period_duration = period
+            .attribute("duration")
+            .and_then(parse_duration)
+            .or(total_duration);
+
+        for set in children(period, "AdaptationSet") {
+            let set_base = resolve_base(&period_base, set)?;
+
+            for rep in children(set, "Representation") {
+                let rep_base = resolve_base(&set_base, rep)?;
+                // Attributes on the AdaptationSet apply to every Representation in it
+                let inherited = |name: &str| rep.attribute(name).or_else(|| set.attribute(name));
+
+                let id = rep.attribute("id").unwrap_or_default().to_string();
+                let bandwidth = rep.attribute("bandwidth").and_then(|b| b.parse().ok());
+                let mut representation = Representation {
+                    id,
+                    bandwidth,
+                    width: inherited("width").and_then(|w| w.parse().ok()),
+                    height: inherited("height").and_then(|h| h.parse().ok()),
+                    frame_rate: inherited("frameRate").and_then(parse_frame_rate),
+                    codecs: inherited("codecs").map(|c| c.to_string()),
+                    mime_type: inherited("mimeType")
+                        .map(|m| m.to_string())
+                        .or_else(|| set.attribute("contentType").map(|t| format!("{}/*", t))),
+                    url: rep_base.to_string(),
+                    initialization_url: None,
+                    segment_urls: Vec::new(),
+                };
+
+                let template = [rep, set, period]
+                    .into_iter()
+                    .find_map(|node| children(node, "SegmentTemplate").next());
+                if let Some(template) = template {
+                    apply_template(&mut representation, template, &rep_base, period_duration)?;
+                }
+
+                representations.push(representation);
+            }
+        }
+    }
+
+    Ok(representations)
+}
+
+/// Expand `SegmentTemplate` addressing into concrete segment URLs
+fn apply_template(
+    representation: &mut Representation,
+    template: Node,
+    base: &Url,
+    period_duration: Option<f64>,
+) -> Result<()> {
+    let timescale: u64 = template
+        .attribute("timescale")
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(1);
+    let start_number: u64 = template
+        .attribute("startNumber")
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1);
+
+    if let Some(init) = template.attribute("initialization") {
+        let path = substitute(init, representation, None, None);
+        representation.initialization_url = Some(base.join(&path)?.to_string());
+    }
+
+    let Some(media) = template.attribute("media") else {
+        return Ok(());
+    };
+
+    // (number, time) for each segment
+    let mut segments: Vec<(u64, u64)> = Vec::new();
+
+    let duration: Option<u64> = template.attribute("duration").and_then(|d| d.parse().ok());
+
+    if let Some(timeline) = children(template, "SegmentTimeline").next() {
+        let mut time = 0u64;
+        let mut number = start_number;
+        let period_end = period_duration.map(|d| (d * timescale as f64) as u64);
+
+        for s in children(timeline, "S") {
+            let d: u64 = s.attribute("d").and_then(|d| d.parse().ok()).unwrap_or(0);
+            if d == 0 {
+                continue;
+            }
+            if let Some(t) = s.attribute("t").and_then(|t| t.parse().ok()) {
+                time = t;
+            }
+            let repeat: i64 = s.attribute("r").and_then(|r| r.parse().ok()).unwrap_or(0);
+            // A negative repeat runs to the end of the period
+            let count = match (repeat, period_end) {
+                (r, _) if r >= 0 => r as u64 + 1,
+                (_, Some(end)) if end > time => (end - time).div_ceil(d),
+                _ => 1,
+            };
+
+            for _ in 0..count {
+                segments.push((number, time));
+                number += 1;
+                time += d;
+            }
+        }
+    } else if let Some(duration) = duration {
+        let Some(period_duration) = period_duration.filter(|_| duration > 0) else {
+            return Err(ScraperError::InvalidFormat(
+                "SegmentTemplate@duration needs a known period duration".to_string(),
+            ));
+        };
+        let count = (period_duration * timescale as f64 / duration as f64).ceil() as u64;
+        segments.extend((0..count).map(|i| (start_number + i, i * duration)));
+    }
+
+    representation.segment_urls = segments
+        .into_iter()
+        .map(|(number, time)| {
+            let path = substitute(media, representation, Some(number), Some(time));
+            base.join(&path).map(|u| u.to_string())
+        })
+        .collect::<std::result::Result<_, _>>()?;
+
+    Ok(())
+}
+
+/// Replace `$RepresentationID$`, `$Bandwidth$`, `$Number$`, and `$Time$`
+/// identifiers, honouring `%0Nd` width specifiers
+fn substitute(
+    template: &str,
+    representation: &Representation,
+    number: Option<u64>,
+    time: Option<u64>,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut parts = template.split('$');
+
+    // Text alternates with identifiers; `$$` is an escaped dollar sign
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    while let Some(ident) = parts.next() {
+        let (name, width) = match ident.split_once('%') {
+            Some((name, spec)) => (
+                name,
+                spec.trim_start_matches('0')
+                    .trim_end_matches('d')
+                    .parse::<usize>()
+                    .unwrap_or(0),
+            ),
+            None => (ident, 0),
+        };
+        let value = match name {
+            "" => Some("$".to_string()),
+            "RepresentationID" => Some(representation.id.clone()),
+            "Bandwidth" => representation.bandwidth.map(|b| format!("{:0width$}", b)),
+            "Number" => number.map(|n| format!("{:0width$}", n)),
+            "Time" => time.map(|t| format!("{:0width$}", t)),
+            _ => None,
+        };
+        let text = parts.next();
+        match value {
+            Some(value) if text.is_some() => out.push_str(&value),
+            // Unknown or unterminated identifiers are kept verbatim
+            _ => {
+                out.push('$');
+                out.push_str(ident);
+                if text.is_some() {
+                    out.push('$');
+                }
+            }
+        }
+        if let Some(text) = text {
+            out.push_str(text);
+        }
+    }
+
+    out
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |c| c.is_element() && c.tag_name().name() == name)
+}
+
+/// Resolve a node's `BaseURL` child against its parent's base
+fn resolve_base(parent: &Url, node: Node) -> Result<Url> {
+    match children(node, "BaseURL").next().and_then(|b| b.text()) {
+        Some(base) => Ok(parent.join(base.trim())?),
+        None => Ok(parent.clone()),
+    }
+}
+
+/// Parse an ISO 8601 duration such as `PT1H2M3.5S` into seconds
+pub(crate) fn parse_duration(value: &str) -> Option<f64> {
+    let rest = value.strip_prefix('P')?;
+    let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+
+    let mut secs = 0.0;
+    for (part, units) in [
+        (date, &[('D', 86_400.0)][..]),
+        (time, &[('H', 3_600.0), ('M', 60.0), ('S', 1.0)][..]),
+    ] {
+        let mut number = String::new();
+        for c in part.chars() {
+            match units.iter().find(|(unit, _)| *unit == c) {
+                Some((_, scale)) => {
+                    secs += number.parse::<f64>().ok()? * scale;
+                    number.clear();
+                }
+                None => number.push(c),
+            }
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+
+    Some(secs)
+}
+
+/// Parse `frameRate`, which may be a fraction like `30000/1001`
+fn parse_frame_rate(value: &str) -> Option<f64> {
+    match value.split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            (den != 0.0).then_some(num.parse::<f64>().ok()? / den)
+        }
+        None => value.parse().ok(),
+    }
+}
@@ -0,0 +1,199 @@
+//! Record of finished downloads that outlives the pipeline, so a re-run
+//! only fetches what is new
+
+use crate::error::Result;
+use crate::extractor::normalize_url;
+use chrono::{DateTime, Utc};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// A download remembered by the dedup store
+#[pyclass]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupEntry {
+    #[pyo3(get)]
+    pub source_url: String,
+    #[pyo3(get)]
+    pub video_url: String,
+    /// SHA-256 of the downloaded file
+    #[pyo3(get)]
+    pub sha256: String,
+    #[pyo3(get)]
+    pub storage_key: Option<String>,
+    #[pyo3(get)]
+    pub completed_at: String,
+}
+
+#[pymethods]
+impl DedupEntry {
+    fn __repr__(&self) -> String {
+        format!(
+            "DedupEntry(source_url={}, storage_key={:?})",
+            self.source_url, self.storage_key
+        )
+    }
+}
+
+/// Entries in file order, looked up by normalized URL and content hash
+#[derive(Default)]
+struct Index {
+    entries: Vec<DedupEntry>,
+    /// Normalized source URL to the latest entry for it
+    by_source: HashMap<String, usize>,
+    /// Normalized source and video URLs
+    by_url: HashMap<String, usize>,
+    by_hash: HashMap<String, usize>,
+}
+
+impl Index {
+    fn insert(&mut self, entry: DedupEntry, strip_params: &[String]) {
+        let i = self.entries.len();
+        let source = normalize_url(&entry.source_url, strip_params);
+        self.by_source.insert(source.clone(), i);
+        self.by_url.insert(source, i);
+        self.by_url.insert(normalize_url(&entry.video_url, strip_params), i);
+        self.by_hash.insert(entry.sha256.clone(), i);
+        self.entries.push(entry);
+    }
+
+    /// Entries not superseded by a later one for the same source URL
+    fn live(&self) -> impl Iterator<Item = &DedupEntry> {
+        let mut live: Vec<usize> = self.by_source.values().copied().collect();
+        live.sort_unstable();
+        live.into_iter().map(|i| &self.entries[i])
+    }
+}
+
+/// Downloads finished by this or earlier runs, kept in a JSON Lines file.
+/// Each finished download appends a line; later lines win when loading.
+pub struct DedupStore {
+    path: PathBuf,
+    strip_params: Vec<String>,
+    index: Mutex<Index>,
+}
+
+impl DedupStore {
+    /// Load the store at `path`, starting empty if the file doesn't exist yet.
+    /// URLs are compared after `normalize_url` with `strip_params`.
+    pub fn open(path: &Path, strip_params: Vec<String>) -> Result<Self> {
+        let mut index = Index::default();
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                for (n, line) in text.lines().enumerate() {
+                    match serde_json::from_str(line) {
+                        Ok(entry) => index.insert(entry, &strip_params),
+                        // A crash mid-append can leave the last line cut short
+                        Err(e) if !line.trim().is_empty() => {
+                            warn!("Skipping line {} of {:?}: {}", n + 1, path, e)
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        info!("Loaded {} finished downloads from {:?}", index.by_source.len(), path);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            strip_params,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// The entry for a page or video URL downloaded before
+    pub async fn find_url(&self, url: &str) -> Option<DedupEntry> {
+        let index = self.index.lock().await;
+        let key = normalize_url(url, &self.strip_params);
+        index.by_url.get(&key).map(|&i| index.entries[i].clone())
+    }
+
+    /// The entry for a download with content hash `sha256`
+    pub async fn find_content(&self, sha256: &str) -> Option<DedupEntry> {
+        let index = self.index.lock().await;
+        index.by_hash.get(sha256).map(|&i| index.entries[i].clone())
+    }
+
+    /// Remember a finished download
+    pub async fn record(&self, entry: DedupEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        let mut index = self.index.lock().await;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+
+        index.insert(entry, &self.strip_params);
+        Ok(())
+    }
+
+    /// Every remembered download, oldest first
+    pub async fn entries(&self) -> Vec<DedupEntry> {
+        let index = self.index.lock().await;
+        index.live().cloned().collect()
+    }
+
+    /// Forget every download, so the next run fetches everything again
+    pub async fn clear(&self) -> Result<()> {
+        let mut index = self.index.lock().await;
+        match tokio::fs::remove_file(&self.path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        *index = Index::default();
+        Ok(())
+    }
+
+    /// Forget downloads finished more than `max_age` ago, so their URLs are
+    /// fetched again. Returns how many were forgotten.
+    pub async fn prune(&self, max_age: Duration) -> Result<usize> {
+        let cutoff = chrono::Duration::from_std(max_age)
+            .ok()
+            .and_then(|max_age| Utc::now().checked_sub_signed(max_age))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let mut index = self.index.lock().await;
+
+        let mut kept = Index::default();
+        let mut removed = 0;
+        for entry in index.live() {
+            let finished = DateTime::parse_from_rfc3339(&entry.completed_at).ok();
+            if finished.is_some_and(|finished| finished < cutoff) {
+                removed += 1;
+            } else {
+                kept.insert(entry.clone(), &self.strip_params);
+            }
+        }
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        // Rewrite then rename so a crash mid-write leaves the old file intact
+        let mut text = String::new();
+        for entry in &kept.entries {
+            text.push_str(&serde_json::to_string(entry)?);
+            text.push('\n');
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, text).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+
+        *index = kept;
+        Ok(removed)
+    }
+}
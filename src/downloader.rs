@@ -1,20 +1,30 @@
 //! High-performance download manager with chunked and resumable downloads
 
-use crate::client::HttpClient;
+use crate::checksum::Checksums;
+use crate::client::{body_length, HttpClient, Probe};
 use crate::config::ScraperConfig;
 use crate::error::{Result, ScraperError};
+use crate::hls;
+use crate::metrics::DownloadMetrics;
+use crate::storage::{ObjectMetadata, StorageBackend};
 use bytes::Bytes;
-use futures::stream::StreamExt;
+use dashmap::DashMap;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use pyo3::prelude::*;
-use sha2::{Digest, Sha256};
+use pyo3::types::PyBytes;
+use reqwest::header::{HeaderMap, CONTENT_TYPE, ETAG, LAST_MODIFIED};
+use reqwest::{Response, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::fs::{self, File, OpenOptions};
-use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::{mpsc, Semaphore};
-use tracing::{debug, error, info, warn};
-use uuid::Uuid;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
 
 /// Progress information for a download
 #[pyclass]
@@ -34,6 +44,9 @@ pub struct DownloadProgress {
     pub eta_secs: Option<f64>,
     #[pyo3(get)]
     pub status: String,
+    /// Pipeline job the download belongs to, set on `progress_stream` events
+    #[pyo3(get)]
+    pub job_id: Option<String>,
 }
 
 #[pymethods]
@@ -58,6 +71,15 @@ pub struct DownloadResult {
     pub size_bytes: u64,
     #[pyo3(get)]
     pub sha256_hash: String,
+    /// Hex digest in the configured `checksum_algorithm`
+    #[pyo3(get)]
+    pub checksum: String,
+    /// Name of the algorithm `checksum` was computed with, e.g. `"md5"`
+    #[pyo3(get)]
+    pub checksum_algorithm: String,
+    /// Every checksum computed for the download, keyed by algorithm name
+    #[pyo3(get)]
+    pub checksums: HashMap<String, String>,
     #[pyo3(get)]
     pub duration_secs: f64,
     #[pyo3(get)]
@@ -66,6 +88,8 @@ pub struct DownloadResult {
     pub resumed: bool,
     #[pyo3(get)]
     pub chunks_downloaded: u32,
+    #[pyo3(get)]
+    pub connections: u32,
 }
 
 #[pymethods]
@@ -78,6 +102,152 @@ impl DownloadResult {
     }
 }
 
+/// Receives periodic progress updates; returning an error aborts the download
+/// without retrying, as `ScraperError::Aborted`
+pub type ProgressCallback = Arc<dyn Fn(&DownloadProgress) -> Result<()> + Send + Sync>;
+
+/// Per-download options beyond the manager-wide configuration
+#[derive(Clone, Default)]
+pub struct DownloadOptions {
+    /// Expected SHA256 (hex), checked when `verify_checksums` is enabled
+    pub expected_sha256: Option<String>,
+    /// Called roughly every `PROGRESS_INTERVAL` while bytes arrive
+    pub progress: Option<ProgressCallback>,
+    /// Cancels the download when triggered; `DownloadManager::cancel` also
+    /// fires it
+    pub cancel: Option<CancellationToken>,
+    /// What a HEAD request the caller already made reported about the URL,
+    /// saving the download its own
+    pub probe: Option<Probe>,
+}
+
+/// Upper bound on the buffer reserved up front from a `Content-Length`
+const MAX_PREALLOCATED_BUFFER: u64 = 64 * 1024 * 1024;
+
+/// Minimum time between progress callbacks
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a segmented download saves its resume state when
+/// `state_save_interval_secs` is 0
+const SEGMENT_STATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Weight of the newest sample in the smoothed speed estimate
+const SPEED_EMA_ALPHA: f64 = 0.3;
+
+/// Rate-limited progress reporting with a smoothed transfer speed.
+/// Shared by parallel segments, so all state sits behind one lock.
+struct ProgressReporter<'a> {
+    callback: Option<&'a ProgressCallback>,
+    url: &'a str,
+    total_bytes: Option<u64>,
+    state: Mutex<ProgressState>,
+}
+
+struct ProgressState {
+    downloaded: u64,
+    last_emit: Instant,
+    last_emit_bytes: u64,
+    speed: f64,
+}
+
+impl<'a> ProgressReporter<'a> {
+    fn new(
+        callback: Option<&'a ProgressCallback>,
+        url: &'a str,
+        total_bytes: Option<u64>,
+        downloaded: u64,
+    ) -> Self {
+        Self {
+            callback,
+            url,
+            total_bytes,
+            state: Mutex::new(ProgressState {
+                downloaded,
+                last_emit: Instant::now(),
+                last_emit_bytes: downloaded,
+                speed: 0.0,
+            }),
+        }
+    }
+
+    /// Record `bytes` received, invoking the callback if the interval has passed
+    fn advance(&self, bytes: u64) -> Result<()> {
+        let Some(callback) = self.callback else {
+            return Ok(());
+        };
+
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            state.downloaded += bytes;
+
+            let elapsed = state.last_emit.elapsed();
+            if elapsed < PROGRESS_INTERVAL {
+                return Ok(());
+            }
+
+            let sample = (state.downloaded - state.last_emit_bytes) as f64 / elapsed.as_secs_f64();
+            state.speed = if state.speed == 0.0 {
+                sample
+            } else {
+                SPEED_EMA_ALPHA * sample + (1.0 - SPEED_EMA_ALPHA) * state.speed
+            };
+            state.last_emit = Instant::now();
+            state.last_emit_bytes = state.downloaded;
+
+            self.snapshot(&state, "downloading")
+        };
+
+        Self::report(callback, &progress)
+    }
+
+    /// Report the final state once all bytes are on disk
+    fn finish(&self) -> Result<()> {
+        let Some(callback) = self.callback else {
+            return Ok(());
+        };
+        let progress = self.snapshot(&self.state.lock().unwrap(), "completed");
+        Self::report(callback, &progress)
+    }
+
+    /// Invoke the callback, turning its error into one that isn't retried
+    fn report(callback: &ProgressCallback, progress: &DownloadProgress) -> Result<()> {
+        callback(progress).map_err(|e| match e {
+            ScraperError::Aborted(_) => e,
+            e => ScraperError::Aborted(format!("progress callback failed: {}", e)),
+        })
+    }
+
+    fn snapshot(&self, state: &ProgressState, status: &str) -> DownloadProgress {
+        let total = self.total_bytes.or((status == "completed").then_some(state.downloaded));
+        let percentage = match total {
+            Some(0) => 100.0,
+            Some(total) => (state.downloaded as f64 / total as f64 * 100.0).min(100.0),
+            None => 0.0,
+        };
+        let eta_secs = total
+            .filter(|_| state.speed > 0.0 || status == "completed")
+            .map(|total| {
+                let remaining = total.saturating_sub(state.downloaded) as f64;
+                if remaining == 0.0 {
+                    0.0
+                } else {
+                    remaining / state.speed
+                }
+            });
+
+        DownloadProgress {
+            url: self.url.to_string(),
+            downloaded_bytes: state.downloaded,
+            total_bytes: total,
+            percentage,
+            speed_bytes_per_sec: state.speed,
+            eta_secs,
+            status: status.to_string(),
+            job_id: None,
+        }
+    }
+}
+
 /// Metadata for resumable downloads
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct DownloadState {
@@ -88,8 +258,143 @@ struct DownloadState {
     chunk_size: usize,
     partial_hash: String,
     chunks_completed: Vec<(u64, u64)>,
+    /// `chunks_completed` holds the ranges written by parallel segments
+    /// rather than one leading range
+    #[serde(default)]
+    segmented: bool,
     started_at: chrono::DateTime<chrono::Utc>,
     last_updated: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    validators: ResumeValidators,
+}
+
+/// `ETag` and `Last-Modified` of the file being downloaded, so a resume can
+/// check the server still has the same version
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct ResumeValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl ResumeValidators {
+    fn from_response(response: &Response) -> Self {
+        let header = |headers: &HeaderMap, name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string())
+        };
+        Self {
+            etag: header(response.headers(), ETAG),
+            last_modified: header(response.headers(), LAST_MODIFIED),
+        }
+    }
+
+    /// The `If-Range` value: a strong ETag, or else `Last-Modified`, since
+    /// weak ETags can't be used for ranges
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+/// Spaces the chunks of one transfer at least `min_chunk_interval_ms` apart,
+/// counting every `chunk_size_bytes` written as a chunk, so ranged and
+/// streamed downloads pace alike
+struct ChunkPacer {
+    interval: Duration,
+    chunk_size: u64,
+    written: u64,
+    last_chunk: Instant,
+}
+
+impl ChunkPacer {
+    fn new(config: &ScraperConfig) -> Self {
+        Self {
+            interval: Duration::from_millis(config.min_chunk_interval_ms),
+            chunk_size: config.chunk_size_bytes as u64,
+            written: 0,
+            last_chunk: Instant::now(),
+        }
+    }
+
+    /// Account for `bytes` just written, sleeping once a chunk is complete
+    /// until the interval since the previous one has passed
+    async fn wrote(&mut self, bytes: usize) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        self.written += bytes as u64;
+        if self.written < self.chunk_size {
+            return;
+        }
+        self.written = 0;
+
+        let elapsed = self.last_chunk.elapsed();
+        if elapsed < self.interval {
+            tokio::time::sleep(self.interval - elapsed).await;
+        }
+        self.last_chunk = Instant::now();
+    }
+}
+
+/// Token bucket shared by all downloads to cap aggregate bandwidth
+struct BandwidthThrottle {
+    bytes_per_sec: f64,
+    burst_bytes: f64,
+    state: Mutex<ThrottleState>,
+}
+
+struct ThrottleState {
+    available: f64,
+    last_refill: Instant,
+}
+
+impl BandwidthThrottle {
+    fn new(bytes_per_sec: u64) -> Self {
+        let bytes_per_sec = bytes_per_sec as f64;
+        Self {
+            bytes_per_sec,
+            // Allow ~100ms worth of burst so pacing stays smooth
+            burst_bytes: bytes_per_sec / 10.0,
+            state: Mutex::new(ThrottleState {
+                available: 0.0,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Account for `bytes` just received, sleeping off any deficit
+    async fn consume(&self, bytes: usize) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let refill = now.duration_since(state.last_refill).as_secs_f64() * self.bytes_per_sec;
+            state.last_refill = now;
+            state.available = (state.available + refill).min(self.burst_bytes);
+            state.available -= bytes as f64;
+
+            if state.available < 0.0 {
+                Duration::from_secs_f64(-state.available / self.bytes_per_sec)
+            } else {
+                Duration::ZERO
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// A download that `DownloadManager::cancel` can stop
+struct ActiveDownload {
+    url: String,
+    output_path: PathBuf,
+    token: CancellationToken,
 }
 
 /// High-performance download manager
@@ -97,7 +402,12 @@ pub struct DownloadManager {
     client: Arc<HttpClient>,
     config: ScraperConfig,
     semaphore: Arc<Semaphore>,
-    active_downloads: Arc<AtomicU64>,
+    metrics: Arc<DownloadMetrics>,
+    throttle: Option<Arc<BandwidthThrottle>>,
+    /// In-progress downloads by an id of their own, since several may share
+    /// a URL
+    cancellations: Arc<DashMap<u64, ActiveDownload>>,
+    next_download_id: Arc<AtomicU64>,
 }
 
 impl DownloadManager {
@@ -107,27 +417,86 @@ impl DownloadManager {
             client,
             config: config.clone(),
             semaphore: Arc::new(Semaphore::new(config.max_concurrent_downloads)),
-            active_downloads: Arc::new(AtomicU64::new(0)),
+            metrics: Arc::new(DownloadMetrics::default()),
+            throttle: (config.max_bandwidth_bytes_per_sec > 0)
+                .then(|| Arc::new(BandwidthThrottle::new(config.max_bandwidth_bytes_per_sec))),
+            cancellations: Arc::new(DashMap::new()),
+            next_download_id: Arc::new(AtomicU64::new(0)),
         }
     }
 
     /// Download a single file
     pub async fn download(&self, url: &str, output_path: &Path) -> Result<DownloadResult> {
-        let _permit = self.semaphore.acquire().await.map_err(|_| {
-            ScraperError::DownloadFailed {
-                attempts: 0,
-                message: "Semaphore closed".to_string(),
-            }
-        })?;
+        self.download_with_options(url, output_path, &DownloadOptions::default())
+            .await
+    }
 
-        self.active_downloads.fetch_add(1, Ordering::SeqCst);
-        let result = self.download_internal(url, output_path).await;
-        self.active_downloads.fetch_sub(1, Ordering::SeqCst);
+    /// Download a single file with per-download options
+    pub async fn download_with_options(
+        &self,
+        url: &str,
+        output_path: &Path,
+        options: &DownloadOptions,
+    ) -> Result<DownloadResult> {
+        // Register before queueing so downloads waiting on a permit can be cancelled too
+        let cancel = options.cancel.clone().unwrap_or_default();
+        let id = self.next_download_id.fetch_add(1, Ordering::Relaxed);
+        self.cancellations.insert(
+            id,
+            ActiveDownload {
+                url: url.to_string(),
+                output_path: output_path.to_path_buf(),
+                token: cancel.clone(),
+            },
+        );
+
+        let task = self.download_internal(url, output_path, options, &cancel);
+        let result = self.in_slot(task, |result| result).await;
 
+        self.cancellations.remove(&id);
         result
     }
 
-    async fn download_internal(&self, url: &str, output_path: &Path) -> Result<DownloadResult> {
+    /// Cancel the in-progress downloads of `url`, or only the one writing to
+    /// `output_path` if given, keeping their `.part` files and resume state.
+    /// Returns whether any was found.
+    pub fn cancel(&self, url: &str, output_path: Option<&Path>) -> bool {
+        let mut found = false;
+        for download in self.cancellations.iter() {
+            if download.url == url && output_path.is_none_or(|p| p == download.output_path) {
+                download.token.cancel();
+                found = true;
+            }
+        }
+        found
+    }
+
+    async fn download_internal(
+        &self,
+        url: &str,
+        output_path: &Path,
+        options: &DownloadOptions,
+        cancel: &CancellationToken,
+    ) -> Result<DownloadResult> {
+        if cancel.is_cancelled() {
+            return Err(cancelled());
+        }
+
+        if hls::is_hls_url(url) {
+            return self.download_hls(url, output_path, options, cancel).await;
+        }
+        // One HEAD request gives the type, size and range support
+        let head = match &options.probe {
+            Some(probe) => probe.clone(),
+            None => tokio::select! {
+                head = self.client.probe(url) => head?,
+                _ = cancel.cancelled() => return Err(cancelled()),
+            },
+        };
+        if hls::is_hls(url, head.content_type.as_deref()) {
+            return self.download_hls(url, output_path, options, cancel).await;
+        }
+
         let start_time = std::time::Instant::now();
         let mut resumed = false;
         let mut chunks_downloaded = 0u32;
@@ -137,131 +506,1061 @@ impl DownloadManager {
             fs::create_dir_all(parent).await?;
         }
 
+        // Bytes land in a sibling `.part` file that is renamed into place only
+        // once complete, so `output_path` never holds a truncated download
+        let part_path = self.get_part_path(output_path);
+
+        let total_bytes = head.content_length;
+        let supports_range = head.accepts_ranges;
+
+        let chunked_total =
+            total_bytes.filter(|_| supports_range && self.config.chunk_size_bytes > 0);
+        let connections = self.config.download_connections.max(1);
+        let mut connections_used = 1u32;
+
+        let segmented = chunked_total.is_some() && connections > 1;
+
         // Check for existing partial download
         let state_path = self.get_state_path(output_path);
         let mut start_byte = 0u64;
+        // Ranges already on disk from an earlier segmented download
+        let mut segments_done = Vec::new();
+        let mut validators = ResumeValidators::default();
+        let mut hasher = self.checksums();
 
         if self.config.enable_resume {
             if let Ok(state) = self.load_state(&state_path).await {
-                if state.url == url {
-                    start_byte = state.downloaded_bytes;
-                    resumed = true;
-                    info!(
-                        "Resuming download from byte {}: {}",
-                        start_byte, url
-                    );
+                let on_disk = fs::metadata(&part_path).await.map(|m| m.len()).ok();
+                let on_disk = on_disk.filter(|_| state.url == url);
+                if let Some(on_disk) = on_disk.filter(|_| state.segmented) {
+                    let written: Vec<_> = state
+                        .chunks_completed
+                        .iter()
+                        .map(|&(start, end)| (start, end.min(on_disk)))
+                        .filter(|&(start, end)| start < end)
+                        .collect();
+                    if let Some(total) = total_bytes.filter(|&t| state.total_bytes == Some(t)) {
+                        if segmented {
+                            segments_done = written;
+                        } else {
+                            // Only the leading range can be continued in order
+                            let gaps = missing_ranges(&written, total);
+                            start_byte = gaps.first().map_or(total, |&(start, _)| start);
+                            hash_file_into(&part_path, start_byte, &mut hasher).await?;
+                        }
+                        validators = state.validators;
+                        resumed = true;
+                        info!("Resuming segmented download of {}", url);
+                    }
+                } else if let Some(on_disk) = on_disk {
+                    // A crash can lose writes that the state already counted
+                    start_byte = state.downloaded_bytes.min(on_disk);
+                    if start_byte < state.downloaded_bytes {
+                        warn!(
+                            "Only {} of {} saved bytes of {} are on disk",
+                            on_disk, state.downloaded_bytes, url
+                        );
+                    }
+
+                    // Seed the hasher with the bytes already on disk so the final
+                    // hash covers the whole file, not just the resumed tail
+                    hash_file_into(&part_path, start_byte, &mut hasher).await?;
+
+                    // Lost writes inside a preallocated file don't shorten it,
+                    // but they do change the hash of the saved prefix
+                    let comparable =
+                        !state.partial_hash.is_empty() && start_byte == state.downloaded_bytes;
+                    if comparable && hasher.sha256_hex() != state.partial_hash {
+                        warn!("Partial download of {} doesn't match its saved hash", url);
+                        start_byte = 0;
+                        hasher = self.checksums();
+                    } else {
+                        validators = state.validators;
+                        resumed = true;
+                        info!(
+                            "Resuming download from byte {}: {}",
+                            start_byte, url
+                        );
+                    }
                 }
             }
         }
 
-        // Get content length
-        let total_bytes = self.client.get_content_length(url).await?;
-        let supports_range = self.client.supports_range_requests(url).await?;
+        // Skip files outside the configured size bounds, or that won't fit on
+        // disk, before transferring anything
+        if let Some(total) = total_bytes {
+            self.check_size_bounds(total)?;
+            self.check_disk_space(&part_path, total).await?;
+        }
 
         // If we can't resume or don't support range, start fresh
         if resumed && !supports_range {
             warn!("Server doesn't support range requests, starting from beginning");
             start_byte = 0;
+            segments_done.clear();
             resumed = false;
+            hasher = self.checksums();
+        }
+
+        // Appending to bytes of an older version of the file would corrupt it
+        let resumable = total_bytes.is_none_or(|total| start_byte < total);
+        if let Some(if_range) = validators.if_range().filter(|_| resumed && resumable) {
+            let probe = self
+                .client
+                .get_range(url, start_byte, Some(start_byte), Some(if_range))
+                .await?;
+            if probe.status() != StatusCode::PARTIAL_CONTENT {
+                warn!("{} changed since the partial download, starting over", url);
+                start_byte = 0;
+                segments_done.clear();
+                resumed = false;
+                validators = ResumeValidators::default();
+                hasher = self.checksums();
+            }
+        }
+
+        // Segments send `If-Range` from the start, so a file that changes
+        // mid-download fails rather than mixing two versions
+        if segmented && validators.if_range().is_none() {
+            validators.etag = head.etag.clone();
         }
 
         // Open file for writing
-        let mut file = if resumed && start_byte > 0 {
+        let mut file = if resumed && (start_byte > 0 || !segments_done.is_empty()) {
             let mut f = OpenOptions::new()
                 .write(true)
-                .open(output_path)
+                .open(&part_path)
                 .await?;
             f.seek(std::io::SeekFrom::Start(start_byte)).await?;
             f
         } else {
-            File::create(output_path).await?
+            File::create(&part_path).await?
+        };
+
+        // Reserve the whole file up front so it isn't fragmented as it grows
+        let preallocated = match total_bytes {
+            Some(total) if self.config.preallocate && total > start_byte => {
+                preallocate(&file, total).await?;
+                true
+            }
+            _ => false,
         };
 
         // Download with chunking
-        let mut hasher = Sha256::new();
-        let mut downloaded = start_byte;
+        let mut downloaded = start_byte + segments_done.iter().map(|(s, e)| e - s).sum::<u64>();
 
-        if supports_range && total_bytes.is_some() && self.config.chunk_size_bytes > 0 {
-            // Chunked download for large files
-            let total = total_bytes.unwrap();
-            let chunk_size = self.config.chunk_size_bytes as u64;
+        let progress =
+            ProgressReporter::new(options.progress.as_ref(), url, total_bytes, downloaded);
 
-            while downloaded < total {
-                let end = (downloaded + chunk_size - 1).min(total - 1);
-                
-                let response = self.client.get_range(url, downloaded, Some(end)).await?;
-                let bytes = response.bytes().await?;
-                
-                file.write_all(&bytes).await?;
-                hasher.update(&bytes);
-                
-                downloaded += bytes.len() as u64;
-                chunks_downloaded += 1;
+        // What parallel segments have written, as half-open byte ranges
+        segments_done.push((0, start_byte));
+        let written = Mutex::new(segments_done);
 
-                // Save state for resume
-                if self.config.enable_resume && chunks_downloaded % 10 == 0 {
-                    self.save_state(&state_path, &DownloadState {
-                        url: url.to_string(),
-                        output_path: output_path.to_string_lossy().to_string(),
-                        total_bytes,
-                        downloaded_bytes: downloaded,
-                        chunk_size: self.config.chunk_size_bytes,
-                        partial_hash: hex::encode(hasher.clone().finalize()),
-                        chunks_completed: vec![(start_byte, downloaded)],
-                        started_at: chrono::Utc::now(),
-                        last_updated: chrono::Utc::now(),
-                    }).await?;
-                }
+        // Size the finished file must have, when the server told us
+        let mut expected_size = None;
 
-                debug!(
-                    "Downloaded chunk {}/{}: {} bytes",
-                    chunks_downloaded,
-                    (total / chunk_size) + 1,
-                    bytes.len()
+        // Everything that touches the network runs inside this block so that a
+        // cancellation can drop it at any await point
+        let transfer = async {
+            if let Some(total) = chunked_total.filter(|_| segmented) {
+                // Parallel segmented download into a preallocated file
+                expected_size = Some(total);
+                file.set_len(total).await?;
+                let if_range = validators.if_range();
+                let fetch = self.download_segments(
+                    url, &part_path, total, connections, if_range, &written, &progress,
                 );
-            }
-        } else {
-            // Streaming download for smaller files or when range not supported
-            let response = if start_byte > 0 {
-                self.client.get_range(url, start_byte, None).await?
+                let mut fetch = std::pin::pin!(fetch);
+
+                // Segments have no chunk order to count, so they save on a timer
+                let save_after = match self.config.state_save_interval_secs {
+                    0 => SEGMENT_STATE_INTERVAL,
+                    secs => Duration::from_secs(secs),
+                };
+                let first_save = tokio::time::Instant::now() + save_after;
+                let mut saves = tokio::time::interval_at(first_save, save_after);
+                chunks_downloaded = loop {
+                    tokio::select! {
+                        fetched = &mut fetch => break fetched?,
+                        _ = saves.tick(), if self.config.enable_resume => {
+                            self.save_segment_state(
+                                &state_path, url, output_path, total, &written, &validators,
+                            )
+                            .await?;
+                        }
+                    }
+                };
+                connections_used = chunks_downloaded.min(connections as u32);
+                downloaded = total;
+
+                // Segments finish out of order, so hash the reassembled file
+                hasher = self.checksums();
+                hash_file_into(&part_path, total, &mut hasher).await?;
+            } else if let Some(total) = chunked_total {
+                // Chunked download for large files
+                let chunk_size = self.config.chunk_size_bytes as u64;
+                let mut pacer = ChunkPacer::new(&self.config);
+                expected_size = Some(total);
+                let save_every = self.config.state_save_interval_chunks;
+                let save_after = Duration::from_secs(self.config.state_save_interval_secs);
+                let mut last_save = Instant::now();
+
+                while downloaded < total {
+                    let end = (downloaded + chunk_size - 1).min(total - 1);
+
+                    let response = self
+                        .client
+                        .get_range(url, downloaded, Some(end), validators.if_range())
+                        .await?;
+                    check_same_version(&response, &mut validators, downloaded)?;
+                    let mut stream = std::pin::pin!(response.bytes_stream());
+                    let mut chunk_len = 0u64;
+
+                    while let Some(bytes) = self.next_chunk(&mut stream).await? {
+                        file.write_all(&bytes).await?;
+                        hasher.update(&bytes);
+                        chunk_len += bytes.len() as u64;
+                        downloaded += bytes.len() as u64;
+                        progress.advance(bytes.len() as u64)?;
+                        self.throttle(bytes.len()).await;
+                        pacer.wrote(bytes.len()).await;
+                    }
+
+                    chunks_downloaded += 1;
+
+                    // Save state for resume, by chunk count or elapsed time, and
+                    // once the last chunk is in
+                    let save_due = (save_every > 0 && chunks_downloaded.is_multiple_of(save_every))
+                        || (!save_after.is_zero() && last_save.elapsed() >= save_after)
+                        || downloaded >= total;
+                    if self.config.enable_resume && save_due {
+                        self.save_resume_state(
+                            &state_path, url, output_path, total_bytes, start_byte, downloaded,
+                            &hasher, &validators,
+                        )
+                        .await?;
+                        last_save = Instant::now();
+                    }
+
+                    debug!(
+                        "Downloaded chunk {}/{}: {} bytes",
+                        chunks_downloaded,
+                        (total / chunk_size) + 1,
+                        chunk_len
+                    );
+                }
             } else {
-                self.client.get(url).await?
-            };
+                // Streaming download for smaller files or when range not supported
+                let response = if start_byte > 0 {
+                    self.client
+                        .get_range(url, start_byte, None, validators.if_range())
+                        .await?
+                } else {
+                    self.client.get_with_headers(url, None).await?
+                };
+                check_same_version(&response, &mut validators, start_byte)?;
+                // Absent for chunked encoding and for bodies reqwest decompresses
+                expected_size = body_length(&response).map(|len| start_byte + len);
 
-            let mut stream = response.bytes_stream();
-            
-            while let Some(chunk) = stream.next().await {
-                let bytes = chunk?;
-                file.write_all(&bytes).await?;
-                hasher.update(&bytes);
-                downloaded += bytes.len() as u64;
+                let mut stream = std::pin::pin!(response.bytes_stream());
+                let mut pacer = ChunkPacer::new(&self.config);
+
+                let max = self.config.max_file_size_bytes;
+
+                while let Some(bytes) = self.next_chunk(&mut stream).await? {
+                    file.write_all(&bytes).await?;
+                    hasher.update(&bytes);
+                    downloaded += bytes.len() as u64;
+
+                    // Length was unknown up front, so enforce the cap as bytes arrive
+                    if max > 0 && downloaded > max {
+                        return Err(ScraperError::FileTooLarge {
+                            size_bytes: downloaded,
+                            limit_bytes: max,
+                        });
+                    }
+
+                    progress.advance(bytes.len() as u64)?;
+                    self.throttle(bytes.len()).await;
+                    pacer.wrote(bytes.len()).await;
+                }
+                chunks_downloaded = 1;
             }
-            chunks_downloaded = 1;
-        }
+
+            Ok(())
+        };
+
+        let transfer = tokio::select! {
+            result = transfer => result,
+            _ = cancel.cancelled() => Err(cancelled()),
+        };
 
         file.flush().await?;
+        // Trim the reserved space if fewer bytes arrived than advertised
+        if preallocated && transfer.is_ok() {
+            file.set_len(downloaded).await?;
+        }
         drop(file);
 
+        if let Err(e) = transfer {
+            let too_large = matches!(e, ScraperError::FileTooLarge { .. });
+            if let Some(total) = chunked_total.filter(|_| segmented && !too_large) {
+                // Only the state knows which parts of the `.part` file hold data
+                if self.config.enable_resume {
+                    self.save_segment_state(
+                        &state_path, url, output_path, total, &written, &validators,
+                    )
+                    .await?;
+                }
+            } else if cancel.is_cancelled() {
+                // Keep the `.part` file and record how far we got
+                if self.config.enable_resume {
+                    self.save_resume_state(
+                        &state_path, url, output_path, total_bytes, start_byte, downloaded,
+                        &hasher, &validators,
+                    )
+                    .await?;
+                }
+            } else if too_large {
+                let _ = fs::remove_file(&part_path).await;
+                let _ = fs::remove_file(&state_path).await;
+            }
+            if cancel.is_cancelled() {
+                info!("Cancelled download of {} at byte {}", url, downloaded);
+            }
+            return Err(e);
+        }
+
+        // A connection dropped at EOF can look like a clean end of the body
+        if let Some(expected) = expected_size.filter(|&expected| expected != downloaded) {
+            warn!(
+                "Got {} of {} bytes from {}, removing {:?}",
+                downloaded, expected, url, part_path
+            );
+            let _ = fs::remove_file(&part_path).await;
+            let _ = fs::remove_file(&state_path).await;
+            return Err(ScraperError::DownloadFailed {
+                attempts: 1,
+                message: format!("received {} of {} bytes", downloaded, expected),
+            });
+        }
+
         // Clean up state file
         if self.config.enable_resume {
             let _ = fs::remove_file(&state_path).await;
         }
 
+        let digests = hasher.finalize();
+        self.finalize_download(url, &part_path, output_path, downloaded, &digests.sha256, options)
+            .await?;
+        progress.finish()?;
         let duration = start_time.elapsed();
-        let hash = hex::encode(hasher.finalize());
 
         Ok(DownloadResult {
             url: url.to_string(),
             output_path: output_path.to_string_lossy().to_string(),
             size_bytes: downloaded,
-            sha256_hash: hash,
+            sha256_hash: digests.sha256,
+            checksum: digests.all[self.config.checksum_algorithm.name()].clone(),
+            checksum_algorithm: self.config.checksum_algorithm.name().to_string(),
+            checksums: digests.all,
             duration_secs: duration.as_secs_f64(),
             avg_speed_bytes_per_sec: downloaded as f64 / duration.as_secs_f64(),
             resumed,
             chunks_downloaded,
+            connections: connections_used,
+        })
+    }
+
+    /// Check the finished `.part` file against the size floor and expected
+    /// checksum, then move it into place. Rejected files are removed.
+    async fn finalize_download(
+        &self,
+        url: &str,
+        part_path: &Path,
+        output_path: &Path,
+        size_bytes: u64,
+        hash: &str,
+        options: &DownloadOptions,
+    ) -> Result<()> {
+        if size_bytes < self.config.min_file_size_bytes {
+            let _ = fs::remove_file(part_path).await;
+            return Err(ScraperError::FileTooSmall {
+                size_bytes,
+                min_bytes: self.config.min_file_size_bytes,
+            });
+        }
+
+        if self.config.verify_checksums {
+            if let Some(ref expected) = options.expected_sha256 {
+                if !expected.eq_ignore_ascii_case(hash) {
+                    warn!("Checksum mismatch for {}, removing {:?}", url, part_path);
+                    let _ = fs::remove_file(part_path).await;
+                    return Err(ScraperError::ChunkVerificationFailed {
+                        expected: expected.clone(),
+                        actual: hash.to_string(),
+                    });
+                }
+            }
+        }
+
+        fs::rename(part_path, output_path).await?;
+        Ok(())
+    }
+
+    /// Download a video-only and an audio-only stream side by side and merge
+    /// them into `output_path` with ffmpeg, without re-encoding. The streams
+    /// are staged next to `output_path` and removed afterwards. The result
+    /// describes the merged file.
+    #[cfg(feature = "ffmpeg")]
+    pub async fn download_and_mux(
+        &self,
+        video_url: &str,
+        audio_url: &str,
+        output_path: &Path,
+    ) -> Result<DownloadResult> {
+        let ffmpeg = &self.config.ffmpeg_path;
+        crate::mux::check_ffmpeg(ffmpeg).await?;
+        let start_time = Instant::now();
+
+        let video_path = sibling_path(output_path, "video");
+        let audio_path = sibling_path(output_path, "audio");
+        let (video, audio) = tokio::try_join!(
+            self.download(video_url, &video_path),
+            self.download(audio_url, &audio_path),
+        )?;
+
+        // Merge into a sibling so `output_path` never holds a partial file
+        let merge_path = sibling_path(output_path, "merging");
+        let merged =
+            crate::mux::mux_with_ffmpeg(ffmpeg, &video_path, &audio_path, &merge_path).await;
+        let _ = fs::remove_file(&video_path).await;
+        let _ = fs::remove_file(&audio_path).await;
+        if let Err(e) = merged {
+            let _ = fs::remove_file(&merge_path).await;
+            return Err(e);
+        }
+
+        let size_bytes = fs::metadata(&merge_path).await?.len();
+        let mut hasher = self.checksums();
+        hash_file_into(&merge_path, size_bytes, &mut hasher).await?;
+        fs::rename(&merge_path, output_path).await?;
+
+        let digests = hasher.finalize();
+        let duration = start_time.elapsed();
+        let downloaded = video.size_bytes + audio.size_bytes;
+        Ok(DownloadResult {
+            url: video_url.to_string(),
+            output_path: output_path.to_string_lossy().to_string(),
+            size_bytes,
+            sha256_hash: digests.sha256,
+            checksum: digests.all[self.config.checksum_algorithm.name()].clone(),
+            checksum_algorithm: self.config.checksum_algorithm.name().to_string(),
+            checksums: digests.all,
+            duration_secs: duration.as_secs_f64(),
+            avg_speed_bytes_per_sec: downloaded as f64 / duration.as_secs_f64(),
+            resumed: video.resumed || audio.resumed,
+            chunks_downloaded: video.chunks_downloaded + audio.chunks_downloaded,
+            connections: video.connections.max(audio.connections),
+        })
+    }
+
+    /// Stream a download straight into a storage backend at `key`, without
+    /// staging it on local disk, hashing it on the way. There is no resume:
+    /// a failed transfer starts over. HLS streams are assembled from
+    /// segments on disk, so they must go through `download` instead.
+    pub async fn download_to_storage(
+        &self,
+        url: &str,
+        key: &str,
+        backend: &dyn StorageBackend,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<(DownloadResult, ObjectMetadata)> {
+        let transfer = self.stream_to_storage(url, key, backend, progress);
+        self.in_slot(transfer, |(result, _)| result)
+            .await
+    }
+
+    /// Download `url` into memory, hashing it on the way. `max_file_size_bytes`
+    /// caps the buffer, so set it before fetching anything large. Like
+    /// `download_to_storage`, this never resumes and rejects HLS streams.
+    pub async fn download_to_bytes(&self, url: &str) -> Result<(Bytes, DownloadResult)> {
+        self.in_slot(self.read_to_bytes(url), |(_, result)| result).await
+    }
+
+    /// Run `task` once a download slot is free, tracking it in the metrics.
+    /// `download` picks the `DownloadResult` out of a successful output.
+    async fn in_slot<T>(
+        &self,
+        task: impl Future<Output = Result<T>>,
+        download: fn(&T) -> &DownloadResult,
+    ) -> Result<T> {
+        self.metrics.queued.fetch_add(1, Ordering::SeqCst);
+        let permit = self.semaphore.acquire().await;
+        self.metrics.queued.fetch_sub(1, Ordering::SeqCst);
+        let _permit = permit.map_err(|_| ScraperError::DownloadFailed {
+            attempts: 0,
+            message: "Semaphore closed".to_string(),
+        })?;
+
+        self.metrics.active.fetch_add(1, Ordering::SeqCst);
+        let result = task.await;
+        self.metrics.active.fetch_sub(1, Ordering::SeqCst);
+
+        self.metrics
+            .record(result.as_ref().ok().map(|output| download(output).size_bytes));
+        result
+    }
+
+    async fn stream_to_storage(
+        &self,
+        url: &str,
+        key: &str,
+        backend: &dyn StorageBackend,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<(DownloadResult, ObjectMetadata)> {
+        let start_time = std::time::Instant::now();
+        let response = self.start_single_stream(url).await?;
+        let expected_size = body_length(&response);
+        let progress = ProgressReporter::new(progress, url, expected_size, 0);
+
+        let mut hasher = self.checksums();
+        let mut downloaded = 0u64;
+        let body = self
+            .body_stream(response, &mut hasher, &mut downloaded)
+            .and_then(|bytes| {
+                let advanced = progress.advance(bytes.len() as u64);
+                async move { advanced.map(|_| bytes) }
+            });
+        let metadata = backend.put_stream(key, body.boxed()).await?;
+
+        let short = expected_size.is_some_and(|expected| expected != downloaded);
+        if short || downloaded < self.config.min_file_size_bytes {
+            let _ = backend.delete(key).await;
+            return Err(match expected_size.filter(|_| short) {
+                Some(expected) => ScraperError::DownloadFailed {
+                    attempts: 1,
+                    message: format!("received {} of {} bytes", downloaded, expected),
+                },
+                None => ScraperError::FileTooSmall {
+                    size_bytes: downloaded,
+                    min_bytes: self.config.min_file_size_bytes,
+                },
+            });
+        }
+
+        progress.finish()?;
+        let digests = hasher.finalize();
+        let duration = start_time.elapsed();
+        let result = DownloadResult {
+            url: url.to_string(),
+            output_path: metadata.key.clone(),
+            size_bytes: downloaded,
+            sha256_hash: digests.sha256,
+            checksum: digests.all[self.config.checksum_algorithm.name()].clone(),
+            checksum_algorithm: self.config.checksum_algorithm.name().to_string(),
+            checksums: digests.all,
+            duration_secs: duration.as_secs_f64(),
+            avg_speed_bytes_per_sec: downloaded as f64 / duration.as_secs_f64(),
+            resumed: false,
+            chunks_downloaded: 1,
+            connections: 1,
+        };
+        Ok((result, metadata))
+    }
+
+    async fn read_to_bytes(&self, url: &str) -> Result<(Bytes, DownloadResult)> {
+        let start_time = std::time::Instant::now();
+        let response = self.start_single_stream(url).await?;
+        let expected_size = body_length(&response);
+
+        // Trust the advertised length only so far when reserving memory
+        let capacity = expected_size.unwrap_or(0).min(MAX_PREALLOCATED_BUFFER);
+        let mut buffer = Vec::with_capacity(capacity as usize);
+        let mut hasher = self.checksums();
+        let mut downloaded = 0u64;
+        let mut chunks_downloaded = 0u32;
+        {
+            let mut body = std::pin::pin!(self.body_stream(response, &mut hasher, &mut downloaded));
+            while let Some(bytes) = body.try_next().await? {
+                buffer.extend_from_slice(&bytes);
+                chunks_downloaded += 1;
+            }
+        }
+
+        if let Some(expected) = expected_size.filter(|&expected| expected != downloaded) {
+            return Err(ScraperError::DownloadFailed {
+                attempts: 1,
+                message: format!("received {} of {} bytes", downloaded, expected),
+            });
+        }
+        if downloaded < self.config.min_file_size_bytes {
+            return Err(ScraperError::FileTooSmall {
+                size_bytes: downloaded,
+                min_bytes: self.config.min_file_size_bytes,
+            });
+        }
+
+        let digests = hasher.finalize();
+        let duration = start_time.elapsed();
+        let result = DownloadResult {
+            url: url.to_string(),
+            output_path: String::new(),
+            size_bytes: downloaded,
+            sha256_hash: digests.sha256,
+            checksum: digests.all[self.config.checksum_algorithm.name()].clone(),
+            checksum_algorithm: self.config.checksum_algorithm.name().to_string(),
+            checksums: digests.all,
+            duration_secs: duration.as_secs_f64(),
+            avg_speed_bytes_per_sec: downloaded as f64 / duration.as_secs_f64(),
+            resumed: false,
+            chunks_downloaded,
+            connections: 1,
+        };
+        Ok((Bytes::from(buffer), result))
+    }
+
+    /// Open a plain GET for a download that is consumed as one stream rather
+    /// than written to a local file, checking any advertised size up front
+    async fn start_single_stream(&self, url: &str) -> Result<Response> {
+        let response = self.client.get_with_headers(url, None).await?;
+        let content_type = response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok());
+        if hls::is_hls(url, content_type) {
+            return Err(ScraperError::InvalidFormat(format!(
+                "{} is an HLS stream, which must be downloaded to a local file",
+                url
+            )));
+        }
+        if let Some(total) = body_length(&response) {
+            self.check_size_bounds(total)?;
+        }
+        Ok(response)
+    }
+
+    /// A response body that feeds `hasher` and `downloaded` as it is read,
+    /// with the idle timeout, bandwidth limit and `max_file_size_bytes`
+    /// applied
+    fn body_stream<'a>(
+        &'a self,
+        response: reqwest::Response,
+        hasher: &'a mut Checksums,
+        downloaded: &'a mut u64,
+    ) -> impl Stream<Item = Result<Bytes>> + Send + 'a {
+        let max = self.config.max_file_size_bytes;
+        let body = Box::pin(response.bytes_stream());
+
+        stream::try_unfold(
+            (body, hasher, downloaded),
+            move |(mut body, hasher, downloaded)| async move {
+                let Some(bytes) = self.next_chunk(&mut body).await? else {
+                    return Ok(None);
+                };
+                hasher.update(&bytes);
+                *downloaded += bytes.len() as u64;
+
+                if max > 0 && *downloaded > max {
+                    return Err(ScraperError::FileTooLarge {
+                        size_bytes: *downloaded,
+                        limit_bytes: max,
+                    });
+                }
+                self.throttle(bytes.len()).await;
+                Ok(Some((bytes, (body, hasher, downloaded))))
+            },
+        )
+    }
+
+    /// Fresh running checksums for the configured algorithms
+    fn checksums(&self) -> Checksums {
+        let mut algorithms = vec![self.config.checksum_algorithm];
+        algorithms.extend_from_slice(&self.config.extra_checksum_algorithms);
+        Checksums::new(&algorithms)
+    }
+
+    /// Reject a file whose known size is outside the configured bounds
+    fn check_size_bounds(&self, size: u64) -> Result<()> {
+        let max = self.config.max_file_size_bytes;
+        if max > 0 && size > max {
+            return Err(ScraperError::FileTooLarge {
+                size_bytes: size,
+                limit_bytes: max,
+            });
+        }
+        if size < self.config.min_file_size_bytes {
+            return Err(ScraperError::FileTooSmall {
+                size_bytes: size,
+                min_bytes: self.config.min_file_size_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fail if `total` bytes won't fit next to `part_path` while leaving
+    /// `disk_space_margin_bytes` free. Space the `.part` file already holds
+    /// is reused, whether the download resumes or starts over.
+    async fn check_disk_space(&self, part_path: &Path, total: u64) -> Result<()> {
+        let Some(dir) = part_path.parent() else {
+            return Ok(());
+        };
+        let Some(available) = available_space(dir) else {
+            debug!("Couldn't read free space of {:?}, skipping the check", dir);
+            return Ok(());
+        };
+
+        let reused = fs::metadata(part_path).await.map_or(0, |m| m.len());
+        let needed = total.saturating_sub(reused);
+        let margin = self.config.disk_space_margin_bytes;
+        if needed.saturating_add(margin) > available {
+            return Err(ScraperError::StorageError(format!(
+                "insufficient disk space in {:?}: {} bytes needed plus a {} byte margin, \
+                 {} available",
+                dir, needed, margin, available
+            )));
+        }
+        Ok(())
+    }
+
+    /// Download every segment of an HLS media playlist and concatenate them
+    /// into `output_path`. Playlists are small and segments are fetched
+    /// whole, so HLS downloads restart rather than resume.
+    async fn download_hls(
+        &self,
+        url: &str,
+        output_path: &Path,
+        options: &DownloadOptions,
+        cancel: &CancellationToken,
+    ) -> Result<DownloadResult> {
+        let start_time = Instant::now();
+
+        let playlists = async {
+            let (playlist_url, text) = self.fetch_playlist(url).await?;
+            if !hls::is_master_playlist(&text) {
+                return Ok((playlist_url, text));
+            }
+
+            // Given a master playlist, fetch its highest-bandwidth variant
+            let variant = hls::parse_master_playlist(&text, &playlist_url)?
+                .into_iter()
+                .max_by_key(|v| v.bandwidth.unwrap_or(0))
+                .ok_or_else(|| {
+                    ScraperError::InvalidFormat(format!("HLS master has no variants: {}", url))
+                })?;
+            debug!("Selected HLS variant {:?}", variant);
+            self.fetch_playlist(&variant.url).await
+        };
+        let (playlist_url, text) = tokio::select! {
+            result = playlists => result?,
+            _ = cancel.cancelled() => return Err(cancelled()),
+        };
+        let playlist = hls::parse_media_playlist(&text, &playlist_url)?;
+        if playlist.segments.is_empty() {
+            return Err(ScraperError::InvalidFormat(format!(
+                "HLS playlist has no segments: {}",
+                url
+            )));
+        }
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let part_path = self.get_part_path(output_path);
+        let mut file = File::create(&part_path).await?;
+        let mut hasher = self.checksums();
+        let mut downloaded = 0u64;
+        let progress = ProgressReporter::new(options.progress.as_ref(), url, None, 0);
+
+        let concurrency = self
+            .config
+            .download_connections
+            .max(1)
+            .min(playlist.segments.len());
+        let max = self.config.max_file_size_bytes;
+
+        info!(
+            "Downloading {} HLS segments ({:.1}s) from {}",
+            playlist.segments.len(),
+            playlist.duration_secs(),
+            url
+        );
+
+        let transfer = async {
+            // `buffered` keeps segments in playlist order while fetching ahead
+            let segments: Vec<_> =
+                playlist.init_segment.iter().chain(&playlist.segments).cloned().collect();
+            let mut fetched = stream::iter(segments)
+                .map(|segment| self.fetch_hls_segment(segment))
+                .buffered(concurrency);
+
+            while let Some(bytes) = fetched.try_next().await? {
+                file.write_all(&bytes).await?;
+                hasher.update(&bytes);
+                downloaded += bytes.len() as u64;
+
+                if max > 0 && downloaded > max {
+                    return Err(ScraperError::FileTooLarge {
+                        size_bytes: downloaded,
+                        limit_bytes: max,
+                    });
+                }
+
+                progress.advance(bytes.len() as u64)?;
+                self.throttle(bytes.len()).await;
+            }
+
+            Ok(())
+        };
+
+        let transfer = tokio::select! {
+            result = transfer => result,
+            _ = cancel.cancelled() => Err(cancelled()),
+        };
+
+        file.flush().await?;
+        drop(file);
+
+        if let Err(e) = transfer {
+            let _ = fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+
+        let digests = hasher.finalize();
+        self.finalize_download(url, &part_path, output_path, downloaded, &digests.sha256, options)
+            .await?;
+        progress.finish()?;
+        let duration = start_time.elapsed();
+
+        Ok(DownloadResult {
+            url: url.to_string(),
+            output_path: output_path.to_string_lossy().to_string(),
+            size_bytes: downloaded,
+            sha256_hash: digests.sha256,
+            checksum: digests.all[self.config.checksum_algorithm.name()].clone(),
+            checksum_algorithm: self.config.checksum_algorithm.name().to_string(),
+            checksums: digests.all,
+            duration_secs: duration.as_secs_f64(),
+            avg_speed_bytes_per_sec: downloaded as f64 / duration.as_secs_f64(),
+            resumed: false,
+            chunks_downloaded: playlist.segments.len() as u32,
+            connections: concurrency as u32,
         })
     }
 
+    /// Fetch one HLS segment into memory
+    /// Fetch a playlist past the response cache, as live playlists change
+    /// between requests, failing once it exceeds `max_page_bytes`. Returns
+    /// the URL it ended up at, which segment URIs are relative to, and its text.
+    async fn fetch_playlist(&self, url: &str) -> Result<(String, String)> {
+        let response = self.client.get_with_headers(url, None).await?;
+        let playlist_url = response.url().to_string();
+        Ok((playlist_url, self.client.read_page(response).await?))
+    }
+
+    async fn fetch_hls_segment(&self, segment: hls::MediaSegment) -> Result<Bytes> {
+        let response = match segment.byte_range {
+            Some((start, end)) => {
+                let response = self
+                    .client
+                    .get_range(&segment.url, start, Some(end), None)
+                    .await?;
+                if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                    return Err(ScraperError::DownloadFailed {
+                        attempts: 1,
+                        message: format!(
+                            "Expected 206 for segment range {}-{} of {}, got {}",
+                            start,
+                            end,
+                            segment.url,
+                            response.status()
+                        ),
+                    });
+                }
+                response
+            }
+            None => self.client.get_with_headers(&segment.url, None).await?,
+        };
+
+        let mut stream = std::pin::pin!(response.bytes_stream());
+        let mut body = Vec::new();
+        while let Some(bytes) = self.next_chunk(&mut stream).await? {
+            body.extend_from_slice(&bytes);
+        }
+
+        Ok(Bytes::from(body))
+    }
+
+    /// Fetch the parts of `[0, total)` missing from `written` over concurrent
+    /// range requests, each writing at its own file offset and recording in
+    /// `written` how far it got. A single gap, as in a fresh download, is
+    /// split across `connections`; a resumed download fetches each of its
+    /// gaps as a segment. Returns the number of segments fetched.
+    #[allow(clippy::too_many_arguments)]
+    async fn download_segments(
+        &self,
+        url: &str,
+        part_path: &Path,
+        total: u64,
+        connections: usize,
+        if_range: Option<&str>,
+        written: &Mutex<Vec<(u64, u64)>>,
+        progress: &ProgressReporter<'_>,
+    ) -> Result<u32> {
+        let gaps = missing_ranges(&written.lock().unwrap(), total);
+        let segments = match gaps[..] {
+            [(start, end)] => {
+                let segment_size = (end - start).div_ceil(connections as u64).max(1);
+                (0..connections as u64)
+                    .map(|i| start + i * segment_size)
+                    .take_while(|&seg_start| seg_start < end)
+                    .map(|seg_start| (seg_start, (seg_start + segment_size).min(end)))
+                    .collect()
+            }
+            _ => gaps,
+        };
+        let count = segments.len() as u32;
+
+        debug!("Downloading {} in {} parallel segments", url, count);
+
+        // Each segment records its progress in its own slot
+        let slots = {
+            let mut written = written.lock().unwrap();
+            let first = written.len();
+            written.extend(segments.iter().map(|&(start, _)| (start, start)));
+            first..written.len()
+        };
+
+        stream::iter(segments.into_iter().zip(slots))
+            .map(|((_, end), slot)| {
+                self.download_segment(url, part_path, end, if_range, written, slot, progress)
+            })
+            .buffer_unordered(connections)
+            .try_collect::<Vec<()>>()
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Fetch one segment, up to the exclusive offset `end`, picking up where
+    /// the last attempt stopped after a transient error
+    #[allow(clippy::too_many_arguments)]
+    async fn download_segment(
+        &self,
+        url: &str,
+        part_path: &Path,
+        end: u64,
+        if_range: Option<&str>,
+        written: &Mutex<Vec<(u64, u64)>>,
+        slot: usize,
+        progress: &ProgressReporter<'_>,
+    ) -> Result<()> {
+        let mut attempt = 1;
+        loop {
+            let start = written.lock().unwrap()[slot].1;
+            let fetched = self
+                .fetch_segment(url, part_path, start, end - 1, if_range, written, slot, progress)
+                .await;
+            match fetched {
+                Err(e) if e.is_retryable() && attempt < self.config.max_retries => {
+                    let delay = self.client.retry_delay(attempt);
+                    warn!(
+                        "Segment of {} failed at byte {} ({}), retrying in {:?}",
+                        url, start, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch the inclusive byte range `[start, end]` into the file at
+    /// `start`, advancing the segment's slot in `written` as bytes land
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_segment(
+        &self,
+        url: &str,
+        part_path: &Path,
+        start: u64,
+        end: u64,
+        if_range: Option<&str>,
+        written: &Mutex<Vec<(u64, u64)>>,
+        slot: usize,
+        progress: &ProgressReporter<'_>,
+    ) -> Result<()> {
+        let mut file = OpenOptions::new().write(true).open(part_path).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+
+        let response = self.client.get_range(url, start, Some(end), if_range).await?;
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            return Err(ScraperError::DownloadFailed {
+                attempts: 1,
+                message: format!(
+                    "Expected 206 for range {}-{}, got {}",
+                    start,
+                    end,
+                    response.status()
+                ),
+            });
+        }
+
+        let mut stream = std::pin::pin!(response.bytes_stream());
+        let mut received = 0u64;
+        let mut pacer = ChunkPacer::new(&self.config);
+
+        while let Some(bytes) = self.next_chunk(&mut stream).await? {
+            file.write_all(&bytes).await?;
+            received += bytes.len() as u64;
+            written.lock().unwrap()[slot].1 += bytes.len() as u64;
+            progress.advance(bytes.len() as u64)?;
+            self.throttle(bytes.len()).await;
+            pacer.wrote(bytes.len()).await;
+        }
+        file.flush().await?;
+
+        let expected = end - start + 1;
+        if received != expected {
+            return Err(ScraperError::DownloadFailed {
+                attempts: 1,
+                message: format!(
+                    "Segment {}-{} returned {} of {} bytes",
+                    start, end, received, expected
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pull the next piece of a response body, failing if the server sends
+    /// nothing for longer than `read_idle_timeout_secs`
+    async fn next_chunk<S>(&self, stream: &mut S) -> Result<Option<Bytes>>
+    where
+        S: Stream<Item = reqwest::Result<Bytes>> + Unpin,
+    {
+        let idle_secs = self.config.read_idle_timeout_secs;
+        let next = if idle_secs > 0 {
+            tokio::time::timeout(Duration::from_secs(idle_secs), stream.next())
+                .await
+                .map_err(|_| {
+                    warn!("No data received for {} seconds, aborting transfer", idle_secs);
+                    ScraperError::Timeout {
+                        timeout_secs: idle_secs,
+                    }
+                })?
+        } else {
+            stream.next().await
+        };
+
+        Ok(next.transpose()?)
+    }
+
+    /// Wait on the global bandwidth limit, if one is configured
+    async fn throttle(&self, bytes: usize) {
+        if let Some(ref throttle) = self.throttle {
+            throttle.consume(bytes).await;
+        }
+    }
+
     /// Download multiple files concurrently
     pub async fn download_batch(
         &self,
@@ -287,6 +1586,7 @@ impl DownloadManager {
                                 speed_bytes_per_sec: r.avg_speed_bytes_per_sec,
                                 eta_secs: Some(0.0),
                                 status: "completed".to_string(),
+                                job_id: None,
                             },
                             Err(e) => DownloadProgress {
                                 url: url.clone(),
@@ -296,6 +1596,7 @@ impl DownloadManager {
                                 speed_bytes_per_sec: 0.0,
                                 eta_secs: None,
                                 status: format!("error: {}", e),
+                                job_id: None,
                             },
                         };
                         let _ = tx.send(progress).await;
@@ -311,7 +1612,20 @@ impl DownloadManager {
 
     /// Get the number of active downloads
     pub fn active_downloads(&self) -> u64 {
-        self.active_downloads.load(Ordering::SeqCst)
+        self.metrics.active.load(Ordering::SeqCst)
+    }
+
+    /// Download counters and gauges in Prometheus exposition format
+    pub fn metrics_text(&self) -> String {
+        let mut out = String::new();
+        self.metrics.render(&mut out);
+        out
+    }
+
+    fn get_part_path(&self, output_path: &Path) -> PathBuf {
+        let mut part_path = output_path.as_os_str().to_owned();
+        part_path.push(".part");
+        PathBuf::from(part_path)
     }
 
     fn get_state_path(&self, output_path: &Path) -> PathBuf {
@@ -326,6 +1640,64 @@ impl DownloadManager {
         serde_json::from_str(&content).map_err(|e| e.into())
     }
 
+    /// Record progress so a later call can resume from `downloaded`
+    #[allow(clippy::too_many_arguments)]
+    async fn save_resume_state(
+        &self,
+        state_path: &Path,
+        url: &str,
+        output_path: &Path,
+        total_bytes: Option<u64>,
+        start_byte: u64,
+        downloaded: u64,
+        hasher: &Checksums,
+        validators: &ResumeValidators,
+    ) -> Result<()> {
+        self.save_state(state_path, &DownloadState {
+            url: url.to_string(),
+            output_path: output_path.to_string_lossy().to_string(),
+            total_bytes,
+            downloaded_bytes: downloaded,
+            chunk_size: self.config.chunk_size_bytes,
+            partial_hash: hasher.sha256_hex(),
+            chunks_completed: vec![(start_byte, downloaded)],
+            segmented: false,
+            started_at: chrono::Utc::now(),
+            last_updated: chrono::Utc::now(),
+            validators: validators.clone(),
+        }).await
+    }
+
+    /// Record the ranges parallel segments have written so a later call can
+    /// fetch just the gaps
+    async fn save_segment_state(
+        &self,
+        state_path: &Path,
+        url: &str,
+        output_path: &Path,
+        total: u64,
+        written: &Mutex<Vec<(u64, u64)>>,
+        validators: &ResumeValidators,
+    ) -> Result<()> {
+        let mut ranges: Vec<_> =
+            written.lock().unwrap().iter().copied().filter(|(s, e)| s < e).collect();
+        ranges.sort_unstable();
+        self.save_state(state_path, &DownloadState {
+            url: url.to_string(),
+            output_path: output_path.to_string_lossy().to_string(),
+            total_bytes: Some(total),
+            downloaded_bytes: ranges.iter().map(|(s, e)| e - s).sum(),
+            chunk_size: self.config.chunk_size_bytes,
+            // Written out of order, so there is no running hash to save
+            partial_hash: String::new(),
+            chunks_completed: ranges,
+            segmented: true,
+            started_at: chrono::Utc::now(),
+            last_updated: chrono::Utc::now(),
+            validators: validators.clone(),
+        }).await
+    }
+
     async fn save_state(&self, path: &Path, state: &DownloadState) -> Result<()> {
         let content = serde_json::to_string_pretty(state)?;
         fs::write(path, content).await?;
@@ -333,13 +1705,137 @@ impl DownloadManager {
     }
 }
 
+/// A hidden file next to `path` named after it, e.g. `.video.clip.mp4`,
+/// keeping the extension that tells ffmpeg the container
+#[cfg(feature = "ffmpeg")]
+fn sibling_path(path: &Path, label: &str) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}", label, file_name))
+}
+
+/// The error a cancelled download fails with
+fn cancelled() -> ScraperError {
+    ScraperError::Cancelled("Download".to_string())
+}
+
+/// The parts of `[0, total)` that none of the half-open `ranges` cover
+fn missing_ranges(ranges: &[(u64, u64)], total: u64) -> Vec<(u64, u64)> {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable();
+
+    let mut gaps = Vec::new();
+    let mut covered = 0;
+    for (start, end) in ranges {
+        if start > covered {
+            gaps.push((covered, start.min(total)));
+        }
+        covered = covered.max(end);
+    }
+    if covered < total {
+        gaps.push((covered, total));
+    }
+    gaps.retain(|(start, end)| start < end);
+    gaps
+}
+
+/// Record the validators of the first response, and reject a range that
+/// comes back whole, which means the file changed under `If-Range` or the
+/// server ignored the range
+fn check_same_version(
+    response: &Response,
+    validators: &mut ResumeValidators,
+    offset: u64,
+) -> Result<()> {
+    if offset > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(ScraperError::Aborted(
+            "file changed on the server during the download".to_string(),
+        ));
+    }
+    // States saved before validators were recorded pick them up on resume
+    if offset == 0 || validators.if_range().is_none() {
+        *validators = ResumeValidators::from_response(response);
+    }
+    Ok(())
+}
+
+/// Reserve `len` bytes for `file` so the filesystem can lay it out
+/// contiguously. Uses `fallocate` on Linux and falls back to `set_len`, which
+/// only extends the file, where that isn't supported.
+async fn preallocate(file: &File, len: u64) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::fd::AsRawFd;
+
+        if let Ok(len) = libc::off_t::try_from(len) {
+            // SAFETY: the descriptor belongs to `file`, which outlives the call
+            if unsafe { libc::fallocate(file.as_raw_fd(), 0, 0, len) } == 0 {
+                return Ok(());
+            }
+            debug!(
+                "fallocate failed ({}), extending with set_len",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    file.set_len(len).await?;
+    Ok(())
+}
+
+/// Bytes available to unprivileged writers on the filesystem holding `dir`,
+/// or `None` where that can't be read
+fn available_space(dir: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+        let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+        // SAFETY: `path` is NUL-terminated and `stats` is only read after
+        // `statvfs` succeeds and has filled it in
+        let stats = unsafe {
+            if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+                return None;
+            }
+            stats.assume_init()
+        };
+        // Field widths differ between platforms
+        #[allow(clippy::unnecessary_cast)]
+        Some((stats.f_bavail as u64).saturating_mul(stats.f_frsize as u64))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = dir;
+        None
+    }
+}
+
+/// Feed the first `len` bytes of a file into the running checksums
+async fn hash_file_into(path: &Path, len: u64, hasher: &mut Checksums) -> Result<()> {
+    let mut reader = File::open(path).await?.take(len);
+    let mut buf = vec![0u8; 1024 * 1024];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(())
+}
+
 impl Clone for DownloadManager {
     fn clone(&self) -> Self {
         Self {
             client: self.client.clone(),
             config: self.config.clone(),
             semaphore: self.semaphore.clone(),
-            active_downloads: self.active_downloads.clone(),
+            metrics: self.metrics.clone(),
+            throttle: self.throttle.clone(),
+            cancellations: self.cancellations.clone(),
+            next_download_id: self.next_download_id.clone(),
         }
     }
 }
@@ -357,9 +1853,7 @@ impl PyDownloadManager {
     #[pyo3(signature = (config=None))]
     pub fn new(config: Option<&ScraperConfig>) -> PyResult<Self> {
         let config = config.cloned().unwrap_or_default();
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+        let runtime = crate::runtime::shared_runtime(config.worker_threads)?;
 
         let client = HttpClient::new(&config).map_err(|e| {
             pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create client: {}", e))
@@ -369,23 +1863,159 @@ impl PyDownloadManager {
 
         Ok(Self {
             inner: Arc::new(manager),
-            runtime: Arc::new(runtime),
+            runtime,
         })
     }
 
-    /// Download a single file
-    pub fn download(&self, url: &str, output_path: &str) -> PyResult<DownloadResult> {
+    /// Download a single file. Raises `CancelledError`, not `DownloadError`,
+    /// when `cancel` stops it.
+    #[pyo3(signature = (url, output_path, expected_sha256=None))]
+    pub fn download(
+        &self,
+        py: Python<'_>,
+        url: &str,
+        output_path: &str,
+        expected_sha256: Option<String>,
+    ) -> PyResult<DownloadResult> {
         let manager = self.inner.clone();
         let url = url.to_string();
         let path = PathBuf::from(output_path);
+        let options = DownloadOptions {
+            expected_sha256,
+            ..Default::default()
+        };
 
-        self.runtime.block_on(async move {
-            manager.download(&url, &path).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
+        // Release the GIL so other Python threads can call `cancel`
+        let runtime = self.runtime.clone();
+        py.allow_threads(move || {
+            runtime.block_on(async move {
+                manager.download_with_options(&url, &path, &options).await.map_err(PyErr::from)
             })
         })
     }
 
+    /// Download `url` into memory and return its contents as `bytes`.
+    /// `max_file_size_bytes` bounds how much will be buffered.
+    pub fn download_bytes(&self, py: Python<'_>, url: &str) -> PyResult<PyObject> {
+        let manager = self.inner.clone();
+        let url = url.to_string();
+
+        let runtime = self.runtime.clone();
+        let (data, _) = py.allow_threads(move || {
+            runtime.block_on(async move {
+                manager.download_to_bytes(&url).await.map_err(PyErr::from)
+            })
+        })?;
+        Ok(PyBytes::new(py, &data).into())
+    }
+
+    /// Download separate video and audio streams, such as an
+    /// `ExtractionResult`'s `best_video_url` and `best_audio_url`, and merge
+    /// them into one file with ffmpeg
+    #[cfg(feature = "ffmpeg")]
+    pub fn download_and_mux(
+        &self,
+        py: Python<'_>,
+        video_url: &str,
+        audio_url: &str,
+        output_path: &str,
+    ) -> PyResult<DownloadResult> {
+        let manager = self.inner.clone();
+        let (video_url, audio_url) = (video_url.to_string(), audio_url.to_string());
+        let path = PathBuf::from(output_path);
+
+        let runtime = self.runtime.clone();
+        py.allow_threads(move || {
+            runtime.block_on(async move {
+                manager
+                    .download_and_mux(&video_url, &audio_url, &path)
+                    .await
+                    .map_err(PyErr::from)
+            })
+        })
+    }
+
+    /// Async variant of `download` for use inside an asyncio event loop. Like
+    /// `download`, it raises `CancelledError`, not `DownloadError`, when
+    /// `cancel` stops it.
+    #[pyo3(signature = (url, output_path, expected_sha256=None))]
+    pub fn download_async<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        output_path: &str,
+        expected_sha256: Option<String>,
+    ) -> PyResult<&'py PyAny> {
+        let manager = self.inner.clone();
+        let url = url.to_string();
+        let path = PathBuf::from(output_path);
+        let cancel = CancellationToken::new();
+        let options = DownloadOptions {
+            expected_sha256,
+            cancel: Some(cancel.clone()),
+            ..Default::default()
+        };
+
+        crate::runtime::into_awaitable(py, async move {
+            // Cancelling the awaitable cancels the download as `cancel` does,
+            // keeping its `.part` file and resume state
+            let _cancel_on_drop = cancel.drop_guard();
+            let download =
+                async move { manager.download_with_options(&url, &path, &options).await };
+            crate::runtime::detached(download).await?.map_err(PyErr::from)
+        })
+    }
+
+    /// Download a single file, calling `callback(progress)` about every 250ms.
+    /// An exception raised by the callback aborts the download and is re-raised.
+    #[pyo3(signature = (url, output_path, callback, expected_sha256=None))]
+    pub fn download_with_progress(
+        &self,
+        py: Python<'_>,
+        url: &str,
+        output_path: &str,
+        callback: PyObject,
+        expected_sha256: Option<String>,
+    ) -> PyResult<DownloadResult> {
+        let manager = self.inner.clone();
+        let url = url.to_string();
+        let path = PathBuf::from(output_path);
+
+        // Keep the Python exception so it can be re-raised unchanged
+        let raised: Arc<Mutex<Option<PyErr>>> = Arc::new(Mutex::new(None));
+        let slot = raised.clone();
+        let progress: ProgressCallback = Arc::new(move |update: &DownloadProgress| {
+            Python::with_gil(|py| {
+                callback.call1(py, (update.clone(),)).map(|_| ()).map_err(|e| {
+                    let message = format!("Progress callback raised: {}", e);
+                    *slot.lock().unwrap() = Some(e);
+                    ScraperError::Aborted(message)
+                })
+            })
+        });
+        let options = DownloadOptions {
+            expected_sha256,
+            progress: Some(progress),
+            ..Default::default()
+        };
+
+        // Release the GIL so the runtime's threads can take it for callbacks
+        let runtime = self.runtime.clone();
+        let result = py.allow_threads(move || {
+            runtime.block_on(async move {
+                manager.download_with_options(&url, &path, &options).await
+            })
+        });
+
+        result.map_err(|e| {
+            raised
+                .lock()
+                .unwrap()
+                .take()
+                .unwrap_or_else(|| e.into())
+        })
+    }
+
     /// Download multiple files concurrently
     pub fn download_batch(&self, items: Vec<(String, String)>) -> PyResult<Vec<DownloadResult>> {
         let manager = self.inner.clone();
@@ -410,9 +2040,23 @@ impl PyDownloadManager {
         })
     }
 
+    /// Cancel the in-progress downloads of `url`, or only the one writing to
+    /// `output_path`; they raise `CancelledError`, which is not a
+    /// `DownloadError`, and can be resumed later. Returns whether any was found.
+    #[pyo3(signature = (url, output_path=None))]
+    pub fn cancel(&self, url: &str, output_path: Option<&str>) -> bool {
+        self.inner.cancel(url, output_path.map(Path::new))
+    }
+
     /// Get number of active downloads
     pub fn active_downloads(&self) -> u64 {
         self.inner.active_downloads()
     }
+
+    /// Download metrics in Prometheus exposition format, for serving from a
+    /// `/metrics` endpoint
+    pub fn metrics_text(&self) -> String {
+        self.inner.metrics_text()
+    }
 }
 
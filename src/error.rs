@@ -1,9 +1,61 @@
 //! Error types for the video scraper system
 
-use pyo3::exceptions::PyRuntimeError;
-use pyo3::PyErr;
+use pyo3::prelude::*;
 use thiserror::Error;
 
+/// Python exception classes, one per kind of failure, all deriving from
+/// `ScraperError`. Structured fields of the Rust error, like
+/// `retry_after_secs`, are set as attributes, along with `retryable`.
+pub mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyException;
+    use pyo3::prelude::*;
+
+    create_exception!(videoscraper, ScraperError, PyException);
+    create_exception!(videoscraper, HttpError, ScraperError);
+    create_exception!(videoscraper, InvalidUrlError, ScraperError);
+    create_exception!(videoscraper, ParseError, ScraperError);
+    create_exception!(videoscraper, ScraperIOError, ScraperError);
+    create_exception!(videoscraper, RateLimitError, ScraperError);
+    create_exception!(videoscraper, DownloadError, ScraperError);
+    create_exception!(videoscraper, ExtractionError, ScraperError);
+    create_exception!(videoscraper, StorageError, ScraperError);
+    create_exception!(videoscraper, ConfigError, ScraperError);
+    create_exception!(videoscraper, PipelineError, ScraperError);
+    create_exception!(videoscraper, RequestTimeoutError, ScraperError);
+    create_exception!(videoscraper, FileSizeError, ScraperError);
+    create_exception!(videoscraper, InvalidFormatError, ScraperError);
+    create_exception!(videoscraper, NotFoundError, ScraperError);
+    create_exception!(videoscraper, AccessDeniedError, ScraperError);
+    create_exception!(videoscraper, VerificationError, ScraperError);
+    create_exception!(videoscraper, CircuitOpenError, ScraperError);
+    create_exception!(videoscraper, CancelledError, ScraperError);
+
+    /// Add the exception classes to the Python module
+    pub fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+        m.add("ScraperError", py.get_type::<ScraperError>())?;
+        m.add("HttpError", py.get_type::<HttpError>())?;
+        m.add("InvalidUrlError", py.get_type::<InvalidUrlError>())?;
+        m.add("ParseError", py.get_type::<ParseError>())?;
+        m.add("ScraperIOError", py.get_type::<ScraperIOError>())?;
+        m.add("RateLimitError", py.get_type::<RateLimitError>())?;
+        m.add("DownloadError", py.get_type::<DownloadError>())?;
+        m.add("ExtractionError", py.get_type::<ExtractionError>())?;
+        m.add("StorageError", py.get_type::<StorageError>())?;
+        m.add("ConfigError", py.get_type::<ConfigError>())?;
+        m.add("PipelineError", py.get_type::<PipelineError>())?;
+        m.add("RequestTimeoutError", py.get_type::<RequestTimeoutError>())?;
+        m.add("FileSizeError", py.get_type::<FileSizeError>())?;
+        m.add("InvalidFormatError", py.get_type::<InvalidFormatError>())?;
+        m.add("NotFoundError", py.get_type::<NotFoundError>())?;
+        m.add("AccessDeniedError", py.get_type::<AccessDeniedError>())?;
+        m.add("VerificationError", py.get_type::<VerificationError>())?;
+        m.add("CircuitOpenError", py.get_type::<CircuitOpenError>())?;
+        m.add("CancelledError", py.get_type::<CancelledError>())?;
+        Ok(())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ScraperError {
     #[error("HTTP request failed: {0}")]
@@ -39,6 +91,12 @@ pub enum ScraperError {
     #[error("Timeout after {timeout_secs} seconds")]
     Timeout { timeout_secs: u64 },
 
+    #[error("File too large: {size_bytes} bytes exceeds limit of {limit_bytes} bytes")]
+    FileTooLarge { size_bytes: u64, limit_bytes: u64 },
+
+    #[error("File too small: {size_bytes} bytes is below minimum of {min_bytes} bytes")]
+    FileTooSmall { size_bytes: u64, min_bytes: u64 },
+
     #[error("Invalid video format: {0}")]
     InvalidFormat(String),
 
@@ -48,6 +106,18 @@ pub enum ScraperError {
     #[error("Access denied: {0}")]
     AccessDenied(String),
 
+    #[error("Circuit open for {domain}, retry after {retry_after_secs} seconds")]
+    CircuitOpen { domain: String, retry_after_secs: u64 },
+
+    /// A download or job stopped on request, e.g. by `cancel` or a timeout
+    #[error("{0} cancelled")]
+    Cancelled(String),
+
+    /// A download that trying again can't fix, e.g. the file changed on the
+    /// server or a progress callback stopped it
+    #[error("Download aborted: {0}")]
+    Aborted(String),
+
     #[error("Chunk verification failed: expected {expected}, got {actual}")]
     ChunkVerificationFailed { expected: String, actual: String },
 
@@ -56,11 +126,116 @@ pub enum ScraperError {
 
     #[error("GCS error: {0}")]
     GcsError(String),
+
+    #[error("Azure Blob Storage error: {0}")]
+    AzureError(String),
+}
+
+impl ScraperError {
+    /// Whether the failure is likely transient, so trying again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ScraperError::HttpError(e) => e
+                .status()
+                .is_none_or(|s| s.is_server_error() || s.as_u16() == 429),
+            ScraperError::DownloadFailed { .. } => true,
+            // Only connection trouble; a full disk or a permission error stays put
+            ScraperError::IoError(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::UnexpectedEof
+            ),
+            ScraperError::RateLimited { .. }
+            | ScraperError::CircuitOpen { .. }
+            | ScraperError::Timeout { .. }
+            | ScraperError::ChunkVerificationFailed { .. } => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<ScraperError> for PyErr {
     fn from(err: ScraperError) -> PyErr {
-        PyRuntimeError::new_err(err.to_string())
+        use exceptions as exc;
+
+        let message = err.to_string();
+        let py_err = match &err {
+            ScraperError::HttpError(_) => exc::HttpError::new_err(message),
+            ScraperError::UrlError(_) => exc::InvalidUrlError::new_err(message),
+            ScraperError::JsonError(_) => exc::ParseError::new_err(message),
+            ScraperError::IoError(_) => exc::ScraperIOError::new_err(message),
+            ScraperError::RateLimited { .. } => exc::RateLimitError::new_err(message),
+            ScraperError::DownloadFailed { .. } | ScraperError::Aborted(_) => {
+                exc::DownloadError::new_err(message)
+            }
+            ScraperError::ExtractionFailed(_) => exc::ExtractionError::new_err(message),
+            ScraperError::StorageError(_)
+            | ScraperError::S3Error(_)
+            | ScraperError::GcsError(_)
+            | ScraperError::AzureError(_) => exc::StorageError::new_err(message),
+            ScraperError::ConfigError(_) => exc::ConfigError::new_err(message),
+            ScraperError::PipelineError(_) => exc::PipelineError::new_err(message),
+            ScraperError::Timeout { .. } => exc::RequestTimeoutError::new_err(message),
+            ScraperError::FileTooLarge { .. } | ScraperError::FileTooSmall { .. } => {
+                exc::FileSizeError::new_err(message)
+            }
+            ScraperError::InvalidFormat(_) => exc::InvalidFormatError::new_err(message),
+            ScraperError::NotFound(_) => exc::NotFoundError::new_err(message),
+            ScraperError::AccessDenied(_) => exc::AccessDeniedError::new_err(message),
+            ScraperError::CircuitOpen { .. } => exc::CircuitOpenError::new_err(message),
+            ScraperError::Cancelled(_) => exc::CancelledError::new_err(message),
+            ScraperError::ChunkVerificationFailed { .. } => {
+                exc::VerificationError::new_err(message)
+            }
+        };
+
+        Python::with_gil(|py| {
+            let value = py_err.value(py);
+            let set = |name: &str, attr: PyObject| value.setattr(name, attr);
+            let fields = match &err {
+                ScraperError::HttpError(e) => {
+                    set("status_code", e.status().map(|s| s.as_u16()).into_py(py))
+                }
+                ScraperError::RateLimited { retry_after_secs } => {
+                    set("retry_after_secs", retry_after_secs.into_py(py))
+                }
+                ScraperError::DownloadFailed { attempts, .. } => {
+                    set("attempts", attempts.into_py(py))
+                }
+                ScraperError::CircuitOpen {
+                    domain,
+                    retry_after_secs,
+                } => set("domain", domain.into_py(py))
+                    .and_then(|_| set("retry_after_secs", retry_after_secs.into_py(py))),
+                ScraperError::Timeout { timeout_secs } => {
+                    set("timeout_secs", timeout_secs.into_py(py))
+                }
+                ScraperError::FileTooLarge {
+                    size_bytes,
+                    limit_bytes,
+                } => set("size_bytes", size_bytes.into_py(py))
+                    .and_then(|_| set("limit_bytes", limit_bytes.into_py(py))),
+                ScraperError::FileTooSmall {
+                    size_bytes,
+                    min_bytes,
+                } => set("size_bytes", size_bytes.into_py(py))
+                    .and_then(|_| set("min_bytes", min_bytes.into_py(py))),
+                ScraperError::ChunkVerificationFailed { expected, actual } => {
+                    set("expected", expected.into_py(py))
+                        .and_then(|_| set("actual", actual.into_py(py)))
+                }
+                _ => Ok(()),
+            };
+            // Attributes on a fresh exception instance can't fail in practice
+            let _ = fields.and_then(|_| set("retryable", err.is_retryable().into_py(py)));
+        });
+        py_err
     }
 }
 
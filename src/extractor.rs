@@ -1,13 +1,20 @@
 //! Video URL extraction from web pages
 
 use crate::client::HttpClient;
+use crate::dash;
 use crate::error::{Result, ScraperError};
+use crate::hls;
+use crate::sitemap::{self, Sitemap};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
 use pyo3::prelude::*;
 use regex::Regex;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 use url::Url;
 
@@ -39,6 +46,15 @@ pub struct VideoInfo {
     pub quality: Option<String>,
     #[pyo3(get)]
     pub codec: Option<String>,
+    /// Frames per second, when a manifest lists it
+    #[pyo3(get)]
+    pub fps: Option<u32>,
+    /// Total bitrate in kbit/s, when a manifest lists it
+    #[pyo3(get)]
+    pub tbr: Option<f64>,
+    #[pyo3(get)]
+    #[serde(default)]
+    pub subtitles: Vec<SubtitleTrack>,
 }
 
 #[pymethods]
@@ -58,6 +74,31 @@ impl VideoInfo {
     }
 }
 
+/// A subtitle or caption track accompanying a video
+#[pyclass]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    #[pyo3(get)]
+    pub url: String,
+    #[pyo3(get)]
+    pub lang: Option<String>,
+    /// `subtitles` or `captions`
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub label: Option<String>,
+}
+
+#[pymethods]
+impl SubtitleTrack {
+    fn __repr__(&self) -> String {
+        format!(
+            "SubtitleTrack(url={}, lang={:?}, kind={})",
+            self.url, self.lang, self.kind
+        )
+    }
+}
+
 /// Video format/quality option
 #[pyclass]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +129,40 @@ pub struct VideoFormat {
 
 #[pymethods]
 impl VideoFormat {
+    #[new]
+    #[pyo3(signature = (
+        format_id, url, ext, quality=None, width=None, height=None, fps=None,
+        vcodec=None, acodec=None, filesize=None, tbr=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        format_id: String,
+        url: String,
+        ext: String,
+        quality: Option<String>,
+        width: Option<u32>,
+        height: Option<u32>,
+        fps: Option<u32>,
+        vcodec: Option<String>,
+        acodec: Option<String>,
+        filesize: Option<u64>,
+        tbr: Option<f64>,
+    ) -> Self {
+        Self {
+            format_id,
+            url,
+            ext,
+            quality,
+            width,
+            height,
+            fps,
+            vcodec,
+            acodec,
+            filesize,
+            tbr,
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "VideoFormat(id={}, ext={}, quality={:?}, {}x{})",
@@ -100,6 +175,18 @@ impl VideoFormat {
     }
 }
 
+impl VideoFormat {
+    /// Whether the format has a video stream, by a `vcodec` other than "none"
+    pub fn has_video(&self) -> bool {
+        self.vcodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+
+    /// Whether the format has an audio stream, by an `acodec` other than "none"
+    pub fn has_audio(&self) -> bool {
+        self.acodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+}
+
 /// Result of extraction with multiple formats
 #[pyclass]
 #[derive(Debug, Clone)]
@@ -124,6 +211,34 @@ pub struct ExtractionResult {
 
 #[pymethods]
 impl ExtractionResult {
+    #[new]
+    #[pyo3(signature = (
+        source_url, formats=Vec::new(), title=None, description=None, thumbnail=None,
+        duration=None, best_video_url=None, best_audio_url=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        source_url: String,
+        formats: Vec<VideoFormat>,
+        title: Option<String>,
+        description: Option<String>,
+        thumbnail: Option<String>,
+        duration: Option<u64>,
+        best_video_url: Option<String>,
+        best_audio_url: Option<String>,
+    ) -> Self {
+        Self {
+            source_url,
+            title,
+            description,
+            thumbnail,
+            duration,
+            formats,
+            best_video_url,
+            best_audio_url,
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "ExtractionResult(url={}, title={:?}, formats={})",
@@ -133,13 +248,18 @@ impl ExtractionResult {
         )
     }
 
-    /// Get the best quality video format
+    /// Get the best quality video format. Formats with audio win over
+    /// higher-resolution video-only ones, such as DASH representations,
+    /// which would download silent; those are only picked when nothing
+    /// carries both.
     pub fn get_best_format(&self) -> Option<VideoFormat> {
-        self.formats
-            .iter()
-            .filter(|f| f.vcodec.is_some() && f.vcodec.as_ref().map(|v| v != "none").unwrap_or(true))
-            .max_by_key(|f| f.height.unwrap_or(0))
-            .cloned()
+        self.get_best_muxed_format()
+            .or_else(|| self.highest_format(VideoFormat::has_video))
+    }
+
+    /// Get the highest-resolution format with both video and audio
+    pub fn get_best_muxed_format(&self) -> Option<VideoFormat> {
+        self.highest_format(|f| f.has_video() && f.has_audio())
     }
 
     /// Get format by quality (e.g., "1080p", "720p")
@@ -152,11 +272,101 @@ impl ExtractionResult {
     }
 }
 
+impl ExtractionResult {
+    /// The tallest format passing `wanted`
+    fn highest_format(&self, wanted: impl Fn(&VideoFormat) -> bool) -> Option<VideoFormat> {
+        self.formats
+            .iter()
+            .filter(|f| wanted(f))
+            .max_by_key(|f| f.height.unwrap_or(0))
+            .cloned()
+    }
+
+    /// Flatten into one `VideoInfo` per format, or the best video URL when
+    /// no formats were listed
+    pub fn into_video_infos(self) -> Vec<VideoInfo> {
+        let mut formats = self.formats;
+        if formats.is_empty() {
+            if let Some(url) = self.best_video_url {
+                let ext = url.rsplit('.').next().unwrap_or_default().to_string();
+                formats.push(VideoFormat::new(
+                    "best".to_string(),
+                    url,
+                    ext,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        formats
+            .into_iter()
+            .map(|f| VideoInfo {
+                url: f.url,
+                title: self.title.clone(),
+                description: self.description.clone(),
+                duration_secs: self.duration,
+                width: f.width,
+                height: f.height,
+                format: Some(f.ext),
+                file_size_bytes: f.filesize,
+                thumbnail_url: self.thumbnail.clone(),
+                source_page: self.source_url.clone(),
+                quality: f.quality,
+                codec: f.vcodec,
+                fps: f.fps,
+                tbr: f.tbr,
+                subtitles: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// How many HEAD requests `probe_sizes` keeps in flight
+const SIZE_PROBE_CONCURRENCY: usize = 8;
+
+/// How many levels of embedded players are followed from a page
+const MAX_EMBED_DEPTH: usize = 2;
+
+/// Attributes that players and lazy loaders keep video sources in. Values
+/// of the ones not naming video must look like a video URL.
+const VIDEO_DATA_ATTRIBUTES: &[&str] = &[
+    "data-src",
+    "data-file",
+    "data-video",
+    "data-video-src",
+    "data-video-url",
+    "data-mp4",
+    "data-hls",
+    "data-stream-url",
+];
+
+/// Calls whose options object lists a player's sources, as in
+/// `jwplayer("player").setup({file: ...})` or `videojs("player", {sources: ...})`
+const PLAYER_SETUP_CALLS: &[&str] = &[".setup(", "videojs("];
+
+/// `<iframe src>` fragments of video platform players worth following
+const EMBED_PLAYER_PATTERNS: &[&str] = &[
+    "youtube.com/embed",
+    "youtube-nocookie.com/embed",
+    "player.vimeo.com",
+    "dailymotion.com/embed",
+];
+
 /// Generic video URL extractor
 pub struct VideoExtractor {
     client: Arc<HttpClient>,
     video_extensions: Vec<String>,
     video_patterns: Vec<Regex>,
+    /// `file`/`src` values in a player setup object
+    setup_source_pattern: Regex,
+    sites: RwLock<SiteExtractorRegistry>,
 }
 
 impl VideoExtractor {
@@ -172,8 +382,14 @@ impl VideoExtractor {
             Regex::new(r#""(https?://[^"]+\.(mp4|webm|m3u8)[^"]*)""#).unwrap(),
         ];
 
+        // `YouTubeExtractor` is only a placeholder that finds nothing, so
+        // YouTube pages are left to a registered extractor, e.g. yt-dlp's
+        let mut sites = SiteExtractorRegistry::new();
+        sites.register_builtin(Box::new(VimeoExtractor::new(client.clone())));
+
         Self {
             client,
+            sites: RwLock::new(sites),
             video_extensions: vec![
                 "mp4".to_string(),
                 "webm".to_string(),
@@ -186,19 +402,372 @@ impl VideoExtractor {
                 "ts".to_string(),
             ],
             video_patterns,
+            setup_source_pattern: Regex::new(
+                r#"["']?\b(?:file|src)["']?\s*:\s*(?:"([^"]+)"|'([^']+)')"#,
+            )
+            .unwrap(),
         }
     }
 
-    /// Extract video URLs from a page
+    /// Add a site-specific extractor, consulted before the built-in ones and
+    /// generic extraction
+    pub fn register_site_extractor(&self, extractor: Box<dyn SiteExtractor>) {
+        self.sites.write().unwrap().register(extractor);
+    }
+
+    /// Extract video URLs from a page, using a registered site extractor
+    /// when one handles the URL. Embedded players on the page are followed
+    /// when a site extractor handles them too. Fetching and parsing fail with
+    /// `ScraperError::Timeout` after `extraction_timeout_secs`, and pages are
+    /// read up to `max_page_bytes`.
     pub async fn extract_from_url(&self, url: &str) -> Result<Vec<VideoInfo>> {
+        let timeout_secs = self.client.config().extraction_timeout_secs;
+        let extract = self.extract_at_depth(url, 0);
+        let videos = if timeout_secs > 0 {
+            tokio::time::timeout(Duration::from_secs(timeout_secs), extract)
+                .await
+                .map_err(|_| ScraperError::Timeout { timeout_secs })??
+        } else {
+            extract.await?
+        };
+
+        if self.client.config().probe_sizes {
+            Ok(self.probe_sizes(videos).await)
+        } else {
+            Ok(videos)
+        }
+    }
+
+    /// Extract from many pages, up to `concurrency` at a time, returning each
+    /// URL with its outcome in completion order. Requests to each domain still
+    /// go through its rate limiter.
+    pub async fn extract_from_urls(
+        &self,
+        urls: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<(String, Result<Vec<VideoInfo>>)> {
+        stream::iter(urls)
+            .map(|url| async move {
+                let result = self.extract_from_url(&url).await;
+                if let Err(ref e) = result {
+                    debug!("Extraction failed for {}: {}", url, e);
+                }
+                (url, result)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Fill in `file_size_bytes` with a HEAD request per video, a few at a
+    /// time. Videos whose probe fails keep `None`.
+    pub async fn probe_sizes(&self, videos: Vec<VideoInfo>) -> Vec<VideoInfo> {
+        stream::iter(videos)
+            .map(|mut video| async move {
+                if video.file_size_bytes.is_none() {
+                    match self.client.get_content_length(&video.url).await {
+                        Ok(size) => video.file_size_bytes = size,
+                        Err(e) => debug!("Size probe failed for {}: {}", video.url, e),
+                    }
+                }
+                video
+            })
+            .buffered(SIZE_PROBE_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// `extract_from_url` for a page reached through `depth` embedded players
+    fn extract_at_depth<'a>(
+        &'a self,
+        url: &'a str,
+        depth: usize,
+    ) -> BoxFuture<'a, Result<Vec<VideoInfo>>> {
+        Box::pin(async move {
+            let site = self.sites.read().unwrap().find(url);
+            if let Some(site) = site {
+                debug!("Using {} extractor for {}", site.name(), url);
+                return Ok(site.extract(url).await?.into_video_infos());
+            }
+
+            let (mut videos, embeds) = self.fetch_and_scan(url).await?;
+            if depth >= MAX_EMBED_DEPTH {
+                if !embeds.is_empty() {
+                    debug!("Not following {} embedded players in {}", embeds.len(), url);
+                }
+                return Ok(videos);
+            }
+
+            let strip_params = &self.client.config().strip_query_params;
+            let mut seen_urls: HashSet<_> =
+                videos.iter().map(|v| normalize_url(&v.url, strip_params)).collect();
+            for embed in embeds {
+                if self.sites.read().unwrap().find(&embed).is_none() {
+                    debug!("No site extractor for embedded player {}", embed);
+                    continue;
+                }
+                match self.extract_at_depth(&embed, depth + 1).await {
+                    Ok(found) => {
+                        // Embedded videos are credited to the page that embeds them
+                        videos.extend(
+                            found
+                                .into_iter()
+                                .filter(|v| seen_urls.insert(normalize_url(&v.url, strip_params)))
+                                .map(|v| VideoInfo {
+                                    source_page: url.to_string(),
+                                    ..v
+                                }),
+                        );
+                    }
+                    Err(e) => debug!("Extraction failed for embedded player {}: {}", embed, e),
+                }
+            }
+            Ok(videos)
+        })
+    }
+
+    /// Fetch a page and extract videos without any site-specific handling
+    pub async fn extract_generic(&self, url: &str) -> Result<Vec<VideoInfo>> {
+        Ok(self.fetch_and_scan(url).await?.0)
+    }
+
+    /// Fetch a page and extract its videos, along with the embedded players
+    /// it links to
+    async fn fetch_and_scan(&self, url: &str) -> Result<(Vec<VideoInfo>, Vec<String>)> {
         let response = self.client.get(url).await?;
-        let html = response.text().await?;
-        self.extract_from_html(&html, url)
+        let final_url = response.url().to_string();
+        let html = self.client.read_page(response).await?;
+
+        // Streaming manifests list their quality variants directly
+        let mut subtitles = Vec::new();
+        let formats = if hls::is_master_playlist(&html) {
+            subtitles = hls::parse_subtitle_renditions(&html, &final_url)?
+                .into_iter()
+                .map(|r| SubtitleTrack {
+                    url: r.url,
+                    lang: r.language,
+                    kind: "subtitles".to_string(),
+                    label: r.name,
+                })
+                .collect();
+            Some(hls_formats(&hls::parse_master_playlist(&html, &final_url)?))
+        } else if dash::is_mpd(&html) {
+            let representations = dash::parse_mpd(&html, &final_url)?;
+            Some(dash_formats(&representations, &final_url))
+        } else {
+            None
+        };
+
+        if let Some(formats) = formats {
+            info!("Found {} stream variants in {}", formats.len(), url);
+            let videos = formats
+                .into_iter()
+                .map(|f| VideoInfo {
+                    url: f.url,
+                    title: None,
+                    description: None,
+                    duration_secs: None,
+                    width: f.width,
+                    height: f.height,
+                    format: Some(f.ext),
+                    file_size_bytes: None,
+                    thumbnail_url: None,
+                    source_page: url.to_string(),
+                    quality: f.quality,
+                    codec: f.vcodec,
+                    fps: f.fps,
+                    tbr: f.tbr,
+                    subtitles: subtitles.clone(),
+                })
+                .collect();
+            return Ok((videos, Vec::new()));
+        }
+
+        self.scan_page(&html, url, &final_url)
+    }
+
+    /// Fetch a DASH manifest and list its representations, picking the
+    /// highest-bandwidth video-only and audio-only streams
+    pub async fn extract_dash(&self, url: &str) -> Result<ExtractionResult> {
+        let response = self.client.get(url).await?;
+        let base = response.url().to_string();
+        let text = self.client.read_page(response).await?;
+        let representations = dash::parse_mpd(&text, &base)?;
+
+        let best_url = |wanted: fn(&dash::Representation) -> bool| {
+            representations
+                .iter()
+                .filter(|r| wanted(r))
+                .max_by_key(|r| r.bandwidth.unwrap_or(0))
+                .map(|r| dash_format_url(r, url))
+        };
+        let best_video_url = best_url(dash::Representation::is_video);
+        let best_audio_url = best_url(dash::Representation::is_audio);
+
+        Ok(ExtractionResult {
+            source_url: url.to_string(),
+            title: None,
+            description: None,
+            thumbnail: None,
+            duration: None,
+            formats: dash_formats(&representations, url),
+            best_video_url,
+            best_audio_url,
+        })
+    }
+
+    /// Fetch an HLS master playlist and list its variants as formats
+    pub async fn extract_hls_formats(&self, url: &str) -> Result<Vec<VideoFormat>> {
+        let response = self.client.get(url).await?;
+        let base = response.url().to_string();
+        let text = self.client.read_page(response).await?;
+        Ok(hls_formats(&hls::parse_master_playlist(&text, &base)?))
+    }
+
+    /// Fetch a sitemap and list the videos its `<video:video>` entries point
+    /// at, following sitemap indexes into the sitemaps they nest. At most
+    /// `max_sitemaps` files are fetched, and nested sitemaps that fail to
+    /// load are skipped.
+    pub async fn extract_from_sitemap(&self, url: &str) -> Result<Vec<VideoInfo>> {
+        let limit = self.client.config().max_sitemaps.max(1);
+        let strip_params = &self.client.config().strip_query_params;
+        let mut queue = VecDeque::from([url.to_string()]);
+        let mut fetched = HashSet::new();
+        let mut seen_urls = HashSet::new();
+        let mut videos = Vec::new();
+
+        while let Some(sitemap_url) = queue.pop_front() {
+            if fetched.contains(&sitemap_url) {
+                continue;
+            }
+            if fetched.len() == limit {
+                warn!("Stopped after {} sitemaps under {}", limit, url);
+                break;
+            }
+            fetched.insert(sitemap_url.clone());
+
+            let sitemap = match self.fetch_sitemap(&sitemap_url).await {
+                Ok(sitemap) => sitemap,
+                Err(e) if sitemap_url != url => {
+                    warn!("Skipping sitemap {}: {}", sitemap_url, e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let entries = match sitemap {
+                Sitemap::Index(nested) => {
+                    queue.extend(nested);
+                    continue;
+                }
+                Sitemap::Urls(entries) => entries,
+            };
+
+            for entry in entries {
+                if !seen_urls.insert(normalize_url(&entry.content_url, strip_params)) {
+                    continue;
+                }
+                let format = self.extract_format(&entry.content_url);
+                let codec = format.as_deref().and_then(guess_codec);
+                videos.push(VideoInfo {
+                    url: entry.content_url,
+                    title: entry.title,
+                    description: entry.description,
+                    duration_secs: entry.duration_secs,
+                    width: None,
+                    height: None,
+                    format,
+                    file_size_bytes: None,
+                    thumbnail_url: entry.thumbnail_url,
+                    source_page: entry.page_url,
+                    quality: None,
+                    codec,
+                    fps: None,
+                    tbr: None,
+                    subtitles: Vec::new(),
+                });
+            }
+        }
+
+        info!("Found {} videos in {} sitemaps under {}", videos.len(), fetched.len(), url);
+        Ok(videos)
+    }
+
+    async fn fetch_sitemap(&self, url: &str) -> Result<Sitemap> {
+        let response = self.client.get(url).await?;
+        let base = response.url().to_string();
+        let text = self.client.read_page(response).await?;
+        sitemap::parse_sitemap(&text, &base)
+    }
+
+    /// Fetch a page and list the pages it links to, for crawling
+    pub async fn extract_links_from_url(&self, url: &str) -> Result<Vec<String>> {
+        let response = self.client.get(url).await?;
+        let final_url = response.url().to_string();
+        let html = self.client.read_page(response).await?;
+        Ok(self.extract_links(&html, &final_url))
+    }
+
+    /// Absolute http(s) targets of a page's `<a href>` links, excluding direct
+    /// video links, with fragments removed and duplicates dropped
+    pub fn extract_links(&self, html: &str, source_url: &str) -> Vec<String> {
+        let document = Html::parse_document(html);
+        let base_url = self.base_url(&document, source_url);
+        let link_selector = Selector::parse("a[href]").unwrap();
+
+        let mut seen = HashSet::new();
+        document
+            .select(&link_selector)
+            .filter_map(|link| link.value().attr("href"))
+            .filter(|href| !self.is_video_url(href))
+            .filter_map(|href| Url::parse(&self.resolve_url(href, &base_url).ok()?).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .map(|mut url| {
+                url.set_fragment(None);
+                url.to_string()
+            })
+            .filter(|url| seen.insert(url.clone()))
+            .collect()
+    }
+
+    /// The URL relative links resolve against: `<base href>` when the page
+    /// declares one, otherwise the page itself
+    fn base_url(&self, document: &Html, source_url: &str) -> String {
+        let base_selector = Selector::parse("base[href]").unwrap();
+        document
+            .select(&base_selector)
+            .next()
+            .and_then(|el| el.value().attr("href"))
+            .and_then(|href| self.resolve_url(href, source_url).ok())
+            .unwrap_or_else(|| source_url.to_string())
     }
 
     /// Extract video URLs from HTML content
     pub fn extract_from_html(&self, html: &str, source_url: &str) -> Result<Vec<VideoInfo>> {
+        self.extract_from_page(html, source_url, source_url)
+    }
+
+    /// Extract video URLs from a page fetched from `source_url` but served
+    /// from `page_url` after redirects. Relative links resolve against
+    /// `page_url`; videos keep `source_url` as their source page.
+    pub fn extract_from_page(
+        &self,
+        html: &str,
+        source_url: &str,
+        page_url: &str,
+    ) -> Result<Vec<VideoInfo>> {
+        Ok(self.scan_page(html, source_url, page_url)?.0)
+    }
+
+    /// `extract_from_page`, also returning the absolute URLs of embedded
+    /// video platform players found in `<iframe>`s
+    fn scan_page(
+        &self,
+        html: &str,
+        source_url: &str,
+        page_url: &str,
+    ) -> Result<(Vec<VideoInfo>, Vec<String>)> {
         let mut videos = Vec::new();
+        let mut embeds = Vec::new();
         let mut seen_urls = HashSet::new();
 
         // Parse HTML
@@ -211,31 +780,84 @@ impl VideoExtractor {
             .next()
             .map(|el| el.text().collect::<String>());
 
+        let base_url = self.base_url(&document, page_url);
+
+        // Extract from schema.org VideoObject JSON-LD first, since it carries
+        // the richest metadata and later duplicates are skipped
+        let ld_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        for script in document.select(&ld_selector) {
+            let text = script.text().collect::<String>();
+            let value: serde_json::Value = match serde_json::from_str(text.trim()) {
+                Ok(value) => value,
+                Err(e) => {
+                    debug!("Skipping unparseable JSON-LD block: {}", e);
+                    continue;
+                }
+            };
+
+            let mut objects = Vec::new();
+            collect_video_objects(&value, &mut objects);
+            for object in objects {
+                if let Some(video) = self.video_from_json_ld(
+                    object, &base_url, source_url, &mut seen_urls,
+                ) {
+                    videos.push(video);
+                }
+            }
+        }
+
         // Extract from <video> elements
         let video_selector = Selector::parse("video").unwrap();
         for video_el in document.select(&video_selector) {
+            let (width, height, duration) = media_attributes(video_el.value());
+            // Subtitle and caption tracks apply to every source of this <video>
+            let subtitles = self.subtitle_tracks(video_el, &base_url);
+
             // Check src attribute
             if let Some(src) = video_el.value().attr("src") {
-                if let Some(video) = self.create_video_info(src, source_url, &page_title, &mut seen_urls) {
+                if let Some(mut video) = self.create_video_info(
+                    src, &base_url, source_url, &page_title, &mut seen_urls,
+                ) {
+                    video.width = width;
+                    video.height = height;
+                    video.duration_secs = duration;
+                    video.subtitles = subtitles.clone();
                     videos.push(video);
                 }
             }
 
             // Check poster for thumbnail
             let thumbnail = video_el.value().attr("poster").map(|s| {
-                self.resolve_url(s, source_url).unwrap_or_else(|_| s.to_string())
+                self.resolve_url(s, &base_url).unwrap_or_else(|_| s.to_string())
             });
 
             // Check <source> children
             let source_selector = Selector::parse("source").unwrap();
             for source_el in video_el.select(&source_selector) {
                 if let Some(src) = source_el.value().attr("src") {
-                    if let Some(mut video) = self.create_video_info(src, source_url, &page_title, &mut seen_urls) {
+                    if let Some(mut video) = self.create_video_info(
+                        src, &base_url, source_url, &page_title, &mut seen_urls,
+                    ) {
                         video.thumbnail_url = thumbnail.clone();
+                        video.subtitles = subtitles.clone();
+
+                        // A <source> may describe itself; otherwise it inherits from <video>
+                        let (src_width, src_height, src_duration) =
+                            media_attributes(source_el.value());
+                        video.width = src_width.or(width);
+                        video.height = src_height.or(height);
+                        video.duration_secs = src_duration.or(duration);
                         
-                        // Extract type/format
+                        // Extract type/format, and the codec from e.g.
+                        // `video/mp4; codecs="avc1.64001f, mp4a.40.2"`
                         if let Some(type_attr) = source_el.value().attr("type") {
                             video.format = Some(type_attr.to_string());
+                            let codecs = type_attr
+                                .split_once("codecs=")
+                                .map(|(_, codecs)| codecs.trim_matches(|c| c == '"' || c == '\''));
+                            if let Some(codec) = codecs.and_then(hls::first_video_codec) {
+                                video.codec = Some(codec.to_string());
+                            }
                         }
                         
                         videos.push(video);
@@ -244,17 +866,80 @@ impl VideoExtractor {
             }
         }
 
+        // Extract from data-* attributes of players and lazy-loaded elements
+        let data_selector = Selector::parse(
+            &VIDEO_DATA_ATTRIBUTES
+                .iter()
+                .map(|attr| format!("[{}]", attr))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+        .unwrap();
+        for element in document.select(&data_selector) {
+            for attr in VIDEO_DATA_ATTRIBUTES {
+                let Some(src) = element.value().attr(attr) else {
+                    continue;
+                };
+                let generic = matches!(*attr, "data-src" | "data-file");
+                if generic && !self.is_video_url(src) {
+                    continue;
+                }
+                if let Some(video) = self.create_video_info(
+                    src, &base_url, source_url, &page_title, &mut seen_urls,
+                ) {
+                    videos.push(video);
+                }
+            }
+        }
+
+        // Extract sources from JW Player and Video.js setup calls, and from
+        // Video.js `data-setup` options
+        let script_selector = Selector::parse("script:not([src])").unwrap();
+        let setup_selector = Selector::parse("[data-setup]").unwrap();
+        let scripts: Vec<String> = document
+            .select(&script_selector)
+            .map(|script| script.text().collect())
+            .collect();
+        let setups = scripts
+            .iter()
+            .flat_map(|script| player_setup_objects(script))
+            .chain(
+                document
+                    .select(&setup_selector)
+                    .filter_map(|el| el.value().attr("data-setup")),
+            );
+        for setup in setups {
+            for cap in self.setup_source_pattern.captures_iter(setup) {
+                let Some(src) = cap.get(1).or_else(|| cap.get(2)).map(|m| m.as_str()) else {
+                    continue;
+                };
+                // Caption and thumbnail tracks use the same keys; stream URLs
+                // may have no extension at all
+                let file_name = src.split(['?', '#']).next().unwrap_or_default();
+                let file_name = file_name.rsplit('/').next().unwrap_or_default();
+                if file_name.contains('.') && !self.is_video_url(src) {
+                    continue;
+                }
+                if let Some(video) = self.create_video_info(
+                    src, &base_url, source_url, &page_title, &mut seen_urls,
+                ) {
+                    videos.push(video);
+                }
+            }
+        }
+
         // Extract from <iframe> elements (embedded players)
         let iframe_selector = Selector::parse("iframe").unwrap();
         for iframe in document.select(&iframe_selector) {
             if let Some(src) = iframe.value().attr("src") {
                 // Check for video platform embeds
-                if src.contains("youtube.com/embed")
-                    || src.contains("player.vimeo.com")
-                    || src.contains("dailymotion.com/embed")
-                {
+                if EMBED_PLAYER_PATTERNS.iter().any(|p| src.contains(p)) {
                     debug!("Found embedded video player: {}", src);
-                    // These require special handling - note for Python layer
+                    if let Ok(src) = self.resolve_url(src, &base_url) {
+                        if !embeds.contains(&src) {
+                            embeds.push(src);
+                        }
+                    }
                 }
             }
         }
@@ -264,25 +949,76 @@ impl VideoExtractor {
         for link in document.select(&link_selector) {
             if let Some(href) = link.value().attr("href") {
                 if self.is_video_url(href) {
-                    if let Some(video) = self.create_video_info(href, source_url, &page_title, &mut seen_urls) {
+                    if let Some(video) = self.create_video_info(
+                        href, &base_url, source_url, &page_title, &mut seen_urls,
+                    ) {
                         videos.push(video);
                     }
                 }
             }
         }
 
-        // Extract from meta tags (og:video, etc.)
+        // Extract from meta tags (og:video, twitter:player:stream). Open Graph
+        // structured properties like og:video:width describe the og:video
+        // before them.
         let meta_selector = Selector::parse("meta").unwrap();
-        for meta in document.select(&meta_selector) {
-            let property = meta.value().attr("property").or_else(|| meta.value().attr("name"));
-            let content = meta.value().attr("content");
+        let metas: Vec<_> = document
+            .select(&meta_selector)
+            .filter_map(|meta| {
+                let el = meta.value();
+                Some((el.attr("property").or_else(|| el.attr("name"))?, el.attr("content")?))
+            })
+            .collect();
+        let page_thumbnail = ["og:image", "og:image:url", "og:image:secure_url", "twitter:image"]
+            .iter()
+            .find_map(|name| metas.iter().find(|(prop, _)| prop == name))
+            .and_then(|(_, content)| self.resolve_url(content, &base_url).ok());
 
-            if let (Some(prop), Some(content)) = (property, content) {
-                if prop == "og:video" || prop == "og:video:url" || prop == "og:video:secure_url" {
-                    if let Some(video) = self.create_video_info(content, source_url, &page_title, &mut seen_urls) {
+        let strip_params = &self.client.config().strip_query_params;
+        let mut meta_videos = HashSet::new();
+        let mut current = None;
+        for &(prop, content) in &metas {
+            match prop {
+                "og:video" | "og:video:url" | "og:video:secure_url" | "twitter:player:stream" => {
+                    let Ok(url) = self.resolve_url(content, &base_url) else {
+                        continue;
+                    };
+                    if let Some(video) = self.create_video_info(
+                        &url, &base_url, source_url, &page_title, &mut seen_urls,
+                    ) {
                         videos.push(video);
                     }
+                    // A URL already found elsewhere still gets the metadata
+                    let key = normalize_url(&url, strip_params);
+                    current = videos
+                        .iter()
+                        .position(|v| normalize_url(&v.url, strip_params) == key);
+                    meta_videos.extend(current);
+                }
+                "og:video:width" => {
+                    if let Some(video) = current.map(|i| &mut videos[i]) {
+                        video.width = video.width.or(parse_dimension(content));
+                    }
                 }
+                "og:video:height" => {
+                    if let Some(video) = current.map(|i| &mut videos[i]) {
+                        video.height = video.height.or(parse_dimension(content));
+                    }
+                }
+                "og:video:duration" => {
+                    if let Some(video) = current.map(|i| &mut videos[i]) {
+                        let duration = content.trim().parse::<f64>().ok().filter(|d| *d >= 0.0);
+                        video.duration_secs =
+                            video.duration_secs.or(duration.map(|d| d.round() as u64));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for i in meta_videos {
+            let video = &mut videos[i];
+            if video.thumbnail_url.is_none() {
+                video.thumbnail_url = page_thumbnail.clone();
             }
         }
 
@@ -292,7 +1028,9 @@ impl VideoExtractor {
                 if let Some(url_match) = cap.get(1).or_else(|| cap.get(0)) {
                     let url = url_match.as_str();
                     if self.is_video_url(url) {
-                        if let Some(video) = self.create_video_info(url, source_url, &page_title, &mut seen_urls) {
+                        if let Some(video) = self.create_video_info(
+                            url, &base_url, source_url, &page_title, &mut seen_urls,
+                        ) {
                             videos.push(video);
                         }
                     }
@@ -300,31 +1038,141 @@ impl VideoExtractor {
             }
         }
 
+        if videos.is_empty() && embeds.is_empty() {
+            self.check_unavailable(&document, html, source_url)?;
+        }
+
         info!("Extracted {} video URLs from {}", videos.len(), source_url);
-        Ok(videos)
+        Ok((videos, embeds))
+    }
+
+    /// Tell an anti-bot interstitial (`AccessDenied`) or a "video removed"
+    /// page served with a 200 (`NotFound`) apart from a page that simply has
+    /// no videos, using the config's blocked and removed page markers
+    fn check_unavailable(&self, document: &Html, html: &str, source_url: &str) -> Result<()> {
+        let config = self.client.config();
+        let text = html.to_lowercase();
+        let find_text = |patterns: &[String]| {
+            patterns
+                .iter()
+                .find(|p| !p.is_empty() && text.contains(&p.to_lowercase()))
+                .cloned()
+        };
+
+        let blocked = find_text(&config.blocked_page_patterns).or_else(|| {
+            config
+                .blocked_page_selectors
+                .iter()
+                .find(|s| match Selector::parse(s) {
+                    Ok(selector) => document.select(&selector).next().is_some(),
+                    Err(_) => {
+                        warn!("Ignoring invalid blocked page selector: {}", s);
+                        false
+                    }
+                })
+                .cloned()
+        });
+        if let Some(marker) = blocked {
+            return Err(ScraperError::AccessDenied(format!(
+                "{} is an anti-bot page (matched {:?})",
+                source_url, marker
+            )));
+        }
+
+        if let Some(marker) = find_text(&config.removed_page_patterns) {
+            return Err(ScraperError::NotFound(format!(
+                "{} reports the video unavailable (matched {:?})",
+                source_url, marker
+            )));
+        }
+        Ok(())
+    }
+
+    /// Collect `<track>` children of kind `subtitles` (the default) or `captions`
+    fn subtitle_tracks(&self, video_el: scraper::ElementRef, base_url: &str) -> Vec<SubtitleTrack> {
+        let track_selector = Selector::parse("track[src]").unwrap();
+        video_el
+            .select(&track_selector)
+            .filter_map(|track| {
+                let el = track.value();
+                let kind = el.attr("kind").unwrap_or("subtitles").to_lowercase();
+                if kind != "subtitles" && kind != "captions" {
+                    return None;
+                }
+                let url = self.resolve_url(el.attr("src")?, base_url).ok()?;
+                Some(SubtitleTrack {
+                    url,
+                    lang: el.attr("srclang").map(|l| l.to_string()),
+                    kind,
+                    label: el.attr("label").map(|l| l.to_string()),
+                })
+            })
+            .collect()
+    }
+
+    /// Build a `VideoInfo` from a schema.org `VideoObject`
+    fn video_from_json_ld(
+        &self,
+        object: &serde_json::Value,
+        base_url: &str,
+        source_url: &str,
+        seen_urls: &mut HashSet<String>,
+    ) -> Option<VideoInfo> {
+        let content_url = object.get("contentUrl").and_then(json_ld_url)?;
+        let title = object.get("name").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let mut video =
+            self.create_video_info(&content_url, base_url, source_url, &title, seen_urls)?;
+
+        video.description = object
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        video.duration_secs = object
+            .get("duration")
+            .and_then(|v| v.as_str())
+            .and_then(dash::parse_duration)
+            .map(|secs| secs.round() as u64);
+        video.thumbnail_url = object
+            .get("thumbnailUrl")
+            .or_else(|| object.get("thumbnail"))
+            .and_then(json_ld_url)
+            .map(|u| self.resolve_url(&u, base_url).unwrap_or(u));
+        video.width = object.get("width").and_then(json_ld_number);
+        video.height = object.get("height").and_then(json_ld_number);
+        video.file_size_bytes = object
+            .get("contentSize")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.trim().parse().ok());
+        if let Some(format) = object.get("encodingFormat").and_then(|v| v.as_str()) {
+            video.format = Some(format.to_string());
+        }
+
+        Some(video)
     }
 
     fn create_video_info(
         &self,
         url: &str,
+        base_url: &str,
         source_url: &str,
         page_title: &Option<String>,
         seen_urls: &mut HashSet<String>,
     ) -> Option<VideoInfo> {
         // Resolve relative URLs
-        let resolved = match self.resolve_url(url, source_url) {
+        let resolved = match self.resolve_url(url, base_url) {
             Ok(u) => u,
             Err(_) => return None,
         };
 
-        // Skip if already seen
-        if seen_urls.contains(&resolved) {
+        // Skip if already seen, ignoring query order and tracking parameters
+        let key = normalize_url(&resolved, &self.client.config().strip_query_params);
+        if !seen_urls.insert(key) {
             return None;
         }
-        seen_urls.insert(resolved.clone());
 
         // Extract format from URL
         let format = self.extract_format(&resolved);
+        let codec = format.as_deref().and_then(guess_codec);
 
         Some(VideoInfo {
             url: resolved,
@@ -338,7 +1186,10 @@ impl VideoExtractor {
             thumbnail_url: None,
             source_page: source_url.to_string(),
             quality: None,
-            codec: None,
+            codec,
+            fps: None,
+            tbr: None,
+            subtitles: Vec::new(),
         })
     }
 
@@ -401,6 +1252,276 @@ impl VideoExtractor {
     }
 }
 
+/// The options objects passed to the player setup calls in a script, found
+/// by matching braces outside of string literals
+fn player_setup_objects(script: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    for call in PLAYER_SETUP_CALLS {
+        for (at, _) in script.match_indices(call) {
+            let args = &script[at + call.len()..];
+            // The object is the first argument, or follows a player id
+            let Some(start) = args.find(['{', ')', ';']).filter(|&i| args[i..].starts_with('{'))
+            else {
+                continue;
+            };
+
+            let mut depth = 0;
+            let mut quote = None;
+            let mut escaped = false;
+            for (i, c) in args[start..].char_indices() {
+                match (quote, c) {
+                    (Some(_), _) if escaped => escaped = false,
+                    (Some(_), '\\') => escaped = true,
+                    (Some(q), c) if c == q => quote = None,
+                    (Some(_), _) => {}
+                    (None, '"' | '\'' | '`') => quote = Some(c),
+                    (None, '{') => depth += 1,
+                    (None, '}') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            objects.push(&args[start..start + i + 1]);
+                            break;
+                        }
+                    }
+                    (None, _) => {}
+                }
+            }
+        }
+    }
+    objects
+}
+
+/// Canonical form of a URL for deduplication: lowercase host, no fragment,
+/// query parameters sorted with any in `strip_params` removed. A trailing
+/// `*` in `strip_params` matches by prefix, e.g. `utm_*`.
+pub fn normalize_url(url: &str, strip_params: &[String]) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    let is_stripped = |name: &str| {
+        strip_params.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == p,
+        })
+    };
+    let mut params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| !is_stripped(name))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(&params);
+    }
+    parsed.set_fragment(None);
+
+    if let Some(host) = parsed.host_str().map(|h| h.to_lowercase()) {
+        let _ = parsed.set_host(Some(&host));
+    }
+
+    parsed.to_string()
+}
+
+/// Read `width`, `height`, and `duration`/`data-duration` off a media element
+fn media_attributes(element: &scraper::node::Element) -> (Option<u32>, Option<u32>, Option<u64>) {
+    let width = element.attr("width").and_then(parse_dimension);
+    let height = element.attr("height").and_then(parse_dimension);
+    let duration = element
+        .attr("duration")
+        .or_else(|| element.attr("data-duration"))
+        .and_then(|d| d.trim().trim_end_matches('s').parse::<f64>().ok())
+        .filter(|d| d.is_finite() && *d >= 0.0)
+        .map(|d| d.round() as u64);
+    (width, height, duration)
+}
+
+/// Common name for a codec given as an RFC 6381 string or a plain name, e.g.
+/// `avc1.64001f` -> `h264` and `av01.0.05M.08` -> `av1`
+pub fn codec_family(codec: &str) -> String {
+    let codec = codec.trim().to_ascii_lowercase();
+    let name = codec.split('.').next().unwrap_or_default();
+    match name {
+        "avc1" | "avc3" | "avc" | "h264" => "h264",
+        // Dolby Vision profiles used for streaming are HEVC-based
+        "hvc1" | "hev1" | "hevc" | "h265" | "dvh1" | "dvhe" => "h265",
+        "av01" | "av1" => "av1",
+        "vp09" | "vp9" => "vp9",
+        "vp08" | "vp8" => "vp8",
+        other => other,
+    }
+    .to_string()
+}
+
+/// File extensions for a media MIME type, the usual one first. Parameters
+/// such as `codecs=` are ignored, and unknown or generic types like
+/// `application/octet-stream` yield none.
+pub fn extensions_for_mime(content_type: &str) -> &'static [&'static str] {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    match mime.to_ascii_lowercase().as_str() {
+        "video/mp4" => &["mp4", "m4v"],
+        "video/x-m4v" => &["m4v", "mp4"],
+        "video/webm" | "audio/webm" => &["webm"],
+        "video/x-matroska" => &["mkv"],
+        "video/quicktime" => &["mov"],
+        "video/x-msvideo" => &["avi"],
+        "video/x-flv" => &["flv"],
+        "video/mp2t" => &["ts"],
+        "video/3gpp" => &["3gp"],
+        "video/ogg" => &["ogv"],
+        "audio/mp4" => &["m4a"],
+        "audio/mpeg" => &["mp3"],
+        _ => &[],
+    }
+}
+
+/// The codec a container most likely holds, as a hint when nothing better
+/// is known
+fn guess_codec(format: &str) -> Option<String> {
+    match format {
+        "mp4" | "m4v" | "mov" => Some("h264".to_string()),
+        "webm" => Some("vp9".to_string()),
+        _ => None,
+    }
+}
+
+/// Parse a pixel dimension such as `1920` or `1920px`
+fn parse_dimension(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let value = value.strip_suffix("px").unwrap_or(value).trim();
+    value
+        .parse::<u32>()
+        .ok()
+        .or_else(|| value.parse::<f64>().ok().filter(|v| *v >= 0.0).map(|v| v as u32))
+}
+
+/// Find every `VideoObject` in a JSON-LD document, including inside `@graph`
+/// arrays and nested properties such as an article's `video`
+fn collect_video_objects<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a serde_json::Value>) {
+    match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_video_objects(item, out);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            let is_video = match map.get("@type") {
+                Some(serde_json::Value::String(t)) => t == "VideoObject",
+                Some(serde_json::Value::Array(types)) => {
+                    types.iter().any(|t| t.as_str() == Some("VideoObject"))
+                }
+                _ => false,
+            };
+            if is_video {
+                out.push(value);
+            }
+            for child in map.values() {
+                collect_video_objects(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A URL given as a string, an array of strings, or an object with `url`
+fn json_ld_url(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(items) => items.iter().find_map(json_ld_url),
+        serde_json::Value::Object(map) => map.get("url").and_then(json_ld_url),
+        _ => None,
+    }
+}
+
+/// A dimension given as a number, a numeric string, or a `QuantitativeValue`
+fn json_ld_number(value: &serde_json::Value) -> Option<u32> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        serde_json::Value::Object(map) => map.get("value").and_then(json_ld_number),
+        _ => None,
+    }
+}
+
+/// One `VideoFormat` per HLS variant, with resolution, bitrate, and codecs
+fn hls_formats(variants: &[hls::Variant]) -> Vec<VideoFormat> {
+    variants
+        .iter()
+        .map(|v| {
+            let tbr = v.bandwidth.map(|b| b as f64 / 1000.0);
+            VideoFormat {
+                format_id: match tbr {
+                    Some(tbr) => format!("hls-{}", tbr.round() as u64),
+                    None => "hls".to_string(),
+                },
+                url: v.url.clone(),
+                ext: "m3u8".to_string(),
+                quality: v.height.map(|h| format!("{}p", h)),
+                width: v.width,
+                height: v.height,
+                fps: v.frame_rate.map(|f| f.round() as u32),
+                vcodec: v.video_codec().map(|c| c.to_string()),
+                acodec: v.audio_codec().map(|c| c.to_string()),
+                filesize: None,
+                tbr,
+            }
+        })
+        .collect()
+}
+
+/// One `VideoFormat` per DASH representation. Audio and video are separate
+/// streams, so each format has only one of `vcodec`/`acodec` set.
+fn dash_formats(representations: &[dash::Representation], manifest_url: &str) -> Vec<VideoFormat> {
+    representations
+        .iter()
+        .map(|r| {
+            let ext = if r.is_segmented() {
+                "mpd".to_string()
+            } else {
+                match r.mime_type.as_deref().and_then(|m| m.split('/').nth(1)) {
+                    Some(subtype) if subtype != "*" => subtype.to_string(),
+                    _ => "mp4".to_string(),
+                }
+            };
+            let (vcodec, acodec) = if r.is_audio() {
+                (Some("none".to_string()), r.codecs.clone())
+            } else {
+                (r.codecs.clone(), Some("none".to_string()))
+            };
+
+            VideoFormat {
+                format_id: format!("dash-{}", r.id),
+                url: dash_format_url(r, manifest_url),
+                ext,
+                quality: r.height.map(|h| format!("{}p", h)),
+                width: r.width,
+                height: r.height,
+                fps: r.frame_rate.map(|f| f.round() as u32),
+                vcodec,
+                acodec,
+                filesize: None,
+                tbr: r.bandwidth.map(|b| b as f64 / 1000.0),
+            }
+        })
+        .collect()
+}
+
+/// Single-file representations are fetched directly; segmented ones need
+/// the manifest
+fn dash_format_url(representation: &dash::Representation, manifest_url: &str) -> String {
+    if representation.is_segmented() {
+        manifest_url.to_string()
+    } else {
+        representation.url.clone()
+    }
+}
+
+/// `(url, videos, error)` for one page passed to `PyVideoExtractor::extract_from_urls`
+type ExtractOutcome = (String, Option<Vec<VideoInfo>>, Option<String>);
+
 /// Python-exposed video extractor
 #[pyclass]
 pub struct PyVideoExtractor {
@@ -414,9 +1535,7 @@ impl PyVideoExtractor {
     #[pyo3(signature = (config=None))]
     pub fn new(config: Option<&crate::config::ScraperConfig>) -> PyResult<Self> {
         let config = config.cloned().unwrap_or_default();
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+        let runtime = crate::runtime::shared_runtime(config.worker_threads)?;
 
         let client = HttpClient::new(&config).map_err(|e| {
             pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create client: {}", e))
@@ -426,27 +1545,113 @@ impl PyVideoExtractor {
 
         Ok(Self {
             inner: Arc::new(extractor),
-            runtime: Arc::new(runtime),
+            runtime,
+        })
+    }
+
+    /// Extract from many pages concurrently. Returns a `(url, videos, error)`
+    /// tuple per URL, in completion order, with exactly one of `videos` and
+    /// `error` set.
+    #[pyo3(signature = (urls, concurrency=8))]
+    pub fn extract_from_urls(
+        &self,
+        py: Python<'_>,
+        urls: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<ExtractOutcome> {
+        let extractor = self.inner.clone();
+
+        // Release the GIL so Python site extractors can take it
+        let runtime = self.runtime.clone();
+        py.allow_threads(move || {
+            runtime.block_on(async move {
+                extractor
+                    .extract_from_urls(urls, concurrency)
+                    .await
+                    .into_iter()
+                    .map(|(url, result)| match result {
+                        Ok(videos) => (url, Some(videos), None),
+                        Err(e) => (url, None, Some(e.to_string())),
+                    })
+                    .collect()
+            })
         })
     }
 
     /// Extract video URLs from a web page
-    pub fn extract_from_url(&self, url: &str) -> PyResult<Vec<VideoInfo>> {
+    pub fn extract_from_url(&self, py: Python<'_>, url: &str) -> PyResult<Vec<VideoInfo>> {
         let extractor = self.inner.clone();
         let url = url.to_string();
 
-        self.runtime.block_on(async move {
-            extractor.extract_from_url(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
+        // Release the GIL so Python site extractors can take it
+        let runtime = self.runtime.clone();
+        py.allow_threads(move || {
+            runtime.block_on(async move {
+                extractor.extract_from_url(&url).await.map_err(PyErr::from)
             })
         })
     }
 
+    /// Async variant of `extract_from_url` for use inside an asyncio event loop
+    pub fn extract_from_url_async<'py>(&self, py: Python<'py>, url: &str) -> PyResult<&'py PyAny> {
+        let extractor = self.inner.clone();
+        let url = url.to_string();
+
+        crate::runtime::into_awaitable(py, async move {
+            extractor.extract_from_url(&url).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Register a Python site extractor with `can_handle(url)` and
+    /// `extract(url) -> ExtractionResult` methods. It is tried before the
+    /// built-in extractors and generic extraction, after any previously
+    /// registered extractors.
+    pub fn register_site_extractor(&self, py: Python<'_>, handler: PyObject) -> PyResult<()> {
+        let handler_ref = handler.as_ref(py);
+        let name = match handler_ref.getattr("name") {
+            Ok(name) if name.is_callable() => name.call0()?.extract()?,
+            Ok(name) => name.extract()?,
+            Err(_) => handler_ref.get_type().name()?.to_string(),
+        };
+
+        self.inner
+            .register_site_extractor(Box::new(PySiteExtractor { name, handler }));
+        Ok(())
+    }
+
+    /// List the videos of a sitemap, following nested sitemaps
+    pub fn extract_from_sitemap(&self, url: &str) -> PyResult<Vec<VideoInfo>> {
+        let extractor = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move {
+            extractor.extract_from_sitemap(&url).await.map_err(PyErr::from)
+        })
+    }
+
+    /// List the representations of a DASH manifest
+    pub fn extract_dash(&self, url: &str) -> PyResult<ExtractionResult> {
+        let extractor = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move {
+            extractor.extract_dash(&url).await.map_err(PyErr::from)
+        })
+    }
+
+    /// List the quality variants of an HLS master playlist
+    pub fn extract_hls_formats(&self, url: &str) -> PyResult<Vec<VideoFormat>> {
+        let extractor = self.inner.clone();
+        let url = url.to_string();
+
+        self.runtime.block_on(async move {
+            extractor.extract_hls_formats(&url).await.map_err(PyErr::from)
+        })
+    }
+
     /// Extract video URLs from HTML content
     pub fn extract_from_html(&self, html: &str, source_url: &str) -> PyResult<Vec<VideoInfo>> {
-        self.inner.extract_from_html(html, source_url).map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-        })
+        self.inner.extract_from_html(html, source_url).map_err(PyErr::from)
     }
 
     /// Extract quality information from a URL
@@ -456,14 +1661,93 @@ impl PyVideoExtractor {
 }
 
 /// Site-specific extractor trait for platforms like YouTube
+#[async_trait]
 pub trait SiteExtractor: Send + Sync {
     fn name(&self) -> &str;
     fn can_handle(&self, url: &str) -> bool;
-    fn extract(&self, url: &str) -> Result<ExtractionResult>;
+    async fn extract(&self, url: &str) -> Result<ExtractionResult>;
+}
+
+/// Routes URLs to the first registered site extractor that can handle them,
+/// trying user-registered extractors before the built-in ones
+#[derive(Default)]
+pub struct SiteExtractorRegistry {
+    extractors: Vec<Arc<dyn SiteExtractor>>,
+    /// How many of `extractors`, at the front, were registered by users
+    registered: usize,
+}
+
+impl SiteExtractorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an extractor, which overrides the built-in ones; among the added
+    /// extractors, earlier registrations take precedence
+    pub fn register(&mut self, extractor: Box<dyn SiteExtractor>) {
+        debug!("Registered site extractor: {}", extractor.name());
+        self.extractors.insert(self.registered, Arc::from(extractor));
+        self.registered += 1;
+    }
+
+    /// Add a built-in extractor, used only for URLs no added extractor handles
+    pub fn register_builtin(&mut self, extractor: Box<dyn SiteExtractor>) {
+        self.extractors.push(Arc::from(extractor));
+    }
+
+    /// The first extractor whose `can_handle` accepts `url`, shared so it can
+    /// be used without holding on to the registry
+    pub fn find(&self, url: &str) -> Option<Arc<dyn SiteExtractor>> {
+        self.extractors.iter().find(|e| e.can_handle(url)).cloned()
+    }
+
+    /// Extract with the matching site extractor, or `None` so the caller can
+    /// fall back to generic extraction
+    pub async fn extract(&self, url: &str) -> Option<Result<ExtractionResult>> {
+        let extractor = self.find(url)?;
+        debug!("Using {} extractor for {}", extractor.name(), url);
+        Some(extractor.extract(url).await)
+    }
+}
+
+/// A site extractor implemented in Python. The handler provides
+/// `can_handle(url) -> bool` and `extract(url) -> ExtractionResult`.
+struct PySiteExtractor {
+    name: String,
+    handler: PyObject,
+}
+
+#[async_trait]
+impl SiteExtractor for PySiteExtractor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn can_handle(&self, url: &str) -> bool {
+        Python::with_gil(|py| {
+            self.handler
+                .call_method1(py, "can_handle", (url,))
+                .and_then(|r| r.extract::<bool>(py))
+                .unwrap_or_else(|e| {
+                    warn!("{}.can_handle raised: {}", self.name, e);
+                    false
+                })
+        })
+    }
+
+    async fn extract(&self, url: &str) -> Result<ExtractionResult> {
+        Python::with_gil(|py| {
+            self.handler
+                .call_method1(py, "extract", (url,))
+                .and_then(|r| r.extract::<ExtractionResult>(py))
+                .map_err(|e| ScraperError::ExtractionFailed(format!("{}: {}", self.name, e)))
+        })
+    }
 }
 
 /// YouTube extractor placeholder (full implementation would need yt-dlp integration)
 pub struct YouTubeExtractor {
+    #[allow(dead_code)]
     client: Arc<HttpClient>,
 }
 
@@ -473,6 +1757,7 @@ impl YouTubeExtractor {
     }
 }
 
+#[async_trait]
 impl SiteExtractor for YouTubeExtractor {
     fn name(&self) -> &str {
         "youtube"
@@ -482,7 +1767,7 @@ impl SiteExtractor for YouTubeExtractor {
         url.contains("youtube.com") || url.contains("youtu.be")
     }
 
-    fn extract(&self, url: &str) -> Result<ExtractionResult> {
+    async fn extract(&self, url: &str) -> Result<ExtractionResult> {
         // Note: Full YouTube extraction requires yt-dlp or similar
         // This is a placeholder showing the interface
         warn!(
@@ -503,3 +1788,168 @@ impl SiteExtractor for YouTubeExtractor {
     }
 }
 
+/// Vimeo extractor reading the player config, which lists the progressive
+/// MP4 files and the HLS and DASH manifests of a video
+pub struct VimeoExtractor {
+    client: Arc<HttpClient>,
+}
+
+impl VimeoExtractor {
+    pub fn new(client: Arc<HttpClient>) -> Self {
+        Self { client }
+    }
+
+    /// The video id of a `vimeo.com/{id}` or `player.vimeo.com/video/{id}`
+    /// URL, with the privacy hash unlisted videos need when one is given
+    pub fn video_id(url: &str) -> Option<(String, Option<String>)> {
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        let segments: Vec<_> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let is_id = |s: &&str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+        let (id, hash) = match (host, segments.as_slice()) {
+            ("vimeo.com", [id, rest @ ..]) if is_id(id) => {
+                // Unlisted videos are shared as `vimeo.com/{id}/{hash}`
+                let hash = rest
+                    .first()
+                    .filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()))
+                    .map(|h| h.to_string());
+                (id.to_string(), hash)
+            }
+            ("player.vimeo.com", ["video", id, ..]) if is_id(id) => {
+                let hash = parsed
+                    .query_pairs()
+                    .find(|(name, _)| name == "h")
+                    .map(|(_, value)| value.into_owned());
+                (id.to_string(), hash)
+            }
+            _ => return None,
+        };
+        Some((id, hash))
+    }
+
+    /// Build the result from a player config document
+    pub(crate) fn parse_config(
+        source_url: &str,
+        config: &serde_json::Value,
+    ) -> Result<ExtractionResult> {
+        let files = config.pointer("/request/files").ok_or_else(|| {
+            ScraperError::AccessDenied(format!(
+                "{} is private or password-protected: the player lists no files",
+                source_url
+            ))
+        })?;
+        let video = &config["video"];
+
+        let mut formats: Vec<VideoFormat> = files["progressive"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|file| {
+                let url = file["url"].as_str()?.to_string();
+                let height = file["height"].as_u64().map(|h| h as u32);
+                let quality = file["quality"]
+                    .as_str()
+                    .map(|q| q.to_string())
+                    .or_else(|| height.map(|h| format!("{}p", h)));
+                Some(VideoFormat {
+                    format_id: format!("progressive-{}", quality.as_deref().unwrap_or("unknown")),
+                    url,
+                    ext: "mp4".to_string(),
+                    quality,
+                    width: file["width"].as_u64().map(|w| w as u32),
+                    height,
+                    fps: file["fps"].as_f64().map(|f| f.round() as u32),
+                    vcodec: guess_codec("mp4"),
+                    acodec: None,
+                    filesize: None,
+                    tbr: None,
+                })
+            })
+            .collect();
+        formats.sort_by_key(|f| std::cmp::Reverse(f.height.unwrap_or(0)));
+        let best_progressive = formats.first().map(|f| f.url.clone());
+
+        // Manifests are offered by several CDNs; prefer the player's default
+        let manifest = |kind: &str| {
+            let cdns = &files[kind]["cdns"];
+            let default = files[kind]["default_cdn"].as_str().unwrap_or_default();
+            cdns.get(default)
+                .or_else(|| cdns.as_object()?.values().next())
+                .and_then(|cdn| cdn["url"].as_str())
+                .map(|url| url.to_string())
+        };
+        let hls_url = manifest("hls");
+        for (kind, ext, url) in [("hls", "m3u8", &hls_url), ("dash", "mpd", &manifest("dash"))] {
+            if let Some(url) = url {
+                formats.push(VideoFormat::new(
+                    kind.to_string(),
+                    url.clone(),
+                    ext.to_string(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        // The largest numbered thumbnail, e.g. `{"640": ..., "1280": ...}`
+        let thumbnail = video["thumbs"].as_object().and_then(|thumbs| {
+            thumbs
+                .iter()
+                .filter_map(|(size, url)| Some((size.parse::<u32>().ok()?, url.as_str()?)))
+                .max_by_key(|(size, _)| *size)
+                .map(|(_, url)| url.to_string())
+        });
+
+        Ok(ExtractionResult {
+            source_url: source_url.to_string(),
+            title: video["title"].as_str().map(|t| t.to_string()),
+            description: None,
+            thumbnail,
+            duration: video["duration"].as_u64(),
+            formats,
+            best_video_url: best_progressive.or(hls_url),
+            best_audio_url: None,
+        })
+    }
+}
+
+#[async_trait]
+impl SiteExtractor for VimeoExtractor {
+    fn name(&self) -> &str {
+        "vimeo"
+    }
+
+    fn can_handle(&self, url: &str) -> bool {
+        Self::video_id(url).is_some()
+    }
+
+    async fn extract(&self, url: &str) -> Result<ExtractionResult> {
+        let (id, hash) = Self::video_id(url)
+            .ok_or_else(|| ScraperError::ExtractionFailed(format!("Not a Vimeo video: {}", url)))?;
+        let mut config_url = format!("https://player.vimeo.com/video/{}/config", id);
+        if let Some(hash) = hash {
+            config_url.push_str(&format!("?h={}", hash));
+        }
+
+        let response = match self.client.get(&config_url).await {
+            Err(ScraperError::AccessDenied(_)) => {
+                return Err(ScraperError::AccessDenied(format!(
+                    "Vimeo video {} is private or password-protected",
+                    id
+                )))
+            }
+            result => result?,
+        };
+        let text = self.client.read_page(response).await?;
+        let config: serde_json::Value = serde_json::from_str(&text)?;
+        Self::parse_config(url, &config)
+    }
+}
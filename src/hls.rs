@@ -0,0 +1,305 @@
+//! HLS (m3u8) playlist parsing
+
+use crate::error::{Result, ScraperError};
+use url::Url;
+
+/// MIME types servers use for m3u8 playlists
+const HLS_CONTENT_TYPES: &[&str] = &[
+    "application/vnd.apple.mpegurl",
+    "application/x-mpegurl",
+    "audio/mpegurl",
+    "audio/x-mpegurl",
+];
+
+/// A single media segment, optionally a byte range of a larger resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaSegment {
+    pub url: String,
+    /// Inclusive `(start, end)` byte range from `#EXT-X-BYTERANGE`
+    pub byte_range: Option<(u64, u64)>,
+    pub duration_secs: f64,
+}
+
+/// A parsed media playlist
+#[derive(Debug, Clone, Default)]
+pub struct MediaPlaylist {
+    /// Initialization segment from `#EXT-X-MAP` (fragmented MP4 streams)
+    pub init_segment: Option<MediaSegment>,
+    pub segments: Vec<MediaSegment>,
+}
+
+impl MediaPlaylist {
+    /// Sum of all segment durations
+    pub fn duration_secs(&self) -> f64 {
+        self.segments.iter().map(|s| s.duration_secs).sum()
+    }
+}
+
+/// A variant stream listed in a master playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub url: String,
+    /// Peak bits per second from `BANDWIDTH`
+    pub bandwidth: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The raw `CODECS` list, e.g. `avc1.64001f,mp4a.40.2`
+    pub codecs: Option<String>,
+    pub frame_rate: Option<f64>,
+}
+
+/// RFC 6381 prefixes of video codecs
+const VIDEO_CODEC_PREFIXES: &[&str] = &["avc", "hvc", "hev", "vp0", "vp8", "vp9", "av01", "dvh"];
+
+/// The first video codec in a comma-separated RFC 6381 codecs list
+pub fn first_video_codec(codecs: &str) -> Option<&str> {
+    codecs
+        .split(',')
+        .map(str::trim)
+        .find(|codec| VIDEO_CODEC_PREFIXES.iter().any(|p| codec.starts_with(p)))
+}
+
+impl Variant {
+    /// The video codec from `CODECS`, if any
+    pub fn video_codec(&self) -> Option<&str> {
+        first_video_codec(self.codecs.as_deref()?)
+    }
+
+    /// The audio codec from `CODECS`, if any
+    pub fn audio_codec(&self) -> Option<&str> {
+        self.codec_matching(&["mp4a", "ac-3", "ec-3", "opus", "flac", "mp3"])
+    }
+
+    fn codec_matching(&self, prefixes: &[&str]) -> Option<&str> {
+        self.codecs
+            .as_deref()?
+            .split(',')
+            .map(str::trim)
+            .find(|codec| prefixes.iter().any(|p| codec.starts_with(p)))
+    }
+}
+
+/// A subtitle rendition (`#EXT-X-MEDIA:TYPE=SUBTITLES`) from a master playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleRendition {
+    /// URL of the rendition's media playlist of WebVTT segments
+    pub url: String,
+    pub language: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Whether a URL points at an m3u8 playlist, judging by its path
+pub fn is_hls_url(url: &str) -> bool {
+    Url::parse(url)
+        .map(|u| u.path().to_lowercase().ends_with(".m3u8"))
+        .unwrap_or(false)
+}
+
+/// Whether a `Content-Type` header value is an HLS playlist type
+pub fn is_hls_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    HLS_CONTENT_TYPES.iter().any(|t| mime.eq_ignore_ascii_case(t))
+}
+
+/// Whether `url` is an HLS playlist, by its path or its reported content type
+pub fn is_hls(url: &str, content_type: Option<&str>) -> bool {
+    is_hls_url(url) || content_type.is_some_and(is_hls_content_type)
+}
+
+/// Whether the body is a master playlist listing variant streams
+pub fn is_master_playlist(text: &str) -> bool {
+    text.lines().any(|l| l.starts_with("#EXT-X-STREAM-INF"))
+}
+
+/// Parse a media playlist, resolving segment URIs against `base_url`
+pub fn parse_media_playlist(text: &str, base_url: &str) -> Result<MediaPlaylist> {
+    let base = Url::parse(base_url)?;
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    if lines.next() != Some("#EXTM3U") {
+        return Err(ScraperError::InvalidFormat(
+            "Missing #EXTM3U header".to_string(),
+        ));
+    }
+
+    let mut playlist = MediaPlaylist::default();
+    let mut duration = 0.0;
+    let mut pending_range: Option<(u64, Option<u64>)> = None;
+    // Where the previous byte range ended, per resource, for ranges without an offset
+    let mut next_offset: Option<(String, u64)> = None;
+
+    for line in lines {
+        if let Some(value) = line.strip_prefix("#EXTINF:") {
+            let value = value.split(',').next().unwrap_or("");
+            duration = value.trim().parse().unwrap_or(0.0);
+        } else if let Some(value) = line.strip_prefix("#EXT-X-BYTERANGE:") {
+            pending_range = Some(parse_byte_range(value)?);
+        } else if let Some(value) = line.strip_prefix("#EXT-X-MAP:") {
+            let attrs = parse_attributes(value);
+            let uri = attribute(&attrs, "URI").ok_or_else(|| {
+                ScraperError::InvalidFormat("#EXT-X-MAP without URI".to_string())
+            })?;
+            let byte_range = match attribute(&attrs, "BYTERANGE") {
+                Some(range) => {
+                    let (len, offset) = parse_byte_range(range)?;
+                    let start = offset.unwrap_or(0);
+                    Some((start, start + len - 1))
+                }
+                None => None,
+            };
+            playlist.init_segment = Some(MediaSegment {
+                url: base.join(uri)?.to_string(),
+                byte_range,
+                duration_secs: 0.0,
+            });
+        } else if let Some(value) = line.strip_prefix("#EXT-X-KEY:") {
+            let attrs = parse_attributes(value);
+            if let Some(method) = attribute(&attrs, "METHOD").filter(|m| *m != "NONE") {
+                return Err(ScraperError::InvalidFormat(format!(
+                    "Encrypted HLS streams are not supported (METHOD={})",
+                    method
+                )));
+            }
+        } else if line.starts_with('#') {
+            // Other tags don't affect which bytes make up the stream
+        } else {
+            let url = base.join(line)?.to_string();
+            let byte_range = match pending_range.take() {
+                Some((len, offset)) => {
+                    let start = match (offset, &next_offset) {
+                        (Some(offset), _) => offset,
+                        (None, Some((prev_url, end))) if *prev_url == url => *end,
+                        (None, _) => {
+                            return Err(ScraperError::InvalidFormat(format!(
+                                "#EXT-X-BYTERANGE without offset has no previous range: {}",
+                                url
+                            )))
+                        }
+                    };
+                    next_offset = Some((url.clone(), start + len));
+                    Some((start, start + len - 1))
+                }
+                None => None,
+            };
+
+            playlist.segments.push(MediaSegment {
+                url,
+                byte_range,
+                duration_secs: duration,
+            });
+            duration = 0.0;
+        }
+    }
+
+    Ok(playlist)
+}
+
+/// Parse the variant streams of a master playlist, resolving URIs against `base_url`
+pub fn parse_master_playlist(text: &str, base_url: &str) -> Result<Vec<Variant>> {
+    let base = Url::parse(base_url)?;
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    if lines.next() != Some("#EXTM3U") {
+        return Err(ScraperError::InvalidFormat(
+            "Missing #EXTM3U header".to_string(),
+        ));
+    }
+
+    let mut variants = Vec::new();
+    let mut pending: Option<Vec<(String, String)>> = None;
+
+    for line in lines {
+        if let Some(value) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            pending = Some(parse_attributes(value));
+        } else if line.starts_with('#') {
+            continue;
+        } else if let Some(attrs) = pending.take() {
+            let resolution = attribute(&attrs, "RESOLUTION").and_then(|r| r.split_once('x'));
+
+            variants.push(Variant {
+                url: base.join(line)?.to_string(),
+                bandwidth: attribute(&attrs, "BANDWIDTH").and_then(|b| b.parse().ok()),
+                width: resolution.and_then(|(w, _)| w.parse().ok()),
+                height: resolution.and_then(|(_, h)| h.parse().ok()),
+                codecs: attribute(&attrs, "CODECS").map(|c| c.to_string()),
+                frame_rate: attribute(&attrs, "FRAME-RATE").and_then(|f| f.parse().ok()),
+            });
+        }
+    }
+
+    Ok(variants)
+}
+
+/// Parse the subtitle renditions of a master playlist
+pub fn parse_subtitle_renditions(text: &str, base_url: &str) -> Result<Vec<SubtitleRendition>> {
+    let base = Url::parse(base_url)?;
+    let mut renditions = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        let Some(value) = line.strip_prefix("#EXT-X-MEDIA:") else {
+            continue;
+        };
+        let attrs = parse_attributes(value);
+        if attribute(&attrs, "TYPE") != Some("SUBTITLES") {
+            continue;
+        }
+        // Renditions without a URI are carried in-band and can't be fetched separately
+        let Some(uri) = attribute(&attrs, "URI") else {
+            continue;
+        };
+
+        renditions.push(SubtitleRendition {
+            url: base.join(uri)?.to_string(),
+            language: attribute(&attrs, "LANGUAGE").map(|l| l.to_string()),
+            name: attribute(&attrs, "NAME").map(|n| n.to_string()),
+        });
+    }
+
+    Ok(renditions)
+}
+
+/// Parse `<length>[@<offset>]`
+fn parse_byte_range(value: &str) -> Result<(u64, Option<u64>)> {
+    let invalid = || ScraperError::InvalidFormat(format!("Invalid byte range: {}", value));
+    let (len, offset) = match value.trim().split_once('@') {
+        Some((len, offset)) => (len, Some(offset.parse().map_err(|_| invalid())?)),
+        None => (value.trim(), None),
+    };
+    let len: u64 = len.parse().map_err(|_| invalid())?;
+    if len == 0 {
+        return Err(invalid());
+    }
+    Ok((len, offset))
+}
+
+/// Split an attribute list (`KEY=value,KEY="quoted, value"`) into pairs
+pub(crate) fn parse_attributes(value: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = value.trim();
+
+    while let Some((key, after)) = rest.split_once('=') {
+        let (val, remainder) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.split_once('"') {
+                Some((val, remainder)) => (val, remainder),
+                None => (quoted, ""),
+            }
+        } else {
+            match after.split_once(',') {
+                Some((val, remainder)) => (val, remainder),
+                None => (after, ""),
+            }
+        };
+
+        attrs.push((key.trim().to_string(), val.to_string()));
+        rest = remainder.trim_start_matches(',').trim_start();
+    }
+
+    attrs
+}
+
+pub(crate) fn attribute<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
@@ -34,28 +34,49 @@
 //!     print(f"{job.id}: {job.status}")
 //! ```
 
+// pyo3 0.20's `#[pymethods]` expands to impls that newer rustc flags as non-local
+#![allow(non_local_definitions)]
+
+pub mod cache;
+pub mod checksum;
 pub mod client;
 pub mod config;
+pub mod dash;
+pub mod dedup;
 pub mod downloader;
 pub mod error;
 pub mod extractor;
+pub mod hls;
+pub mod metrics;
+#[cfg(feature = "ffmpeg")]
+pub mod mux;
 pub mod pipeline;
+pub mod robots;
+pub mod runtime;
+pub mod sitemap;
 pub mod storage;
+pub mod template;
 
 use pyo3::prelude::*;
 
 // Re-exports for Rust usage
+pub use checksum::ChecksumAlgorithm;
 pub use client::HttpClient;
 pub use config::{ScraperConfig, StorageConfig};
-pub use downloader::{DownloadManager, DownloadProgress, DownloadResult};
+pub use downloader::{
+    DownloadManager, DownloadOptions, DownloadProgress, DownloadResult, ProgressCallback,
+};
 pub use error::{Result, ScraperError};
-pub use extractor::{VideoExtractor, VideoFormat, VideoInfo, ExtractionResult};
+pub use extractor::{
+    ExtractionResult, SiteExtractor, SiteExtractorRegistry, SubtitleTrack, VideoExtractor,
+    VideoFormat, VideoInfo,
+};
 pub use pipeline::{ScrapingPipeline, ScrapeJob, JobStatus, PipelineStats, VideoFilter};
-pub use storage::{StorageBackend, StorageManager, ObjectMetadata};
+pub use storage::{LocalStorage, StorageBackend, StorageManager, ObjectMetadata};
 
 /// Python module definition
 #[pymodule]
-fn _core(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+fn _core(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -65,21 +86,28 @@ fn _core(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
         .try_init()
         .ok();
 
+    // Exceptions
+    error::exceptions::register(py, m)?;
+
     // Configuration classes
     m.add_class::<config::ScraperConfig>()?;
     m.add_class::<config::StorageConfig>()?;
 
     // HTTP client
     m.add_class::<client::PyHttpClient>()?;
+    m.add_class::<client::PyResponse>()?;
+    m.add_class::<client::Probe>()?;
 
     // Downloader
     m.add_class::<downloader::PyDownloadManager>()?;
     m.add_class::<downloader::DownloadProgress>()?;
     m.add_class::<downloader::DownloadResult>()?;
+    m.add_class::<checksum::ChecksumAlgorithm>()?;
 
     // Extractor
     m.add_class::<extractor::PyVideoExtractor>()?;
     m.add_class::<extractor::VideoInfo>()?;
+    m.add_class::<extractor::SubtitleTrack>()?;
     m.add_class::<extractor::VideoFormat>()?;
     m.add_class::<extractor::ExtractionResult>()?;
 
@@ -93,6 +121,8 @@ fn _core(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<pipeline::JobStatus>()?;
     m.add_class::<pipeline::PipelineStats>()?;
     m.add_class::<pipeline::VideoFilter>()?;
+    m.add_class::<pipeline::ProgressStream>()?;
+    m.add_class::<dedup::DedupEntry>()?;
 
     // Version info
     m.add("__version__", "0.1.0")?;
@@ -103,23 +133,27 @@ fn _core(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
         config: Option<&config::ScraperConfig>,
         storage_config: Option<&config::StorageConfig>,
     ) -> PyResult<pipeline::PyPipeline> {
-        pipeline::PyPipeline::new(config, storage_config)
+        pipeline::PyPipeline::new(config, storage_config, true)
     }
 
     // Convenience function to extract videos from a URL
     #[pyfn(m)]
-    fn extract_videos(url: &str) -> PyResult<Vec<extractor::VideoInfo>> {
+    fn extract_videos(py: Python<'_>, url: &str) -> PyResult<Vec<extractor::VideoInfo>> {
         let config = config::ScraperConfig::default();
         let extractor = extractor::PyVideoExtractor::new(Some(&config))?;
-        extractor.extract_from_url(url)
+        extractor.extract_from_url(py, url)
     }
 
     // Convenience function to download a file
     #[pyfn(m)]
-    fn download_file(url: &str, output_path: &str) -> PyResult<downloader::DownloadResult> {
+    fn download_file(
+        py: Python<'_>,
+        url: &str,
+        output_path: &str,
+    ) -> PyResult<downloader::DownloadResult> {
         let config = config::ScraperConfig::default();
         let manager = downloader::PyDownloadManager::new(Some(&config))?;
-        manager.download(url, output_path)
+        manager.download(py, url, output_path, None)
     }
 
     Ok(())
@@ -128,6 +162,143 @@ fn _core(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// A parsed request as seen by the test server
+    struct TestRequest {
+        method: String,
+        path: String,
+        headers: HashMap<String, String>,
+    }
+
+    /// A canned response from the test server
+    struct TestResponse {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    type Handler = Arc<dyn Fn(&TestRequest) -> TestResponse + Send + Sync>;
+
+    /// Serve one request per connection on localhost, returning the base URL
+    async fn serve(handler: Handler) -> String {
+        serve_with(handler, None).await
+    }
+
+    /// `serve`, but with `stall_after` set, response bodies stop after that
+    /// many bytes and the connection stays open until the client hangs up
+    async fn serve_with(handler: Handler, stall_after: Option<usize>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let mut raw = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => raw.extend_from_slice(&buf[..n]),
+                        }
+                    }
+
+                    let text = String::from_utf8_lossy(&raw);
+                    let mut lines = text.lines();
+                    let mut request_line = lines.next().unwrap_or_default().split(' ');
+                    let request = TestRequest {
+                        method: request_line.next().unwrap_or_default().to_string(),
+                        path: request_line.next().unwrap_or_default().to_string(),
+                        headers: lines
+                            .filter_map(|l| l.split_once(": "))
+                            .map(|(k, v)| (k.to_lowercase(), v.to_string()))
+                            .collect(),
+                    };
+
+                    let mut response = handler(&request);
+                    let mut head = format!("HTTP/1.1 {} X\r\n", response.status);
+                    for (name, value) in &response.headers {
+                        head.push_str(&format!("{}: {}\r\n", name, value));
+                    }
+                    // Handlers asking for chunked encoding get the body as one chunk
+                    let chunked = response.headers.iter().any(|(name, value)| {
+                        name.eq_ignore_ascii_case("transfer-encoding") && value == "chunked"
+                    });
+                    if chunked {
+                        let mut body = format!("{:x}\r\n", response.body.len()).into_bytes();
+                        body.append(&mut response.body);
+                        body.extend_from_slice(b"\r\n0\r\n\r\n");
+                        response.body = body;
+                    } else {
+                        head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+                    }
+                    head.push_str("Connection: close\r\n\r\n");
+
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    if request.method != "HEAD" {
+                        let sent = stall_after.unwrap_or(usize::MAX).min(response.body.len());
+                        let _ = socket.write_all(&response.body[..sent]).await;
+                        if sent < response.body.len() {
+                            let _ = socket.read(&mut buf).await;
+                        }
+                    }
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    /// Serve a static file with byte-range support
+    fn file_handler(body: Vec<u8>) -> Handler {
+        Arc::new(move |request: &TestRequest| {
+            let mut headers = vec![("Accept-Ranges".to_string(), "bytes".to_string())];
+            let range = request
+                .headers
+                .get("range")
+                .and_then(|r| r.strip_prefix("bytes="))
+                .and_then(|r| r.split_once('-'));
+
+            match range {
+                Some((start, end)) => {
+                    let start: usize = start.parse().unwrap();
+                    let end: usize = end.parse().unwrap_or(body.len() - 1).min(body.len() - 1);
+                    headers.push((
+                        "Content-Range".to_string(),
+                        format!("bytes {}-{}/{}", start, end, body.len()),
+                    ));
+                    TestResponse {
+                        status: 206,
+                        headers,
+                        body: body[start..=end].to_vec(),
+                    }
+                }
+                None => TestResponse {
+                    status: 200,
+                    headers,
+                    body: body.clone(),
+                },
+            }
+        })
+    }
+
+    fn test_scraper_config() -> ScraperConfig {
+        ScraperConfig {
+            rate_limit_per_second: 1000.0,
+            chunk_size_bytes: 4096,
+            enable_caching: false,
+            max_retries: 1,
+            ..ScraperConfig::default()
+        }
+    }
+
+    fn test_body(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
 
     #[test]
     fn test_config_default() {
@@ -141,6 +312,1794 @@ mod tests {
         let config = ScraperConfig::high_performance();
         assert_eq!(config.max_concurrent_downloads, 128);
         assert_eq!(config.rate_limit_per_second, 50.0);
+        assert_eq!(config.connect_timeout_secs, 10);
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        // Process-wide variables would race with the other tests
+        let mut vars: HashMap<&str, &str> = HashMap::from([
+            ("VIDEOSCRAPER_MAX_CONCURRENT_DOWNLOADS", "64"),
+            ("VIDEOSCRAPER_RATE_LIMIT", "12.5"),
+            ("VIDEOSCRAPER_RESPECT_ROBOTS_TXT", "no"),
+            ("VIDEOSCRAPER_ALLOWED_FORMATS", "mp4, webm"),
+            ("VIDEOSCRAPER_PROXY_URL", "http://proxy:8080"),
+            ("VIDEOSCRAPER_STORAGE_BACKEND", "s3"),
+            ("VIDEOSCRAPER_STORAGE_S3_BUCKET", "videos"),
+        ]);
+        let lookup = |vars: &HashMap<&str, &str>, var: &str| vars.get(var).map(|v| v.to_string());
+
+        let config = ScraperConfig::from_vars(|var| lookup(&vars, var)).unwrap();
+        assert_eq!(config.max_concurrent_downloads, 64);
+        assert_eq!(config.rate_limit_per_second, 12.5);
+        assert!(!config.respect_robots_txt);
+        assert_eq!(config.allowed_formats, vec!["mp4", "webm"]);
+        assert_eq!(config.proxy_url.as_deref(), Some("http://proxy:8080"));
+        assert_eq!(config.max_retries, ScraperConfig::default().max_retries);
+
+        let storage = StorageConfig::from_vars(|var| lookup(&vars, var)).unwrap();
+        assert_eq!(storage.backend, "s3");
+        assert_eq!(storage.s3_bucket.as_deref(), Some("videos"));
+
+        for bad in ["lots", "-1", "99999999999"] {
+            vars.insert("VIDEOSCRAPER_MAX_RETRIES", bad);
+            assert!(ScraperConfig::from_vars(|var| lookup(&vars, var)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ScraperConfig::default().validate().is_ok());
+        assert!(ScraperConfig::conservative().validate().is_ok());
+        assert!(StorageConfig::default().validate().is_ok());
+        assert!(StorageConfig::s3("bucket", None, None, None).validate().is_ok());
+        assert!(StorageConfig::azure("account", "videos", None, Some("sv=x"), None)
+            .validate()
+            .is_ok());
+
+        let config = ScraperConfig {
+            chunk_size_bytes: 0,
+            request_timeout_secs: 0,
+            rate_limit_per_second: f64::NAN,
+            ..ScraperConfig::default()
+        };
+        assert_eq!(config.validate().unwrap_err().len(), 3);
+
+        let storage = StorageConfig::fanout(vec![
+            StorageConfig::local("./videos"),
+            StorageConfig {
+                backend: "s3".to_string(),
+                ..StorageConfig::default()
+            },
+            StorageConfig::gcs("bucket", None, None),
+            StorageConfig::azure("account", "videos", None, None, None),
+        ]);
+        assert_eq!(
+            storage.validate().unwrap_err(),
+            vec![
+                "backends[1]: s3_bucket is required for the s3 backend",
+                "backends[2]: gcs_project is required for the gcs backend",
+                "backends[3]: azure_access_key or azure_sas_token is required for the azure \
+                 backend",
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn test_config_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, text: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, text).unwrap();
+            path
+        };
+
+        let path = write("scraper.toml", "max_retries = 7\nallowed_formats = [\"mp4\"]\n");
+        let config = ScraperConfig::from_toml_file(&path).unwrap();
+        assert_eq!(config.max_retries, 7);
+        assert_eq!(config.allowed_formats, vec!["mp4"]);
+        assert_eq!(config.rate_limit_per_second, ScraperConfig::default().rate_limit_per_second);
+
+        let path = write("scraper.yaml", "max_retries: 7\nmax_retires: 3\n");
+        let err = ScraperConfig::from_yaml_file(&path).unwrap_err();
+        assert!(err.to_string().contains("unknown keys: max_retires"), "{}", err);
+
+        let path = write(
+            "storage.yaml",
+            "backend: fanout\nbackends:\n  - backend: s3\n    s3_bucket: videos\n    buckte: x\n",
+        );
+        let err = StorageConfig::from_yaml_file(&path).unwrap_err();
+        assert!(err.to_string().contains("backends[0].buckte"), "{}", err);
+
+        let path = write("storage.toml", "backend = \"s3\"\ns3_bucket = \"videos\"\n");
+        let storage = StorageConfig::from_toml_file(&path).unwrap();
+        assert_eq!(storage.s3_bucket.as_deref(), Some("videos"));
+        assert_eq!(storage.key_prefix, StorageConfig::default().key_prefix);
+    }
+
+    #[test]
+    fn test_runtime_honors_worker_threads() {
+        let runtime = runtime::build_runtime(3).unwrap();
+        assert_eq!(runtime.metrics().num_workers(), 3);
+
+        let runtime = runtime::build_runtime(0).unwrap();
+        assert_eq!(runtime.metrics().num_workers(), num_cpus::get());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        use chrono::TimeZone;
+
+        let now = chrono::Utc.with_ymd_and_hms(2025, 10, 21, 7, 27, 0).unwrap();
+        assert_eq!(client::parse_retry_after("120", now), Some(120));
+        assert_eq!(
+            client::parse_retry_after("Wed, 21 Oct 2025 07:28:00 GMT", now),
+            Some(60)
+        );
+        // Dates in the past mean "retry now", absurd values are clamped
+        assert_eq!(
+            client::parse_retry_after("Wed, 21 Oct 2025 07:00:00 GMT", now),
+            Some(0)
+        );
+        assert_eq!(client::parse_retry_after("99999999", now), Some(3600));
+        assert_eq!(client::parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_robots_txt_is_enforced() {
+        let rules = robots::RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\n\
+             User-agent: VideoScraper # us\nDisallow: /private\nAllow: /private/ok$\n\
+             Disallow: /*.json$\nCrawl-delay: 0.5\n",
+            "VideoScraper/0.1.0 (Rust/Python)",
+        );
+        assert!(rules.is_allowed("/videos/1"));
+        assert!(!rules.is_allowed("/private/x"));
+        assert!(rules.is_allowed("/private/ok"));
+        assert!(!rules.is_allowed("/private/ok/more"));
+        assert!(!rules.is_allowed("/api/feed.json"));
+        assert!(rules.is_allowed("/api/feed.json?page=2"));
+        assert_eq!(rules.crawl_delay(), Some(std::time::Duration::from_millis(500)));
+
+        let base = serve(Arc::new(|request: &TestRequest| TestResponse {
+            status: 200,
+            headers: vec![],
+            body: match request.path.as_str() {
+                "/robots.txt" => b"User-agent: *\nDisallow: /private\n\n\
+                    User-agent: BadBot\nDisallow: /\n"
+                    .to_vec(),
+                _ => b"ok".to_vec(),
+            },
+        }))
+        .await;
+
+        let client = HttpClient::new(&test_scraper_config()).unwrap();
+        assert!(client.get(&format!("{}/public", base)).await.is_ok());
+        let err = client.get(&format!("{}/private/video", base)).await.unwrap_err();
+        assert!(matches!(err, ScraperError::AccessDenied(_)));
+
+        // Rotated user agents must each be allowed
+        let rotating = |agents: &[&str]| ScraperConfig {
+            user_agents: agents.iter().map(|a| a.to_string()).collect(),
+            ..test_scraper_config()
+        };
+        let client = HttpClient::new(&rotating(&["GoodBot/1.0"])).unwrap();
+        assert!(client.get(&format!("{}/public", base)).await.is_ok());
+        let client = HttpClient::new(&rotating(&["GoodBot/1.0", "BadBot/2.0"])).unwrap();
+        let err = client.get(&format!("{}/public", base)).await.unwrap_err();
+        assert!(matches!(err, ScraperError::AccessDenied(_)));
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        assert!(client.get(&format!("{}/private/video", base)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_limit_and_final_url() {
+        // /hop/3 -> /hop/2 -> /hop/1 -> /videos/page
+        let base = serve(Arc::new(|request: &TestRequest| {
+            match request.path.strip_prefix("/hop/") {
+                Some(n) => {
+                    let next = match n.parse::<u32>().unwrap() {
+                        1 => "/videos/page".to_string(),
+                        n => format!("/hop/{}", n - 1),
+                    };
+                    TestResponse {
+                        status: 302,
+                        headers: vec![("Location".to_string(), next)],
+                        body: Vec::new(),
+                    }
+                }
+                None => TestResponse {
+                    status: 200,
+                    headers: vec![],
+                    body: br#"<html><video src="clip.mp4"></video></html>"#.to_vec(),
+                },
+            }
+        }))
+        .await;
+        let start = format!("{}/hop/3", base);
+        let config = |max_redirects| ScraperConfig {
+            max_redirects,
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+
+        let client = Arc::new(HttpClient::new(&config(3)).unwrap());
+        let response = client.get(&start).await.unwrap();
+        assert_eq!(response.url().as_str(), format!("{}/videos/page", base));
+
+        // Relative links resolve against where the page was actually served
+        let videos = VideoExtractor::new(client).extract_from_url(&start).await.unwrap();
+        assert_eq!(videos[0].url, format!("{}/videos/clip.mp4", base));
+        assert_eq!(videos[0].source_page, start);
+
+        let client = HttpClient::new(&config(2)).unwrap();
+        assert!(client.get(&start).await.is_err());
+
+        let client = HttpClient::new(&config(0)).unwrap();
+        let response = client.get(&start).await.unwrap();
+        assert_eq!(response.status().as_u16(), 302);
+        assert_eq!(response.headers()["location"], "/hop/2");
+    }
+
+    #[tokio::test]
+    async fn test_page_size_limit() {
+        let page = format!("<html><video src=\"clip.mp4\"></video>{}</html>", " ".repeat(512));
+        let base = serve(Arc::new(move |_: &TestRequest| TestResponse {
+            status: 200,
+            headers: vec![],
+            body: page.clone().into_bytes(),
+        }))
+        .await;
+        let extract = |max_page_bytes| {
+            let config = ScraperConfig {
+                max_page_bytes,
+                respect_robots_txt: false,
+                ..test_scraper_config()
+            };
+            let client = Arc::new(HttpClient::new(&config).unwrap());
+            let url = format!("{}/page", base);
+            async move { VideoExtractor::new(client).extract_from_url(&url).await }
+        };
+
+        let err = extract(256).await.unwrap_err();
+        assert!(matches!(err, ScraperError::InvalidFormat(_)), "{}", err);
+        assert_eq!(extract(4096).await.unwrap().len(), 1);
+        assert_eq!(extract(0).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_extraction_timeout() {
+        // Accept connections but never answer them
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/slow", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let config = ScraperConfig {
+            extraction_timeout_secs: 1,
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let started = std::time::Instant::now();
+        let err = VideoExtractor::new(client).extract_from_url(&url).await.unwrap_err();
+        assert!(matches!(err, ScraperError::Timeout { timeout_secs: 1 }), "{}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let healthy = Arc::new(AtomicBool::new(false));
+        let (h, up) = (hits.clone(), healthy.clone());
+        let url = serve(Arc::new(move |_: &TestRequest| {
+            h.fetch_add(1, Ordering::SeqCst);
+            TestResponse {
+                status: if up.load(Ordering::SeqCst) { 200 } else { 503 },
+                headers: Vec::new(),
+                body: b"ok".to_vec(),
+            }
+        }))
+        .await;
+
+        let client = HttpClient::new(&ScraperConfig {
+            max_retries: 5,
+            retry_delay_ms: 1,
+            circuit_breaker_threshold: 2,
+            circuit_breaker_cooldown_secs: 1,
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        })
+        .unwrap();
+
+        // The retry loop stops once the second failure opens the circuit
+        let err = client.get(&url).await.unwrap_err();
+        assert!(matches!(err, ScraperError::CircuitOpen { .. }), "{}", err);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        let err = client.get(&url).await.unwrap_err();
+        assert!(matches!(err, ScraperError::CircuitOpen { .. }), "{}", err);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        // After the cooldown a successful probe closes the circuit again
+        healthy.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        client.get(&url).await.unwrap();
+        client.get(&url).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_rotation() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let url = serve(Arc::new(move |request: &TestRequest| {
+            log.lock().unwrap().push(request.headers["user-agent"].clone());
+            TestResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: b"ok".to_vec(),
+            }
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("agents.txt");
+        std::fs::write(&list, "# desktop\nAgentA/1.0\n\n  AgentB/2.0  \n").unwrap();
+        let mut config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        assert_eq!(config.load_user_agents(&list).unwrap(), 2);
+
+        let client = HttpClient::new(&config).unwrap();
+        for _ in 0..3 {
+            client.get(&url).await.unwrap();
+        }
+        assert_eq!(*seen.lock().unwrap(), ["AgentA/1.0", "AgentB/2.0", "AgentA/1.0"]);
+
+        // Without a list every request carries `user_agent`
+        seen.lock().unwrap().clear();
+        config.user_agents.clear();
+        HttpClient::new(&config).unwrap().get(&url).await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), [config.user_agent.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_configured_default_headers() {
+        let seen = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let log = seen.clone();
+        let url = serve(Arc::new(move |request: &TestRequest| {
+            *log.lock().unwrap() = request.headers.clone();
+            TestResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: b"ok".to_vec(),
+            }
+        }))
+        .await;
+
+        let mut config = ScraperConfig {
+            respect_robots_txt: false,
+            accept_language: Some("de-DE,de;q=0.9".to_string()),
+            extra_headers: HashMap::from([
+                ("X-Api-Key".to_string(), "secret".to_string()),
+                ("Accept".to_string(), "video/*".to_string()),
+            ]),
+            ..test_scraper_config()
+        };
+        HttpClient::new(&config).unwrap().get(&url).await.unwrap();
+        let headers = seen.lock().unwrap().clone();
+        assert_eq!(headers["accept-language"], "de-DE,de;q=0.9");
+        assert_eq!(headers["x-api-key"], "secret");
+        assert_eq!(headers["accept"], "video/*");
+
+        config.extra_headers.insert("Bad Name".to_string(), "x".to_string());
+        assert!(config.validate().is_err());
+        let err = HttpClient::new(&config).err().unwrap();
+        assert!(matches!(err, ScraperError::ConfigError(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_tls_options() {
+        let config = ScraperConfig {
+            danger_accept_invalid_certs: true,
+            ..test_scraper_config()
+        };
+        assert!(HttpClient::new(&config).is_ok());
+
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("ca.pem");
+        std::fs::write(&bundle, "not a certificate\n").unwrap();
+        let config = ScraperConfig {
+            ca_cert_path: Some(bundle.to_string_lossy().to_string()),
+            ..test_scraper_config()
+        };
+        let err = HttpClient::new(&config).err().unwrap();
+        assert!(matches!(err, ScraperError::ConfigError(_)), "{}", err);
+
+        let config = ScraperConfig {
+            ca_cert_path: Some(dir.path().join("missing.pem").to_string_lossy().to_string()),
+            ..test_scraper_config()
+        };
+        assert!(config.validate().is_err());
+        assert!(HttpClient::new(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_status() {
+        let url = serve(file_handler(test_body(100))).await;
+        assert_eq!(HttpClient::domain_of(&url).unwrap(), "127.0.0.1");
+        assert!(HttpClient::domain_of("not a url").is_err());
+
+        let client = HttpClient::new(&ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        })
+        .unwrap();
+        assert!(client.rate_limiter_status().is_empty());
+        client.get(&url).await.unwrap();
+        assert_eq!(client.rate_limiter_status(), [("127.0.0.1".to_string(), 1000.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_domain_limit_held_while_body_streams() {
+        let url = serve_with(file_handler(test_body(10_000)), Some(1_000)).await;
+        let client = HttpClient::new(&ScraperConfig {
+            respect_robots_txt: false,
+            max_requests_per_domain: 1,
+            ..test_scraper_config()
+        })
+        .unwrap();
+
+        use std::time::Duration;
+
+        // The first body stalls part way, keeping the domain's only slot
+        let first = client.get(&url).await.unwrap();
+        let waiting = tokio::time::timeout(Duration::from_millis(300), client.get(&url)).await;
+        assert!(waiting.is_err());
+        let waiting = tokio::time::timeout(Duration::from_millis(300), client.probe(&url)).await;
+        assert!(waiting.is_err());
+
+        drop(first);
+        let probe = tokio::time::timeout(Duration::from_secs(5), client.probe(&url)).await;
+        assert_eq!(probe.unwrap().unwrap().content_length, Some(10_000));
+    }
+
+    #[test]
+    fn test_retry_jitter_is_seedable() {
+        let config = ScraperConfig::default();
+        let a = HttpClient::new(&config).unwrap();
+        let b = HttpClient::new(&config).unwrap();
+        a.set_retry_seed(42);
+        b.set_retry_seed(42);
+
+        for attempt in 1..=5 {
+            let delay = a.retry_delay(attempt);
+            assert_eq!(delay, b.retry_delay(attempt));
+            assert!(delay.as_millis() <= (config.retry_delay_ms << (attempt - 1)) as u128);
+        }
+
+        let config = ScraperConfig {
+            retry_jitter: false,
+            ..ScraperConfig::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        assert_eq!(client.retry_delay(3).as_millis(), 4000);
+    }
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let revalidations = Arc::new(AtomicUsize::new(0));
+        let (fetched, revalidated) = (fetches.clone(), revalidations.clone());
+        let base = serve(Arc::new(move |request: &TestRequest| {
+            if request.headers.get("if-none-match").map(String::as_str) == Some("\"v1\"") {
+                revalidated.fetch_add(1, Ordering::SeqCst);
+                return TestResponse { status: 304, headers: Vec::new(), body: Vec::new() };
+            }
+            fetched.fetch_add(1, Ordering::SeqCst);
+            let mut headers = vec![("ETag".to_string(), "\"v1\"".to_string())];
+            if request.path.starts_with("/chunked") {
+                headers.push(("Transfer-Encoding".to_string(), "chunked".to_string()));
+            }
+            TestResponse { status: 200, headers, body: test_body(2_000) }
+        }))
+        .await;
+        let (page, other) = (format!("{}/page", base), format!("{}/other", base));
+        let chunked = format!("{}/chunked", base);
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            enable_caching: true,
+            cache_dir: dir.path().to_string_lossy().to_string(),
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let get = |client: HttpClient, url: String| async move {
+            let response = client.get(&url).await.unwrap();
+            let from_cache = cache::is_from_cache(&response);
+            assert_eq!(response.bytes().await.unwrap(), test_body(2_000));
+            (client, from_cache)
+        };
+        let fetched = || fetches.load(Ordering::SeqCst);
+
+        // A fresh entry is served without touching the network
+        let client = HttpClient::new(&config).unwrap();
+        let (client, from_cache) = get(client, page.clone()).await;
+        assert!(!from_cache);
+        let (client, from_cache) = get(client, page.clone()).await;
+        assert!(from_cache);
+        assert_eq!(fetched(), 1);
+
+        client.invalidate_cache(&page).await.unwrap();
+        let (_, from_cache) = get(client, page.clone()).await;
+        assert!(!from_cache);
+        assert_eq!(fetched(), 2);
+
+        // Once stale, a 304 serves the stored copy
+        let no_ttl = ScraperConfig { cache_ttl_secs: 0, ..config.clone() };
+        let stale = HttpClient::new(&no_ttl).unwrap();
+        let (_, from_cache) = get(stale, page.clone()).await;
+        assert!(from_cache);
+        assert_eq!((fetched(), revalidations.load(Ordering::SeqCst)), (2, 1));
+
+        // A chunked body past the page limit streams through whole, uncached
+        let small_pages = ScraperConfig { max_page_bytes: 500, ..config.clone() };
+        let client = HttpClient::new(&small_pages).unwrap();
+        let (client, from_cache) = get(client, chunked.clone()).await;
+        assert!(!from_cache);
+        let _ = get(client, chunked).await;
+        assert_eq!(fetched(), 4);
+
+        // Storing past `cache_max_bytes` evicts the oldest entry
+        let bounded = ScraperConfig { cache_max_bytes: 3_000, ..config.clone() };
+        let client = HttpClient::new(&bounded).unwrap();
+        let (client, _) = get(client, other.clone()).await;
+        assert_eq!(fetched(), 5);
+        let (client, from_cache) = get(client, other).await;
+        assert!(from_cache);
+        // `/page` was the oldest
+        let (_, from_cache) = get(client, page).await;
+        assert!(!from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_probe_takes_one_head_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let heads = Arc::new(AtomicUsize::new(0));
+        let counter = heads.clone();
+        let file = file_handler(test_body(5_000));
+        let url = serve(Arc::new(move |request: &TestRequest| {
+            if request.method == "HEAD" {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+            let mut response = file(request);
+            response.headers.push(("ETag".to_string(), "\"v1\"".to_string()));
+            response.headers.push(("Content-Type".to_string(), "video/mp4".to_string()));
+            response
+        }))
+        .await;
+        let url = format!("{}/video.mp4", url);
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let probe = client.probe(&url).await.unwrap();
+        assert_eq!(probe.content_length, Some(5_000));
+        assert!(probe.accepts_ranges);
+        assert_eq!(probe.content_type.as_deref(), Some("video/mp4"));
+        assert_eq!(probe.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(heads.load(Ordering::SeqCst), 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = DownloadManager::new(client, &config);
+        manager.download(&url, &dir.path().join("video.mp4")).await.unwrap();
+        assert_eq!(heads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_download_hash_matches_full_download() {
+        let body = test_body(20_000);
+        let url = format!("{}/video.mp4", serve(file_handler(body.clone())).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+
+        let baseline = manager
+            .download(&url, &dir.path().join("full.mp4"))
+            .await
+            .unwrap();
+        assert!(!baseline.resumed);
+
+        // Simulate an interrupted download: a `.part` prefix plus resume state
+        let partial_path = dir.path().join("partial.mp4");
+        std::fs::write(dir.path().join("partial.mp4.part"), &body[..8192]).unwrap();
+        let state = serde_json::json!({
+            "url": url,
+            "output_path": partial_path.to_string_lossy(),
+            "total_bytes": body.len(),
+            "downloaded_bytes": 8192,
+            "chunk_size": config.chunk_size_bytes,
+            "partial_hash": "",
+            "chunks_completed": [[0, 8192]],
+            "started_at": chrono::Utc::now(),
+            "last_updated": chrono::Utc::now(),
+        });
+        std::fs::write(dir.path().join(".partial.mp4.dlstate"), state.to_string()).unwrap();
+
+        let resumed = manager.download(&url, &partial_path).await.unwrap();
+        assert!(resumed.resumed);
+        assert_eq!(resumed.sha256_hash, baseline.sha256_hash);
+        assert_eq!(std::fs::read(&partial_path).unwrap(), body);
+        assert!(!dir.path().join("partial.mp4.part").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_to_storage_without_staging() {
+        use crate::storage::{LocalStorage, StorageBackend};
+
+        let body = test_body(50_000);
+        let url = format!("{}/video.mp4", serve(file_handler(body.clone())).await);
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(&dir.path().to_string_lossy()).unwrap();
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client.clone(), &config);
+        let baseline = manager
+            .download(&url, &dir.path().join("staged.mp4"))
+            .await
+            .unwrap();
+
+        let (result, metadata) = manager
+            .download_to_storage(&url, "clips/video.mp4", &storage, None)
+            .await
+            .unwrap();
+        assert_eq!(result.sha256_hash, baseline.sha256_hash);
+        assert_eq!(metadata.size_bytes, body.len() as u64);
+        assert_eq!(storage.get("clips/video.mp4").await.unwrap(), body);
+
+        // Oversized bodies are cut off and leave nothing behind
+        let config = ScraperConfig {
+            max_file_size_bytes: 10_000,
+            ..test_scraper_config()
+        };
+        let manager = DownloadManager::new(client, &config);
+        let err = manager
+            .download_to_storage(&url, "clips/big.mp4", &storage, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::FileTooLarge { .. }), "{}", err);
+        assert!(!storage.exists("clips/big.mp4").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_download_to_bytes() {
+        use sha2::{Digest, Sha256};
+
+        let body = test_body(30_000);
+        let url = format!("{}/clip.mp4", serve(file_handler(body.clone())).await);
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client.clone(), &config);
+        let (data, result) = manager.download_to_bytes(&url).await.unwrap();
+        assert_eq!(data, body);
+        assert_eq!(result.size_bytes, body.len() as u64);
+        assert_eq!(result.sha256_hash, hex::encode(Sha256::digest(&body)));
+        assert_eq!(manager.active_downloads(), 0);
+
+        let config = ScraperConfig {
+            max_file_size_bytes: 10_000,
+            ..test_scraper_config()
+        };
+        let manager = DownloadManager::new(client, &config);
+        let err = manager.download_to_bytes(&url).await.unwrap_err();
+        assert!(matches!(err, ScraperError::FileTooLarge { .. }), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_download_checksum_algorithms() {
+        let url = format!("{}/check.mp4", serve(file_handler(b"123456789".to_vec())).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = ScraperConfig {
+            checksum_algorithm: ChecksumAlgorithm::Md5,
+            extra_checksum_algorithms: vec![ChecksumAlgorithm::Crc32, ChecksumAlgorithm::Crc32c],
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+        let result = manager.download(&url, &dir.path().join("check.mp4")).await.unwrap();
+
+        assert_eq!(result.checksum_algorithm, "md5");
+        assert_eq!(result.checksum, "25f9e794323b453885f5181f1b624d0b");
+        assert_eq!(result.checksums["crc32"], "cbf43926");
+        assert_eq!(result.checksums["crc32c"], "e3069283");
+        assert_eq!(result.checksums["sha256"], result.sha256_hash);
+        assert_eq!(result.checksums.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_download_checks_disk_space_first() {
+        let url = format!("{}/big.mp4", serve(file_handler(test_body(4_000))).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = ScraperConfig {
+            disk_space_margin_bytes: u64::MAX,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+        let err = manager.download(&url, &dir.path().join("big.mp4")).await.unwrap_err();
+        assert!(matches!(err, ScraperError::StorageError(_)), "{}", err);
+        assert!(err.to_string().contains("insufficient disk space"), "{}", err);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_download_enforces_file_size_limits() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let gets = Arc::new(AtomicUsize::new(0));
+        let counter = gets.clone();
+        let body = test_body(20_000);
+        let base = serve(Arc::new(move |request: &TestRequest| {
+            if request.method == "GET" {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+            // Without a declared length the limit is checked as bytes arrive
+            let headers = match request.path.as_str() {
+                "/chunked.mp4" => vec![("Transfer-Encoding".to_string(), "chunked".to_string())],
+                _ => Vec::new(),
+            };
+            TestResponse { status: 200, headers, body: body.clone() }
+        }))
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let download = |manager: DownloadManager, name: &'static str| {
+            let (url, path) = (format!("{}/{}", base, name), dir.path().join(name));
+            async move { manager.download(&url, &path).await.unwrap_err() }
+        };
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            max_file_size_bytes: 10_000,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = || DownloadManager::new(client.clone(), &config);
+        let err = download(manager(), "declared.mp4").await;
+        assert!(
+            matches!(err, ScraperError::FileTooLarge { size_bytes: 20_000, limit_bytes: 10_000 }),
+            "{}",
+            err
+        );
+        // A declared length over the limit is refused before the body is requested
+        assert_eq!(gets.load(Ordering::SeqCst), 0);
+        let err = download(manager(), "chunked.mp4").await;
+        assert!(matches!(err, ScraperError::FileTooLarge { limit_bytes: 10_000, .. }), "{}", err);
+
+        let config = ScraperConfig {
+            max_file_size_bytes: 0,
+            min_file_size_bytes: 50_000,
+            ..config
+        };
+        let err = download(DownloadManager::new(client.clone(), &config), "small.mp4").await;
+        assert!(
+            matches!(err, ScraperError::FileTooSmall { size_bytes: 20_000, min_bytes: 50_000 }),
+            "{}",
+            err
+        );
+
+        // Rejected downloads leave nothing behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_progress_callback_reports_and_aborts() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        let url = format!("{}/video.mp4", serve(file_handler(test_body(50_000))).await);
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let seen = updates.clone();
+        let progress: ProgressCallback = Arc::new(move |update: &DownloadProgress| {
+            seen.lock().unwrap().push(update.clone());
+            Ok(())
+        });
+        let options = DownloadOptions { progress: Some(progress), ..Default::default() };
+        let manager = DownloadManager::new(client.clone(), &config);
+        manager.download_with_options(&url, &dir.path().join("a.mp4"), &options).await.unwrap();
+        let last = updates.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(last.status, "completed");
+        assert_eq!(last.downloaded_bytes, 50_000);
+        assert_eq!(last.total_bytes, Some(50_000));
+        assert_eq!(last.percentage, 100.0);
+
+        // Paced so the first update comes mid-transfer; its error stops the download
+        let config = ScraperConfig {
+            min_chunk_interval_ms: 50,
+            ..config
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let stop: ProgressCallback = Arc::new(move |_: &DownloadProgress| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err(ScraperError::Cancelled("Download".to_string()))
+        });
+        let options = DownloadOptions { progress: Some(stop), ..Default::default() };
+        let manager = DownloadManager::new(client, &config);
+        let err = manager
+            .download_with_options(&url, &dir.path().join("b.mp4"), &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::Aborted(_)), "{}", err);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!dir.path().join("b.mp4").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_writes_part_file_until_done() {
+        let body = test_body(5_000);
+        let stalled = serve_with(file_handler(body.clone()), Some(1_000)).await;
+        let stalled = format!("{}/video.mp4", stalled);
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("video.mp4");
+        let part_path = dir.path().join("video.mp4.part");
+        std::fs::write(&output_path, b"previous").unwrap();
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new(&config).unwrap()), &config);
+        let (interrupted, ()) = tokio::join!(manager.download(&stalled, &output_path), async {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            // Bytes land in the `.part` file; the old output stays untouched
+            assert!(part_path.exists());
+            assert_eq!(std::fs::read(&output_path).unwrap(), b"previous");
+            assert!(manager.cancel(&stalled, None));
+        });
+        assert!(matches!(interrupted, Err(ScraperError::Cancelled(_))));
+        assert_eq!(std::fs::read(&output_path).unwrap(), b"previous");
+
+        let url = format!("{}/video.mp4", serve(file_handler(body.clone())).await);
+        manager.download(&url, &output_path).await.unwrap();
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+        assert!(!part_path.exists());
+    }
+
+    #[cfg(all(feature = "ffmpeg", unix))]
+    #[tokio::test]
+    async fn test_download_and_mux() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let h = hits.clone();
+        let (video, audio) = (file_handler(b"VIDEO".to_vec()), file_handler(b"AUDIO".to_vec()));
+        let base = serve(Arc::new(move |req: &TestRequest| {
+            h.fetch_add(1, Ordering::SeqCst);
+            if req.path.contains("audio") {
+                audio(req)
+            } else {
+                video(req)
+            }
+        }))
+        .await;
+        let (video_url, audio_url) = (format!("{}/video.mp4", base), format!("{}/audio.m4a", base));
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out").join("clip.mp4");
+
+        // Without ffmpeg it fails before downloading anything
+        let config = ScraperConfig {
+            ffmpeg_path: dir.path().join("missing-ffmpeg").to_string_lossy().to_string(),
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let err = DownloadManager::new(client, &config)
+            .download_and_mux(&video_url, &audio_url, &output)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::ConfigError(_)), "{}", err);
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+
+        // A stand-in ffmpeg that concatenates its two inputs
+        let ffmpeg = dir.path().join("ffmpeg");
+        std::fs::write(
+            &ffmpeg,
+            concat!(
+                "#!/bin/sh\n",
+                "[ \"$1\" = -version ] && exit 0\n",
+                "for last; do :; done\n",
+                "cat \"$7\" \"$9\" > \"$last\"\n",
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&ffmpeg, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = ScraperConfig {
+            ffmpeg_path: ffmpeg.to_string_lossy().to_string(),
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let result = DownloadManager::new(client, &config)
+            .download_and_mux(&video_url, &audio_url, &output)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), b"VIDEOAUDIO");
+        assert_eq!(result.size_bytes, 10);
+        // The staged streams are gone
+        let left: Vec<_> = std::fs::read_dir(output.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(left, ["clip.mp4"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_download() {
+        let body = test_body(100_000);
+        let url = format!("{}/video.mp4", serve_with(file_handler(body), Some(1_000)).await);
+        let dir = tempfile::tempdir().unwrap();
+        let (first_path, second_path) = (dir.path().join("a.mp4"), dir.path().join("b.mp4"));
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+        let wait_for_active = |count: u64| {
+            let manager = &manager;
+            async move {
+                while manager.active_downloads() != count {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+            }
+        };
+
+        let (first, second, ()) = tokio::join!(
+            manager.download(&url, &first_path),
+            manager.download(&url, &second_path),
+            async {
+                // Two downloads of one URL are told apart by their output paths
+                wait_for_active(2).await;
+                assert!(manager.cancel(&url, Some(&first_path)));
+                // The first finishing leaves the second cancellable
+                wait_for_active(1).await;
+                assert!(manager.cancel(&url, None));
+            },
+        );
+        for result in [first, second] {
+            let err = result.unwrap_err();
+            assert!(matches!(err, ScraperError::Cancelled(_)), "{}", err);
+            assert!(!err.is_retryable());
+        }
+        assert!(!manager.cancel(&url, None));
+    }
+
+    #[tokio::test]
+    async fn test_download_metrics_text() {
+        let body = test_body(5_000);
+        let url = format!("{}/clip.mp4", serve(file_handler(body.clone())).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+        manager.download(&url, &dir.path().join("a.mp4")).await.unwrap();
+        manager.download_to_bytes(&url).await.unwrap();
+        let missing = url.replace("clip.mp4", "clip.m3u8");
+        assert!(manager.download_to_bytes(&missing).await.is_err());
+
+        let text = manager.metrics_text();
+        for line in [
+            "# TYPE videoscraper_downloads_completed_total counter",
+            "videoscraper_downloads_completed_total 2",
+            "videoscraper_downloads_failed_total 1",
+            "videoscraper_downloaded_bytes_total 10000",
+            "# TYPE videoscraper_downloads_active gauge",
+            "videoscraper_downloads_active 0",
+            "videoscraper_downloads_queued 0",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {:?} in\n{}", line, text);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_state_checked_against_disk() {
+        use sha2::{Digest, Sha256};
+
+        let body = test_body(20_000);
+        let url = format!("{}/video.mp4", serve(file_handler(body.clone())).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+
+        let output_path = dir.path().join("video.mp4");
+        let interrupt = |on_disk: &[u8], saved: usize| {
+            std::fs::write(dir.path().join("video.mp4.part"), on_disk).unwrap();
+            let state = serde_json::json!({
+                "url": url,
+                "output_path": output_path.to_string_lossy(),
+                "total_bytes": body.len(),
+                "downloaded_bytes": saved,
+                "chunk_size": config.chunk_size_bytes,
+                "partial_hash": hex::encode(Sha256::digest(&body[..saved])),
+                "chunks_completed": [[0, saved]],
+                "started_at": chrono::Utc::now(),
+                "last_updated": chrono::Utc::now(),
+            });
+            std::fs::write(dir.path().join(".video.mp4.dlstate"), state.to_string()).unwrap();
+        };
+
+        // The last saved chunk never reached disk
+        interrupt(&body[..4096], 8192);
+        let result = manager.download(&url, &output_path).await.unwrap();
+        assert!(result.resumed);
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+
+        // A preallocated file keeps its length but loses the bytes
+        let mut on_disk = body[..4096].to_vec();
+        on_disk.resize(body.len(), 0);
+        interrupt(&on_disk, 8192);
+        let result = manager.download(&url, &output_path).await.unwrap();
+        assert!(!result.resumed);
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_resume_restarts_when_etag_changes() {
+        let body = test_body(20_000);
+        let files = file_handler(body.clone());
+        // Honors `If-Range` like a real server: a stale validator gets the whole file
+        let handler: Handler = Arc::new(move |request: &TestRequest| {
+            let mut headers = request.headers.clone();
+            if headers.get("if-range").is_some_and(|v| v != "\"v2\"") {
+                headers.remove("range");
+            }
+            let mut response = files(&TestRequest {
+                method: request.method.clone(),
+                path: request.path.clone(),
+                headers,
+            });
+            response.headers.push(("ETag".to_string(), "\"v2\"".to_string()));
+            response
+        });
+        let url = format!("{}/video.mp4", serve(handler).await);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = test_scraper_config();
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client, &config);
+
+        let output_path = dir.path().join("video.mp4");
+        let interrupt = |etag: &str, prefix: &[u8]| {
+            std::fs::write(dir.path().join("video.mp4.part"), prefix).unwrap();
+            let state = serde_json::json!({
+                "url": url,
+                "output_path": output_path.to_string_lossy(),
+                "total_bytes": body.len(),
+                "downloaded_bytes": prefix.len(),
+                "chunk_size": config.chunk_size_bytes,
+                "partial_hash": "",
+                "chunks_completed": [[0, prefix.len()]],
+                "started_at": chrono::Utc::now(),
+                "last_updated": chrono::Utc::now(),
+                "etag": etag,
+            });
+            std::fs::write(dir.path().join(".video.mp4.dlstate"), state.to_string()).unwrap();
+        };
+
+        // The partial bytes came from an older version of the file
+        interrupt("\"v1\"", &[0xAA; 8192]);
+        let result = manager.download(&url, &output_path).await.unwrap();
+        assert!(!result.resumed);
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+
+        interrupt("\"v2\"", &body[..8192]);
+        let result = manager.download(&url, &output_path).await.unwrap();
+        assert!(result.resumed);
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn test_segmented_download_reassembles_and_resumes() {
+        use sha2::{Digest, Sha256};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let body = test_body(50_000);
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+        let file = file_handler(body.clone());
+        let url = serve(Arc::new(move |request: &TestRequest| {
+            let response = file(request);
+            if request.method == "GET" {
+                counter.fetch_add(response.body.len(), Ordering::SeqCst);
+            }
+            response
+        }))
+        .await;
+        let url = format!("{}/video.mp4", url);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            download_connections: 4,
+            ..test_scraper_config()
+        };
+        let client = Arc::new(HttpClient::new(&config).unwrap());
+        let manager = DownloadManager::new(client.clone(), &config);
+
+        let result = manager.download(&url, &dir.path().join("whole.mp4")).await.unwrap();
+        assert_eq!(result.connections, 4);
+        assert_eq!(result.sha256_hash, hex::encode(Sha256::digest(&body)));
+        assert_eq!(std::fs::read(dir.path().join("whole.mp4")).unwrap(), body);
+        assert_eq!(served.swap(0, Ordering::SeqCst), body.len());
+
+        // Each segment of a stalled download gets its first 1000 bytes
+        let stalled = serve_with(file_handler(body.clone()), Some(1_000)).await;
+        let stalled = format!("{}/video.mp4", stalled);
+        let output_path = dir.path().join("video.mp4");
+        let (interrupted, ()) = tokio::join!(manager.download(&stalled, &output_path), async {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            assert!(manager.cancel(&stalled, None));
+        });
+        assert!(matches!(interrupted, Err(ScraperError::Cancelled(_))));
+
+        let state_path = dir.path().join(".video.mp4.dlstate");
+        let mut state: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&state_path).unwrap()).unwrap();
+        assert_eq!(state["segmented"], true);
+        assert_eq!(state["downloaded_bytes"], 4_000);
+        let ranges: Vec<(u64, u64)> =
+            serde_json::from_value(state["chunks_completed"].clone()).unwrap();
+        assert_eq!(ranges, [(0, 1_000), (12_500, 13_500), (25_000, 26_000), (37_500, 38_500)]);
+
+        // Resuming from a healthy server fetches only the gaps, four of them
+        // over two connections
+        state["url"] = url.clone().into();
+        std::fs::write(&state_path, state.to_string()).unwrap();
+        let config = ScraperConfig { download_connections: 2, ..config };
+        let manager = DownloadManager::new(client, &config);
+        let result = manager.download(&url, &output_path).await.unwrap();
+        assert!(result.resumed);
+        assert_eq!(result.connections, 2);
+        assert_eq!(result.sha256_hash, hex::encode(Sha256::digest(&body)));
+        assert_eq!(std::fs::read(&output_path).unwrap(), body);
+        assert_eq!(served.load(Ordering::SeqCst), body.len() - 4_000);
+        assert!(!state_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_hls_playlists_bypass_the_cache_within_the_page_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Each request for the live playlist lists a newer segment
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let base = serve(Arc::new(move |request: &TestRequest| {
+            let body = match request.path.as_str() {
+                "/live.m3u8" => {
+                    let n = counter.fetch_add(1, Ordering::SeqCst);
+                    format!("#EXTM3U\n#EXTINF:4.0,\nseg{}.ts\n#EXT-X-ENDLIST\n", n)
+                }
+                path => format!("segment {}", path),
+            };
+            TestResponse { status: 200, headers: Vec::new(), body: body.into_bytes() }
+        }))
+        .await;
+        let url = format!("{}/live.m3u8", base);
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            enable_caching: true,
+            cache_dir: dir.path().join("cache").to_string_lossy().to_string(),
+            ..test_scraper_config()
+        };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new(&config).unwrap()), &config);
+        for expected in ["segment /seg0.ts", "segment /seg1.ts"] {
+            let path = dir.path().join("live.ts");
+            manager.download(&url, &path).await.unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+        }
+
+        let config = ScraperConfig { max_page_bytes: 16, ..config };
+        let manager = DownloadManager::new(Arc::new(HttpClient::new(&config).unwrap()), &config);
+        let err = manager.download(&url, &dir.path().join("big.ts")).await.unwrap_err();
+        assert!(err.to_string().contains("byte page limit"), "{}", err);
+        assert!(!dir.path().join("big.ts").exists());
+    }
+
+    #[test]
+    fn test_parse_hls_media_playlist() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-VERSION:7\n\
+            #EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"720@0\"\n\
+            #EXTINF:4.0,\n\
+            #EXT-X-BYTERANGE:1000@720\n\
+            media.mp4\n\
+            #EXTINF:4.0,\n\
+            #EXT-X-BYTERANGE:500\n\
+            media.mp4\n\
+            #EXTINF:2.5,\n\
+            https://cdn.example.com/tail.ts\n\
+            #EXT-X-ENDLIST\n";
+
+        let parsed =
+            hls::parse_media_playlist(playlist, "https://example.com/v/index.m3u8").unwrap();
+
+        let init = parsed.init_segment.as_ref().unwrap();
+        assert_eq!(init.url, "https://example.com/v/init.mp4");
+        assert_eq!(init.byte_range, Some((0, 719)));
+
+        assert_eq!(parsed.segments.len(), 3);
+        assert_eq!(parsed.segments[0].url, "https://example.com/v/media.mp4");
+        assert_eq!(parsed.segments[0].byte_range, Some((720, 1719)));
+        // A range without an offset continues where the previous one ended
+        assert_eq!(parsed.segments[1].byte_range, Some((1720, 2219)));
+        assert_eq!(parsed.segments[2].url, "https://cdn.example.com/tail.ts");
+        assert_eq!(parsed.segments[2].byte_range, None);
+        assert_eq!(parsed.duration_secs(), 10.5);
+    }
+
+    #[test]
+    fn test_parse_dash_manifest() {
+        let mpd = r#"<?xml version="1.0"?>
+            <MPD xmlns="urn:mpeg:dash:schema:mpd:2011" mediaPresentationDuration="PT10S">
+              <Period>
+                <BaseURL>media/</BaseURL>
+                <AdaptationSet mimeType="video/mp4" codecs="avc1.640028">
+                  <SegmentTemplate timescale="1000" duration="4000" startNumber="1"
+                      initialization="$RepresentationID$/init.mp4"
+                      media="$RepresentationID$/seg-$Number%03d$.m4s"/>
+                  <Representation id="1080" bandwidth="5000000" width="1920" height="1080"/>
+                  <Representation id="360" bandwidth="800000" width="640" height="360"/>
+                </AdaptationSet>
+                <AdaptationSet mimeType="audio/mp4">
+                  <Representation id="audio" bandwidth="128000" codecs="mp4a.40.2">
+                    <BaseURL>audio.m4a</BaseURL>
+                  </Representation>
+                </AdaptationSet>
+              </Period>
+            </MPD>"#;
+
+        let reps = dash::parse_mpd(mpd, "https://example.com/v/manifest.mpd").unwrap();
+        assert_eq!(reps.len(), 3);
+
+        let hd = &reps[0];
+        assert!(hd.is_video());
+        assert_eq!((hd.width, hd.height), (Some(1920), Some(1080)));
+        assert_eq!(hd.codecs.as_deref(), Some("avc1.640028"));
+        assert_eq!(
+            hd.initialization_url.as_deref(),
+            Some("https://example.com/v/media/1080/init.mp4")
+        );
+        // 10s at 4s per segment rounds up to three segments
+        assert_eq!(
+            hd.segment_urls,
+            vec![
+                "https://example.com/v/media/1080/seg-001.m4s",
+                "https://example.com/v/media/1080/seg-002.m4s",
+                "https://example.com/v/media/1080/seg-003.m4s",
+            ]
+        );
+
+        let audio = &reps[2];
+        assert!(audio.is_audio());
+        assert!(!audio.is_segmented());
+        assert_eq!(audio.url, "https://example.com/v/media/audio.m4a");
+    }
+
+    #[test]
+    fn test_extract_json_ld_video_object() {
+        let html = r#"<html><head><script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+              {"@type": "NewsArticle", "headline": "Story"},
+              {"@type": "VideoObject", "name": "Clip", "duration": "PT1M30S",
+               "contentUrl": "/media/clip.mp4", "thumbnailUrl": ["/media/clip.jpg"]}
+            ]}
+            </script></head></html>"#;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_html(html, "https://news.example.com/story")
+            .unwrap();
+
+        assert_eq!(videos.len(), 1);
+        assert_eq!(videos[0].url, "https://news.example.com/media/clip.mp4");
+        assert_eq!(videos[0].title.as_deref(), Some("Clip"));
+        assert_eq!(videos[0].duration_secs, Some(90));
+        assert_eq!(
+            videos[0].thumbnail_url.as_deref(),
+            Some("https://news.example.com/media/clip.jpg")
+        );
+    }
+
+    #[test]
+    fn test_extract_open_graph_and_twitter_player_metadata() {
+        let html = r#"<html><head>
+            <meta property="og:image" content="/thumbs/clip.jpg">
+            <meta property="og:video" content="https://cdn.example.com/clip.mp4">
+            <meta property="og:video:secure_url" content="https://cdn.example.com/clip.mp4">
+            <meta property="og:video:width" content="1280">
+            <meta property="og:video:height" content="720">
+            <meta property="og:video:duration" content="95">
+            <meta name="twitter:player:stream" content="https://cdn.example.com/clip-tw.mp4">
+            </head><body><video src="https://cdn.example.com/other.mp4"></video></body></html>"#;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_html(html, "https://example.com/watch")
+            .unwrap();
+        let urls: Vec<_> = videos.iter().map(|v| v.url.as_str()).collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://cdn.example.com/other.mp4",
+                "https://cdn.example.com/clip.mp4",
+                "https://cdn.example.com/clip-tw.mp4",
+            ]
+        );
+        let og = &videos[1];
+        assert_eq!((og.width, og.height, og.duration_secs), (Some(1280), Some(720), Some(95)));
+        assert_eq!(og.thumbnail_url.as_deref(), Some("https://example.com/thumbs/clip.jpg"));
+        // Structured properties only describe the og:video they follow
+        assert_eq!((videos[2].width, videos[2].height), (None, None));
+        assert_eq!(videos[2].thumbnail_url, og.thumbnail_url);
+        assert_eq!(videos[0].thumbnail_url, None);
+    }
+
+    #[test]
+    fn test_extract_data_attributes_and_player_setup() {
+        let html = r#"<html><body>
+            <div class="player" data-video-url="/stream/1234"></div>
+            <img data-src="/lazy/poster.jpg"><div data-src="/lazy/clip.webm"></div>
+            <video-js data-setup='{"sources": [{"src": "/vjs/master.m3u8"}]}'></video-js>
+            <script>
+              jwplayer("player").setup({
+                title: "Launch {day 1}",
+                playlist: [{file: '/jw/720.mp4', image: "/jw/poster.jpg",
+                            tracks: [{file: "/jw/captions.vtt", kind: "captions"}]}],
+              });
+              var p = videojs('clip', {sources: [{src: "https://cdn.example.com/vjs.mp4"}]});
+            </script>
+            </body></html>"#;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_html(html, "https://example.com/watch")
+            .unwrap();
+        let urls: Vec<_> = videos.iter().map(|v| v.url.as_str()).collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/stream/1234",
+                "https://example.com/lazy/clip.webm",
+                "https://example.com/jw/720.mp4",
+                "https://cdn.example.com/vjs.mp4",
+                "https://example.com/vjs/master.m3u8",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_urls_resolve_against_base_href() {
+        let html = r#"<html><head><base href="/media/v2/"></head><body>
+            <video src="clip.mp4" poster="poster.jpg"></video>
+            <a href="//cdn.example.net/other.webm">mirror</a>
+            <a href="https://elsewhere.example.org/abs.mp4">abs</a>
+            </body></html>"#;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_html(html, "https://example.com/articles/post.html")
+            .unwrap();
+        let urls: Vec<_> = videos.iter().map(|v| v.url.as_str()).collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/media/v2/clip.mp4",
+                "https://cdn.example.net/other.webm",
+                "https://elsewhere.example.org/abs.mp4",
+            ]
+        );
+        assert_eq!(videos[0].source_page, "https://example.com/articles/post.html");
+    }
+
+    #[test]
+    fn test_extract_links_for_crawling() {
+        let html = r#"<html><body>
+            <a href="/videos/1">one</a>
+            <a href="/videos/1#comments">one again</a>
+            <a href="https://other.example.org/page">elsewhere</a>
+            <a href="clip.mp4">direct video</a>
+            <a href="mailto:someone@example.com">mail</a>
+            </body></html>"#;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let links = VideoExtractor::new(client).extract_links(html, "https://example.com/index");
+
+        assert_eq!(
+            links,
+            vec!["https://example.com/videos/1", "https://other.example.org/page"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_from_sitemap() {
+        let base = serve(Arc::new(|request: &TestRequest| {
+            let body = match request.path.as_str() {
+                "/sitemap.xml" => {
+                    r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                    <sitemap><loc>/videos.xml</loc></sitemap>
+                    <sitemap><loc>/missing.xml</loc></sitemap>
+                    <sitemap><loc>/sitemap.xml</loc></sitemap>
+                    <sitemap><loc>/more.xml</loc></sitemap>
+                    </sitemapindex>"#
+                }
+                "/videos.xml" => {
+                    r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                        xmlns:video="http://www.google.com/schemas/sitemap-video/1.1">
+                    <url><loc>https://example.com/watch/a</loc><video:video>
+                        <video:content_loc>/media/a.mp4</video:content_loc>
+                        <video:title><![CDATA[ Clip A ]]></video:title>
+                        <video:duration>600</video:duration>
+                        <video:thumbnail_loc>/thumbs/a.jpg</video:thumbnail_loc>
+                    </video:video></url>
+                    <url><loc>https://example.com/watch/player</loc><video:video>
+                        <video:player_loc>https://example.com/embed/1</video:player_loc>
+                    </video:video><video:video>
+                        <video:content_loc>/media/a.mp4</video:content_loc>
+                    </video:video></url>
+                    </urlset>"#
+                }
+                "/more.xml" => {
+                    r#"<urlset xmlns:v="http://www.google.com/schemas/sitemap-video/1.1">
+                    <url><loc>/watch/b</loc><v:video>
+                        <v:content_loc>https://cdn.example.com/b.webm</v:content_loc>
+                    </v:video></url></urlset>"#
+                }
+                _ => "not found",
+            };
+            TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "application/xml".to_string())],
+                body: body.as_bytes().to_vec(),
+            }
+        }))
+        .await;
+
+        let extract = |max_sitemaps| {
+            let url = format!("{}/sitemap.xml", base);
+            let config = ScraperConfig {
+                max_sitemaps,
+                ..test_scraper_config()
+            };
+            let client = Arc::new(HttpClient::new(&config).unwrap());
+            async move { VideoExtractor::new(client).extract_from_sitemap(&url).await }
+        };
+
+        let videos = extract(10).await.unwrap();
+        let urls: Vec<String> = videos.iter().map(|v| v.url.clone()).collect();
+        let expected = [format!("{}/media/a.mp4", base), "https://cdn.example.com/b.webm".into()];
+        assert_eq!(urls, expected);
+        assert_eq!(videos[0].title.as_deref(), Some("Clip A"));
+        assert_eq!(videos[0].duration_secs, Some(600));
+        assert_eq!(videos[0].thumbnail_url, Some(format!("{}/thumbs/a.jpg", base)));
+        assert_eq!(videos[0].source_page, "https://example.com/watch/a");
+        assert_eq!(videos[0].format.as_deref(), Some("mp4"));
+        assert_eq!(videos[1].source_page, format!("{}/watch/b", base));
+
+        assert_eq!(extract(2).await.unwrap().len(), 1);
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let url = format!("{}/nope", base);
+        let missing = VideoExtractor::new(client).extract_from_sitemap(&url).await;
+        assert!(matches!(missing, Err(ScraperError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_detect_blocked_and_removed_pages() {
+        let mut config = test_scraper_config();
+        config.removed_page_patterns.push("Clip gone".to_string());
+        let extractor = VideoExtractor::new(Arc::new(HttpClient::new(&config).unwrap()));
+        let page = |body: &str| format!("<html><body>{}</body></html>", body);
+        let url = "https://example.com/watch";
+
+        let challenge = page("<h1>Checking your browser before accessing example.com</h1>");
+        let err = extractor.extract_from_html(&challenge, url).unwrap_err();
+        assert!(matches!(err, ScraperError::AccessDenied(_)));
+
+        let captcha = page(r#"<form><div class="g-recaptcha"></div></form>"#);
+        let err = extractor.extract_from_html(&captcha, url).unwrap_err();
+        assert!(matches!(err, ScraperError::AccessDenied(_)));
+
+        let removed = page("<p>This clip gone for good</p>");
+        let err = extractor.extract_from_html(&removed, url).unwrap_err();
+        assert!(matches!(err, ScraperError::NotFound(_)));
+
+        // Markers only matter when the page has no videos
+        let with_video = page(r#"<p>Video unavailable?</p><video src="/a.mp4"></video>"#);
+        assert_eq!(extractor.extract_from_html(&with_video, url).unwrap().len(), 1);
+        assert!(extractor.extract_from_html(&page("<p>Hello</p>"), url).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extract_from_embedded_players() {
+        struct EmbedExtractor;
+        #[async_trait::async_trait]
+        impl SiteExtractor for EmbedExtractor {
+            fn name(&self) -> &str {
+                "embed"
+            }
+            fn can_handle(&self, url: &str) -> bool {
+                url.contains("dailymotion.com/embed")
+            }
+            async fn extract(&self, url: &str) -> Result<ExtractionResult> {
+                let format = VideoFormat::new(
+                    "720p".to_string(),
+                    "https://cdn.example.com/embedded.mp4".to_string(),
+                    "mp4".to_string(),
+                    None,
+                    Some(1280),
+                    Some(720),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                Ok(ExtractionResult::new(
+                    url.to_string(),
+                    vec![format],
+                    Some("Embedded".to_string()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ))
+            }
+        }
+
+        let html = r#"<html><body>
+            <video src="/own.mp4"></video>
+            <iframe src="//www.dailymotion.com/embed/video/x1"></iframe>
+            <iframe src="https://ads.example.com/frame.html"></iframe>
+            </body></html>"#;
+        let base = serve(Arc::new(move |_: &TestRequest| TestResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            body: html.as_bytes().to_vec(),
+        }))
+        .await;
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let extractor = VideoExtractor::new(Arc::new(HttpClient::new(&config).unwrap()));
+        extractor.register_site_extractor(Box::new(EmbedExtractor));
+
+        let page = format!("{}/article", base);
+        let videos = extractor.extract_from_url(&page).await.unwrap();
+        let urls: Vec<_> = videos.iter().map(|v| v.url.as_str()).collect();
+
+        // Frames that aren't video players are not followed
+        assert_eq!(
+            urls,
+            vec![format!("{}/own.mp4", base).as_str(), "https://cdn.example.com/embedded.mp4"]
+        );
+        assert_eq!(videos[1].source_page, page);
+        assert_eq!(videos[1].title.as_deref(), Some("Embedded"));
+        assert_eq!(videos[1].height, Some(720));
+    }
+
+    #[tokio::test]
+    async fn test_registered_site_extractors_override_builtins() {
+        use extractor::VimeoExtractor;
+
+        struct HostExtractor(&'static str, &'static str);
+        #[async_trait::async_trait]
+        impl SiteExtractor for HostExtractor {
+            fn name(&self) -> &str {
+                self.1
+            }
+            fn can_handle(&self, url: &str) -> bool {
+                url.contains(self.0)
+            }
+            async fn extract(&self, url: &str) -> Result<ExtractionResult> {
+                let format = VideoFormat::new(
+                    "best".to_string(),
+                    format!("https://cdn.example.com/{}.mp4", self.1),
+                    "mp4".to_string(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                Ok(ExtractionResult::new(
+                    url.to_string(),
+                    vec![format],
+                    Some(self.1.to_string()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ))
+            }
+        }
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let mut registry = SiteExtractorRegistry::new();
+        registry.register_builtin(Box::new(VimeoExtractor::new(client.clone())));
+        assert_eq!(registry.find("https://vimeo.com/76979871").unwrap().name(), "vimeo");
+
+        // Added extractors win over built-ins, and earlier additions over later ones
+        registry.register(Box::new(HostExtractor("vimeo.com", "first")));
+        registry.register(Box::new(HostExtractor("vimeo.com", "second")));
+        registry.register(Box::new(HostExtractor("youtube.com", "youtube")));
+        assert_eq!(registry.find("https://vimeo.com/76979871").unwrap().name(), "first");
+        assert_eq!(registry.find("https://youtube.com/watch?v=x").unwrap().name(), "youtube");
+        assert!(registry.find("https://example.com/video").is_none());
+
+        // YouTube pages reach a registered extractor without any request being made
+        let extractor = VideoExtractor::new(client);
+        extractor.register_site_extractor(Box::new(HostExtractor("youtube.com", "youtube")));
+        let videos = extractor.extract_from_url("https://youtube.com/watch?v=x").await.unwrap();
+        assert_eq!(videos.len(), 1);
+        assert_eq!(videos[0].url, "https://cdn.example.com/youtube.mp4");
+    }
+
+    #[test]
+    fn test_vimeo_player_config() {
+        use extractor::VimeoExtractor;
+
+        assert_eq!(
+            VimeoExtractor::video_id("https://vimeo.com/76979871"),
+            Some(("76979871".to_string(), None))
+        );
+        assert_eq!(
+            VimeoExtractor::video_id("https://player.vimeo.com/video/76979871?h=8272103f6e"),
+            Some(("76979871".to_string(), Some("8272103f6e".to_string())))
+        );
+        assert_eq!(
+            VimeoExtractor::video_id("https://vimeo.com/76979871/8272103f6e"),
+            Some(("76979871".to_string(), Some("8272103f6e".to_string())))
+        );
+        assert_eq!(VimeoExtractor::video_id("https://vimeo.com/channels/staffpicks"), None);
+        assert_eq!(VimeoExtractor::video_id("https://example.com/76979871"), None);
+
+        let config = serde_json::json!({
+            "request": {"files": {
+                "progressive": [
+                    {"url": "https://vod.example.com/360.mp4", "width": 640, "height": 360,
+                     "quality": "360p", "fps": 25},
+                    {"url": "https://vod.example.com/720.mp4", "width": 1280, "height": 720,
+                     "quality": "720p", "fps": 25}
+                ],
+                "hls": {"default_cdn": "b", "cdns": {
+                    "a": {"url": "https://a.example.com/master.m3u8"},
+                    "b": {"url": "https://b.example.com/master.m3u8"}
+                }},
+                "dash": {"default_cdn": "a", "cdns": {
+                    "a": {"url": "https://a.example.com/master.mpd"}
+                }}
+            }},
+            "video": {"title": "The New Vimeo Player", "duration": 62,
+                      "thumbs": {"640": "https://i.example.com/640.jpg",
+                                 "1280": "https://i.example.com/1280.jpg"}}
+        });
+        let url = "https://vimeo.com/76979871";
+        let result = VimeoExtractor::parse_config(url, &config).unwrap();
+
+        assert_eq!(result.title.as_deref(), Some("The New Vimeo Player"));
+        assert_eq!(result.duration, Some(62));
+        assert_eq!(result.thumbnail.as_deref(), Some("https://i.example.com/1280.jpg"));
+        assert_eq!(result.best_video_url.as_deref(), Some("https://vod.example.com/720.mp4"));
+        let formats: Vec<_> = result
+            .formats
+            .iter()
+            .map(|f| (f.format_id.as_str(), f.url.as_str(), f.height))
+            .collect();
+        assert_eq!(
+            formats,
+            vec![
+                ("progressive-720p", "https://vod.example.com/720.mp4", Some(720)),
+                ("progressive-360p", "https://vod.example.com/360.mp4", Some(360)),
+                ("hls", "https://b.example.com/master.m3u8", None),
+                ("dash", "https://a.example.com/master.mpd", None),
+            ]
+        );
+        assert_eq!(result.get_best_format().unwrap().height, Some(720));
+
+        // Password-protected videos are served a config without any files
+        let locked = serde_json::json!({"video": {"title": "Private"}, "view": 4});
+        let err = VimeoExtractor::parse_config(url, &locked).unwrap_err();
+        assert!(matches!(err, ScraperError::AccessDenied(_)));
+    }
+
+    #[test]
+    fn test_best_format_prefers_muxed() {
+        let format = |id: &str, height, vcodec: &str, acodec: Option<&str>| {
+            let url = format!("https://cdn.example.com/{}.mp4", id);
+            let (vcodec, acodec) = (Some(vcodec.to_string()), acodec.map(str::to_string));
+            let (id, ext) = (id.to_string(), "mp4".to_string());
+            let height = Some(height);
+            VideoFormat::new(id, url, ext, None, None, height, None, vcodec, acodec, None, None)
+        };
+        let mut result = ExtractionResult::new(
+            "https://example.com/watch".to_string(),
+            vec![
+                format("dash-1080", 1080, "avc1.640028", None),
+                format("audio", 0, "none", Some("mp4a.40.2")),
+                format("muxed-360", 360, "avc1.42e01e", Some("mp4a.40.2")),
+                format("muxed-720", 720, "avc1.4d401f", Some("mp4a.40.2")),
+            ],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result.get_best_format().unwrap().format_id, "muxed-720");
+        assert_eq!(result.get_best_muxed_format().unwrap().format_id, "muxed-720");
+
+        result.formats.retain(|f| !f.format_id.starts_with("muxed"));
+        assert_eq!(result.get_best_format().unwrap().format_id, "dash-1080");
+        assert!(result.get_best_muxed_format().is_none());
+    }
+
+    #[test]
+    fn test_normalize_url_for_dedup() {
+        let strip = ScraperConfig::default().strip_query_params;
+        let a = extractor::normalize_url(
+            "https://CDN.Example.com/v.mp4?quality=hd&autoplay=1&utm_source=x#t=10",
+            &strip,
+        );
+        let b = extractor::normalize_url("https://cdn.example.com/v.mp4?quality=hd", &strip);
+        assert_eq!(a, b);
+        assert_eq!(b, "https://cdn.example.com/v.mp4?quality=hd");
+
+        let sorted = extractor::normalize_url("https://x.com/v?b=2&a=1", &strip);
+        assert_eq!(sorted, "https://x.com/v?a=1&b=2");
     }
 
     #[test]
@@ -157,5 +2116,1078 @@ mod tests {
         assert_eq!(config.s3_bucket, Some("my-bucket".to_string()));
         assert_eq!(config.s3_region, Some("us-west-2".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_pipeline_restore_requeues_unfinished_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut done = ScrapeJob::new("https://example.com/done");
+        done.status = JobStatus::Completed;
+        done.bytes_downloaded = 100;
+        let mut failed = ScrapeJob::new("https://example.com/failed");
+        failed.status = JobStatus::Failed;
+        failed.error_message = Some("boom".to_string());
+        let checkpoint = serde_json::json!({
+            "jobs": [done, failed],
+            "seen_urls": ["https://example.com/done", "https://example.com/failed"],
+        });
+        std::fs::write(&path, checkpoint.to_string()).unwrap();
+
+        let pipeline = ScrapingPipeline::restore(
+            &path,
+            &test_scraper_config(),
+            &StorageConfig::local(&dir.path().to_string_lossy()),
+        )
+        .await
+        .unwrap();
+
+        let stats = pipeline.stats().await;
+        assert_eq!(stats.total_jobs, 2);
+        assert_eq!(stats.completed_jobs, 1);
+        assert_eq!(stats.pending_jobs, 1);
+
+        let jobs = pipeline.jobs().await;
+        let requeued = jobs.iter().find(|j| j.source_url.ends_with("failed")).unwrap();
+        assert_eq!(requeued.status, JobStatus::Pending);
+        assert!(requeued.error_message.is_none());
+        assert!(pipeline.add_url("https://example.com/done").await.is_err());
+
+        pipeline.checkpoint(&path).await.unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved["jobs"].as_array().unwrap().len(), 2);
+
+        let csv_path = dir.path().join("jobs.csv");
+        pipeline.export_jobs_csv(&csv_path).await.unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.starts_with("id,source_url,status,"));
+        assert!(csv.contains(",Completed,"));
+
+        // A checkpoint with more unfinished jobs than the queue holds still loads
+        let root = dir.path().to_string_lossy();
+        let jobs: Vec<_> = (0..10_001)
+            .map(|i| ScrapeJob::new(&format!("https://example.com/{}", i)))
+            .collect();
+        let checkpoint = serde_json::json!({"jobs": jobs, "seen_urls": []});
+        std::fs::write(&path, checkpoint.to_string()).unwrap();
+        let (config, storage) = (test_scraper_config(), StorageConfig::local(&root));
+        let restore = ScrapingPipeline::restore(&path, &config, &storage);
+        let pipeline = tokio::time::timeout(std::time::Duration::from_secs(10), restore)
+            .await
+            .expect("restore waited for queue room")
+            .unwrap();
+        assert_eq!(pipeline.stats().await.pending_jobs, 10_001);
+    }
+
+    #[tokio::test]
+    async fn test_export_jobs_csv_quotes_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+        let mut job = ScrapeJob::new("https://example.com/a,b");
+        job.status = JobStatus::Completed;
+        job.error_message = Some("said \"no\"\nthen stopped".to_string());
+        let checkpoint = serde_json::json!({"jobs": [job], "seen_urls": []});
+        std::fs::write(&path, checkpoint.to_string()).unwrap();
+
+        let root = dir.path().to_string_lossy();
+        let storage = StorageConfig::local(&root);
+        let pipeline = ScrapingPipeline::restore(&path, &test_scraper_config(), &storage)
+            .await
+            .unwrap();
+        let csv_path = dir.path().join("jobs.csv");
+        pipeline.export_jobs_csv(&csv_path).await.unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+
+        let row = csv.split_once('\n').unwrap().1;
+        assert!(row.starts_with(&format!("{},\"https://example.com/a,b\",Completed,", job.id)));
+        assert!(row.ends_with(",\"said \"\"no\"\"\nthen stopped\"\n"), "{}", row);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_wait_times_out_on_pending_jobs() {
+        let pipeline =
+            ScrapingPipeline::new(&test_scraper_config(), &StorageConfig::default()).unwrap();
+        assert!(pipeline.wait(Some(std::time::Duration::from_millis(10))).await);
+
+        pipeline.add_url("https://example.com/never-run").await.unwrap();
+        assert!(!pipeline.wait(Some(std::time::Duration::from_millis(50))).await);
+    }
+
+    #[tokio::test]
+    async fn test_job_timeout_frees_worker_and_keeps_resume_state() {
+        let slow = file_handler(test_body(100_000));
+        let fast = file_handler(test_body(500));
+        let page = |html: &'static str| TestResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            body: html.as_bytes().to_vec(),
+        };
+        // Bodies past 1000 bytes stall, so only the slow video hangs
+        let handler: Handler = Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/stalls" => page(r#"<video src="/slow.mp4"></video>"#),
+            "/finishes" => page(r#"<video src="/fast.mp4"></video>"#),
+            "/slow.mp4" => slow(request),
+            _ => fast(request),
+        });
+        let base = serve_with(handler, Some(1_000)).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            job_timeout_secs: 1,
+            ..test_scraper_config()
+        };
+        let pipeline =
+            ScrapingPipeline::new(&config, &StorageConfig::local(&dir.path().to_string_lossy()))
+                .unwrap();
+        pipeline.add_url(&format!("{}/stalls", base)).await.unwrap();
+        pipeline.add_url(&format!("{}/finishes", base)).await.unwrap();
+
+        // One worker, so the second job only runs once the first gives up its slot
+        tokio::time::timeout(std::time::Duration::from_secs(10), pipeline.run(1, None))
+            .await
+            .expect("a timed out job should free its worker");
+
+        let jobs = pipeline.jobs().await;
+        let stalled = jobs.iter().find(|j| j.source_url.ends_with("/stalls")).unwrap();
+        assert_eq!(stalled.status, JobStatus::Failed);
+        assert_eq!(stalled.error_message.as_deref(), Some("Job timeout after 1 seconds"));
+        let finished = jobs.iter().find(|j| j.source_url.ends_with("/finishes")).unwrap();
+        assert_eq!(finished.status, JobStatus::Completed, "{:?}", finished.error_message);
+
+        // The interrupted download can pick up from its first 1000 bytes
+        let output_path = std::path::Path::new(stalled.output_path.as_deref().unwrap());
+        let file_name = output_path.file_name().unwrap().to_string_lossy();
+        let state_path = output_path.with_file_name(format!(".{}.dlstate", file_name));
+        let state: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(state_path).unwrap()).unwrap();
+        assert_eq!(state["downloaded_bytes"], 1_000);
+        assert!(output_path.with_file_name(format!("{}.part", file_name)).exists());
+
+        let stats = pipeline.stats().await;
+        assert_eq!(stats.active_jobs, 0);
+        assert_eq!((stats.completed_jobs, stats.failed_jobs), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_jobs_after_grace() {
+        use std::time::{Duration, Instant};
+
+        let slow = file_handler(test_body(100_000));
+        let base = serve_with(
+            Arc::new(move |request: &TestRequest| match request.path.as_str() {
+                "/page" => TestResponse {
+                    status: 200,
+                    headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                    body: br#"<video src="/slow.mp4"></video>"#.to_vec(),
+                },
+                _ => slow(request),
+            }),
+            Some(1_000),
+        )
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let storage_config = StorageConfig::local(&dir.path().to_string_lossy());
+        let pipeline = ScrapingPipeline::new(&config, &storage_config).unwrap();
+        pipeline.add_url(&format!("{}/page", base)).await.unwrap();
+
+        let grace = Duration::from_millis(200);
+        let ((), (finished, elapsed)) = tokio::join!(pipeline.run(1, None), async {
+            while pipeline.stats().await.active_jobs == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            // Let the job reach its stalled download
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let started = Instant::now();
+            (pipeline.shutdown(grace).await, started.elapsed())
+        });
+        assert!(!finished);
+        assert!(elapsed >= grace && elapsed < grace * 5, "{:?}", elapsed);
+
+        let job = &pipeline.jobs().await[0];
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.error_message.as_deref(), Some("Interrupted by shutdown"));
+        let stats = pipeline.stats().await;
+        assert_eq!((stats.total_jobs, stats.pending_jobs, stats.active_jobs), (1, 0, 0));
+        assert_eq!((stats.completed_jobs, stats.failed_jobs, stats.cancelled_jobs), (0, 0, 1));
+
+        // A restored pipeline picks the cancelled job up again
+        let checkpoint = dir.path().join("checkpoint.json");
+        pipeline.checkpoint(&checkpoint).await.unwrap();
+        let restored = ScrapingPipeline::restore(&checkpoint, &config, &storage_config)
+            .await
+            .unwrap();
+        assert_eq!(restored.stats().await.pending_jobs, 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_run_crawls_and_returns_when_done() {
+        let video = file_handler(test_body(10_000));
+        let page = |html: &'static str| TestResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            body: html.as_bytes().to_vec(),
+        };
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/index" => page(r#"<a href="/detail">detail</a>"#),
+            "/detail" => page(r#"<video src="/clip.mp4"></video>"#),
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            max_crawl_depth: 1,
+            ..test_scraper_config()
+        };
+        let pipeline =
+            ScrapingPipeline::new(&config, &StorageConfig::local(&dir.path().to_string_lossy()))
+                .unwrap();
+        pipeline.add_url(&format!("{}/index", base)).await.unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(10), pipeline.run(4, None))
+            .await
+            .expect("batch run should return once the queue drains");
+
+        let jobs = pipeline.jobs().await;
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs.iter().all(|j| j.status == JobStatus::Completed));
+        let detail = jobs.iter().find(|j| j.depth == 1).unwrap();
+        assert_eq!(detail.bytes_downloaded, 10_000);
+
+        let stats = pipeline.stats().await;
+        assert_eq!(stats.total_bytes_downloaded, 10_000);
+        assert!(stats.avg_download_speed > 0.0);
+        assert_eq!(stats.recent_download_speed, stats.avg_download_speed);
+    }
+
+    #[tokio::test]
+    async fn test_output_extension_follows_content_type() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let heads = Arc::new(AtomicUsize::new(0));
+        let counter = heads.clone();
+        let video = file_handler(test_body(2_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/page" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            _ => {
+                if request.method == "HEAD" {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+                let mut response = video(request);
+                let content_type = ("Content-Type".to_string(), "video/webm".to_string());
+                response.headers.push(content_type);
+                response
+            }
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = ScrapingPipeline::new(
+            &test_scraper_config(),
+            &StorageConfig::local(&dir.path().to_string_lossy()),
+        )
+        .unwrap();
+        pipeline.add_url(&format!("{}/page", base)).await.unwrap();
+        pipeline.run(1, None).await;
+
+        let job = &pipeline.jobs().await[0];
+        assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error_message);
+        let output_path = job.output_path.as_deref().unwrap();
+        assert!(output_path.ends_with(".webm"), "{}", output_path);
+        assert!(std::path::Path::new(output_path).exists());
+        // The job's probe is handed to the download rather than repeated
+        assert_eq!(heads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_render_output_template() {
+        let value = |name: &str| match name {
+            "title" => Some("  Big Buck Bunny: Director's Cut / 2008 ".to_string()),
+            "id" => Some("../../etc".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            template::render("{title}.{ext}", value),
+            "Big_Buck_Bunny__Director's_Cut___2008.{ext}"
+        );
+        assert_eq!(template::render("out/{id}", value), "out/_.._etc");
+        assert_eq!(template::placeholders("{host}/{title}_{quality"), ["host", "title"]);
+        assert_eq!(template::with_suffix("a.b/clip.mp4", 2), "a.b/clip_2.mp4");
+        assert_eq!(template::with_suffix("clip", 1), "clip_1");
+
+        let storage = StorageConfig {
+            output_template: "../{title}_{resolution}.{ext}".to_string(),
+            ..StorageConfig::default()
+        };
+        let problems = storage.validate().unwrap_err();
+        assert!(problems[0].contains("{resolution}"), "{:?}", problems);
+        assert!(problems[1].contains("relative"), "{:?}", problems);
+    }
+
+    #[tokio::test]
+    async fn test_output_template_names_and_collisions() {
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/a" | "/b" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<title>Big Buck Bunny</title><video src="/bbb_1080p.mp4"></video>"#
+                    .to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageConfig {
+            output_template: "{host}/{title}_{quality}.{ext}".to_string(),
+            ..StorageConfig::local(&dir.path().to_string_lossy())
+        };
+        let pipeline = ScrapingPipeline::new(&test_scraper_config(), &storage).unwrap();
+        pipeline.add_url(&format!("{}/a", base)).await.unwrap();
+        pipeline.add_url(&format!("{}/b", base)).await.unwrap();
+        pipeline.run(2, None).await;
+
+        let mut names: Vec<String> = pipeline
+            .jobs()
+            .await
+            .iter()
+            .map(|job| job.output_path.clone().unwrap())
+            .collect();
+        names.sort();
+        let host_dir = dir.path().join("127.0.0.1");
+        assert_eq!(
+            names,
+            [
+                host_dir.join("Big_Buck_Bunny_1080p.mp4").to_string_lossy(),
+                host_dir.join("Big_Buck_Bunny_1080p_1.mp4").to_string_lossy(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_template_uses_tags_and_job_overrides() {
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/a" | "/b" | "/c" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<title>Big Buck Bunny</title><video src="/bbb.mp4"></video>"#.to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageConfig {
+            key_template: "{tag:source}/{year}/{month}/{title}.{ext}".to_string(),
+            ..StorageConfig::local(&dir.path().to_string_lossy())
+        };
+        let pipeline = ScrapingPipeline::new(&test_scraper_config(), &storage).unwrap();
+        let tags = HashMap::from([("source".to_string(), "news feed".to_string())]);
+        let (a, b, c) = (format!("{}/a", base), format!("{}/b", base), format!("{}/c", base));
+        let invalid = Some("../{id}.{ext}".to_string());
+        let rejected = pipeline.add_url_with_tags(&a, 0, tags.clone(), invalid).await;
+        assert!(matches!(rejected, Err(ScraperError::ConfigError(_))));
+
+        let a = pipeline.add_url_with_tags(&a, 0, tags.clone(), None).await.unwrap();
+        pipeline.add_url_with_tags(&b, 0, tags, None).await.unwrap();
+        let custom = Some("custom/{id}.{ext}".to_string());
+        let c = pipeline.add_url_with_tags(&c, 0, HashMap::new(), custom).await.unwrap();
+        pipeline.run(1, None).await;
+
+        let created = chrono::DateTime::parse_from_rfc3339(&a.created_at).unwrap();
+        let date_dir = format!("news_feed/{}", created.format("%Y/%m"));
+        let mut keys: Vec<String> = pipeline
+            .jobs()
+            .await
+            .iter()
+            .map(|job| job.storage_key.clone().unwrap())
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                format!("custom/{}.mp4", c.id),
+                format!("{}/Big_Buck_Bunny.mp4", date_dir),
+                format!("{}/Big_Buck_Bunny_1.mp4", date_dir),
+            ]
+        );
+        for key in keys {
+            assert!(dir.path().join(key).exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dedup_store_skips_earlier_downloads() {
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/a" | "/b" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4?utm_source=feed"></video>"#.to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            dedup_store_path: Some(dir.path().join("seen.jsonl").to_string_lossy().into()),
+            ..test_scraper_config()
+        };
+        let storage = StorageConfig::local(&dir.path().join("out").to_string_lossy());
+        let run = |urls: &'static [&'static str]| {
+            let pipeline = ScrapingPipeline::new(&config, &storage).unwrap();
+            let base = base.clone();
+            async move {
+                for path in urls {
+                    pipeline.add_url(&format!("{}{}", base, path)).await.unwrap();
+                }
+                pipeline.run(1, None).await;
+                pipeline
+            }
+        };
+
+        // `/b` shows the video `/a` just downloaded
+        let first = run(&["/a", "/b"]).await;
+        let statuses: Vec<JobStatus> = first.jobs().await.into_iter().map(|j| j.status).collect();
+        assert_eq!(statuses, [JobStatus::Completed, JobStatus::Skipped]);
+        let entries = first.dedup_store().unwrap().entries().await;
+        assert_eq!(entries.len(), 1);
+
+        // A later run skips `/a` without fetching it
+        let second = run(&["/a"]).await;
+        let job = &second.jobs().await[0];
+        assert_eq!(job.status, JobStatus::Skipped);
+        assert_eq!(job.storage_key, entries[0].storage_key);
+        assert_eq!(second.stats().await.skipped_jobs, 1);
+
+        let store = second.dedup_store().unwrap();
+        assert_eq!(store.prune(std::time::Duration::from_secs(3600)).await.unwrap(), 0);
+        assert_eq!(store.prune(std::time::Duration::ZERO).await.unwrap(), 1);
+        let third = run(&["/a"]).await;
+        assert_eq!(third.jobs().await[0].status, JobStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_retries_transient_errors() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let gets = Arc::new(AtomicUsize::new(0));
+        let counter = gets.clone();
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/page" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            "/clip.mp4" => {
+                // The first two downloads hit a 503
+                if request.method == "GET" && counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return TestResponse { status: 503, headers: Vec::new(), body: Vec::new() };
+                }
+                video(request)
+            }
+            _ => TestResponse { status: 404, headers: Vec::new(), body: Vec::new() },
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            max_job_retries: 2,
+            retry_delay_ms: 10,
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let storage = StorageConfig::local(&dir.path().to_string_lossy());
+        let pipeline = ScrapingPipeline::new(&config, &storage).unwrap();
+        let (flaky, missing) = (format!("{}/page", base), format!("{}/missing", base));
+        let flaky = pipeline.add_url(&flaky).await.unwrap().id;
+        let missing = pipeline.add_url(&missing).await.unwrap().id;
+        pipeline.run(1, None).await;
+
+        let job = pipeline.get_job(&flaky).await.unwrap();
+        assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error_message);
+        assert_eq!(job.attempts, 3);
+        assert_eq!(gets.load(Ordering::SeqCst), 3);
+
+        let job = pipeline.get_job(&missing).await.unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.attempts, 1);
+
+        // A full disk or a permission error won't clear up by waiting
+        let disk_full = std::io::Error::from(std::io::ErrorKind::StorageFull);
+        assert!(!ScraperError::IoError(disk_full).is_retryable());
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(!ScraperError::IoError(denied).is_retryable());
+        let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        assert!(ScraperError::IoError(reset).is_retryable());
+
+        // Nor is a download its progress callback stopped
+        let stop: ProgressCallback = Arc::new(|_: &DownloadProgress| {
+            Err(ScraperError::DownloadFailed { attempts: 1, message: "stop".to_string() })
+        });
+        let manager = DownloadManager::new(Arc::new(HttpClient::new(&config).unwrap()), &config);
+        let options = DownloadOptions { progress: Some(stop), ..Default::default() };
+        let url = format!("{}/clip.mp4", base);
+        let err = manager
+            .download_with_options(&url, &dir.path().join("stopped.mp4"), &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScraperError::Aborted(_)), "{:?}", err);
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_uploads_downloads_to_storage() {
+        let body = test_body(1_000);
+        let video = file_handler(body.clone());
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/page" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let (staging, remote) = (dir.path().join("staging"), dir.path().join("remote"));
+        let storage = StorageConfig {
+            local_path: staging.to_string_lossy().to_string(),
+            delete_local_after_upload: true,
+            ..StorageConfig::fanout(vec![StorageConfig::local(&remote.to_string_lossy())])
+        };
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let pipeline = ScrapingPipeline::new(&config, &storage).unwrap();
+        pipeline.add_url(&format!("{}/page", base)).await.unwrap();
+        pipeline.run(1, None).await;
+
+        let job = pipeline.jobs().await.remove(0);
+        assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error_message);
+        let key = job.storage_key.unwrap();
+        assert_eq!(std::fs::read(remote.join(&key)).unwrap(), body);
+        assert_eq!(pipeline.stats().await.total_bytes_uploaded, 1_000);
+        // The staged copy is gone once uploaded
+        assert!(job.output_path.is_none());
+        assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retried_upload_reuses_the_jobs_download() {
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/page" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let (staging, remote) = (dir.path().join("staging"), dir.path().join("remote"));
+        // Uploads fail until the remote directory can be created
+        std::fs::write(&remote, b"not a directory").unwrap();
+        let storage = StorageConfig {
+            local_path: staging.to_string_lossy().to_string(),
+            ..StorageConfig::fanout(vec![StorageConfig::local(&remote.to_string_lossy())])
+        };
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let pipeline = ScrapingPipeline::new(&config, &storage).unwrap();
+        pipeline.add_url(&format!("{}/page", base)).await.unwrap();
+        pipeline.run(1, None).await;
+        let failed = pipeline.jobs().await.remove(0);
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert!(failed.error_message.as_ref().unwrap().starts_with("Upload failed"));
+
+        std::fs::remove_file(&remote).unwrap();
+        assert_eq!(pipeline.retry_failed().await.unwrap(), 1);
+        pipeline.run(1, None).await;
+        let job = pipeline.jobs().await.remove(0);
+        assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error_message);
+        assert_eq!(job.output_path, failed.output_path);
+        assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 1);
+        assert!(remote.join(job.storage_key.unwrap()).exists());
+    }
+
+    #[tokio::test]
+    async fn test_retry_failed_requeues_jobs() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let up = Arc::new(AtomicBool::new(false));
+        let online = up.clone();
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| {
+            if !online.load(Ordering::SeqCst) {
+                return TestResponse { status: 404, headers: Vec::new(), body: Vec::new() };
+            }
+            match request.path.as_str() {
+                "/page" => TestResponse {
+                    status: 200,
+                    headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                    body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+                },
+                _ => video(request),
+            }
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScraperConfig {
+            respect_robots_txt: false,
+            ..test_scraper_config()
+        };
+        let storage = StorageConfig::local(&dir.path().to_string_lossy());
+        let pipeline = ScrapingPipeline::new(&config, &storage).unwrap();
+        let page = format!("{}/page", base);
+        let id = pipeline.add_url(&page).await.unwrap().id;
+        pipeline.run(1, None).await;
+        assert_eq!(pipeline.get_job(&id).await.unwrap().status, JobStatus::Failed);
+        assert_eq!(pipeline.stats().await.failed_jobs, 1);
+
+        up.store(true, Ordering::SeqCst);
+        assert_eq!(pipeline.retry_failed().await.unwrap(), 1);
+        let job = pipeline.get_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Pending);
+        assert!(job.error_message.is_none() && job.completed_at.is_none());
+        let stats = pipeline.stats().await;
+        assert_eq!((stats.failed_jobs, stats.pending_jobs), (0, 1));
+        // Nothing is left failed to retry
+        assert_eq!(pipeline.retry_failed().await.unwrap(), 0);
+
+        pipeline.run(1, None).await;
+        let job = pipeline.get_job(&id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error_message);
+        let stats = pipeline.stats().await;
+        assert_eq!((stats.failed_jobs, stats.completed_jobs), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_runs_higher_priority_first() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let base = serve(Arc::new(move |request: &TestRequest| {
+            if request.path != "/robots.txt" {
+                log.lock().unwrap().push(request.path.clone());
+            }
+            TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: b"<p>no videos</p>".to_vec(),
+            }
+        }))
+        .await;
+
+        let pipeline =
+            ScrapingPipeline::new(&test_scraper_config(), &StorageConfig::default()).unwrap();
+        for (path, priority) in [("/low-1", 0), ("/high", 10), ("/low-2", 0), ("/mid", 5)] {
+            let url = format!("{}{}", base, path);
+            let job = pipeline.add_url_with_priority(&url, priority).await.unwrap();
+            assert_eq!(job.priority, priority);
+        }
+        pipeline.run(1, None).await;
+
+        assert_eq!(*seen.lock().unwrap(), ["/high", "/mid", "/low-1", "/low-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_job_callback_sees_each_finished_job() {
+        let video = file_handler(test_body(1_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/good" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            "/empty" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: b"<p>nothing here</p>".to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = ScrapingPipeline::new(
+            &test_scraper_config(),
+            &StorageConfig::local(&dir.path().to_string_lossy()),
+        )
+        .unwrap();
+        let finished = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = finished.clone();
+        pipeline.set_job_callback(Some(Arc::new(move |job: &ScrapeJob| {
+            log.lock().unwrap().push((job.source_url.clone(), job.status.clone()));
+        })));
+
+        pipeline.add_url(&format!("{}/good", base)).await.unwrap();
+        pipeline.add_url(&format!("{}/empty", base)).await.unwrap();
+        pipeline.run(2, None).await;
+
+        let mut finished = finished.lock().unwrap().clone();
+        finished.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            finished,
+            [
+                (format!("{}/empty", base), JobStatus::Failed),
+                (format!("{}/good", base), JobStatus::Completed),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_progress_events_carry_job_id() {
+        let video = file_handler(test_body(50_000));
+        let base = serve(Arc::new(move |request: &TestRequest| match request.path.as_str() {
+            "/page" => TestResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+                body: br#"<video src="/clip.mp4"></video>"#.to_vec(),
+            },
+            _ => video(request),
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = ScrapingPipeline::new(
+            &test_scraper_config(),
+            &StorageConfig::local(&dir.path().to_string_lossy()),
+        )
+        .unwrap();
+        let mut progress = pipeline.subscribe_progress();
+
+        let job = pipeline.add_url(&format!("{}/page", base)).await.unwrap();
+        pipeline.run(1, None).await;
+
+        let mut events = Vec::new();
+        while let Ok(event) = progress.try_recv() {
+            events.push(event);
+        }
+        let last = events.last().expect("no progress events");
+        assert_eq!(last.status, "completed");
+        assert_eq!(last.downloaded_bytes, 50_000);
+        assert!(events.iter().all(|event| event.job_id.as_deref() == Some(job.id.as_str())));
+    }
+
+    #[test]
+    fn test_select_video_by_quality() {
+        let video = |url: &str, height: Option<u32>| -> VideoInfo {
+            serde_json::from_value(serde_json::json!({
+                "url": url,
+                "height": height,
+                "source_page": "https://example.com/",
+            }))
+            .unwrap()
+        };
+        let videos = vec![
+            video("https://cdn.example.com/low.mp4", Some(360)),
+            video("https://cdn.example.com/hd.mp4", Some(1080)),
+            video("https://cdn.example.com/mid_720p.mp4", None),
+        ];
+        let mut pipeline =
+            ScrapingPipeline::new(&test_scraper_config(), &StorageConfig::default()).unwrap();
+
+        let best = pipeline.select_video(videos.clone(), None).unwrap();
+        assert_eq!(best.height, Some(1080));
+
+        let filter = VideoFilter {
+            quality_preference: vec!["720p".to_string(), "1080p".to_string()],
+            ..VideoFilter::default()
+        };
+        let preferred = pipeline.select_video(videos.clone(), Some(&filter)).unwrap();
+        assert_eq!(preferred.url, "https://cdn.example.com/mid_720p.mp4");
+
+        pipeline.set_prefer_highest_quality(false);
+        let first = pipeline.select_video(videos, None).unwrap();
+        assert_eq!(first.height, Some(360));
+    }
+
+    #[test]
+    fn test_codec_hints_and_filter() {
+        let html = r#"<html><body>
+            <video><source src="/av1.mp4" type='video/mp4; codecs="av01.0.05M.08, opus"'></video>
+            <video src="/clip.webm"></video>
+            <video src="/clip.mp4"></video>
+            <video src="/clip.mkv"></video>
+            </body></html>"#;
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_html(html, "https://example.com/")
+            .unwrap();
+        let codecs: Vec<_> = videos.iter().map(|v| v.codec.as_deref()).collect();
+        assert_eq!(codecs, vec![Some("av01.0.05M.08"), Some("vp9"), Some("h264"), None]);
+
+        // Unknown codecs pass, like the other lenient checks
+        let filter = VideoFilter {
+            allowed_codecs: vec!["avc1".to_string(), "vp9".to_string()],
+            ..VideoFilter::default()
+        };
+        let kept: Vec<_> = videos.iter().filter(|v| filter.matches(v)).map(|v| &v.url).collect();
+        assert_eq!(
+            kept,
+            vec![
+                "https://example.com/clip.webm",
+                "https://example.com/clip.mp4",
+                "https://example.com/clip.mkv"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_manifest_variants_by_fps_and_bitrate() {
+        let master = "#EXTM3U\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=400000,RESOLUTION=640x360,FRAME-RATE=30\nlow.m3u8\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=3000000,RESOLUTION=1280x720,FRAME-RATE=30,\
+            CODECS=\"avc1.64001f,mp4a.40.2\"\nmid.m3u8\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=8000000,RESOLUTION=1920x1080,FRAME-RATE=60\nhigh.m3u8\n";
+        let base = serve(Arc::new(move |_: &TestRequest| TestResponse {
+            status: 200,
+            headers: vec![],
+            body: master.as_bytes().to_vec(),
+        }))
+        .await;
+
+        let client = Arc::new(HttpClient::new(&test_scraper_config()).unwrap());
+        let videos = VideoExtractor::new(client)
+            .extract_from_url(&format!("{}/master.m3u8", base))
+            .await
+            .unwrap();
+        let mid = videos.iter().find(|v| v.url.ends_with("mid.m3u8")).unwrap();
+        assert_eq!((mid.fps, mid.tbr), (Some(30), Some(3000.0)));
+        assert_eq!(mid.codec.as_deref(), Some("avc1.64001f"));
+
+        let filter = VideoFilter {
+            max_fps: Some(30),
+            min_bitrate: Some(1000.0),
+            ..VideoFilter::default()
+        };
+        let kept: Vec<_> = videos.iter().filter(|v| filter.matches(v)).collect();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].url.ends_with("mid.m3u8"));
+    }
+
+    #[tokio::test]
+    async fn test_local_list_keys_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(&dir.path().to_string_lossy()).unwrap();
+        storage.put("videos/a.mp4", bytes::Bytes::from_static(b"a")).await.unwrap();
+        storage.put("videos/b.mp4", bytes::Bytes::from_static(b"bb")).await.unwrap();
+        storage.put("videos/2024/01/c.mp4", bytes::Bytes::from_static(b"ccc")).await.unwrap();
+        storage.put("other/d.mp4", bytes::Bytes::from_static(b"d")).await.unwrap();
+
+        let mut listed = storage.list("videos/").await.unwrap();
+        listed.sort_by(|a, b| a.key.cmp(&b.key));
+        let keys: Vec<_> = listed.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, ["videos/2024/01/c.mp4", "videos/a.mp4", "videos/b.mp4"]);
+
+        let partial = storage.list("videos/2").await.unwrap();
+        assert_eq!(partial.len(), 1);
+
+        for object in &listed {
+            let data = storage.get(&object.key).await.unwrap();
+            assert_eq!(data.len() as u64, object.size_bytes);
+        }
+
+        let range = storage.get_range("videos/2024/01/c.mp4", 1, 2).await.unwrap();
+        assert_eq!(&range[..], b"c");
+        let tail = storage.get_range("videos/b.mp4", 1, 100).await.unwrap();
+        assert_eq!(&tail[..], b"b");
+        assert!(storage.get_range("videos/b.mp4", 2, 1).await.is_err());
+
+        let mut keys: Vec<String> = listed.into_iter().map(|o| o.key).collect();
+        keys.push("videos/missing.mp4".to_string());
+        storage.delete_batch(&keys).await.unwrap();
+        assert!(storage.list("videos/").await.unwrap().is_empty());
+        assert!(storage.exists("other/d.mp4").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_local_get_range_and_delete_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(&dir.path().to_string_lossy()).unwrap();
+        storage.put("clips/a.mp4", bytes::Bytes::from_static(b"0123456789")).await.unwrap();
+
+        // Ranges are half-open and clipped to the object
+        assert_eq!(&storage.get_range("clips/a.mp4", 2, 5).await.unwrap()[..], b"234");
+        assert_eq!(&storage.get_range("clips/a.mp4", 8, 20).await.unwrap()[..], b"89");
+        assert!(storage.get_range("clips/a.mp4", 4, 4).await.unwrap().is_empty());
+        assert!(storage.get_range("clips/a.mp4", 5, 4).await.is_err());
+        assert!(storage.get_range("clips/missing.mp4", 0, 1).await.is_err());
+
+        // Missing keys are skipped, and no keys is a no-op
+        storage.put("clips/b.mp4", bytes::Bytes::from_static(b"b")).await.unwrap();
+        storage.put("keep.mp4", bytes::Bytes::from_static(b"k")).await.unwrap();
+        storage.delete_batch(&[]).await.unwrap();
+        let keys = ["clips/a.mp4", "clips/b.mp4", "clips/gone.mp4"].map(String::from);
+        storage.delete_batch(&keys).await.unwrap();
+        assert!(!storage.exists("clips/a.mp4").await.unwrap());
+        assert!(!storage.exists("clips/b.mp4").await.unwrap());
+        assert!(storage.exists("keep.mp4").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_fanout_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second) = (dir.path().join("first"), dir.path().join("second"));
+        let children: Vec<Arc<dyn StorageBackend>> = vec![
+            Arc::new(LocalStorage::new(&first.to_string_lossy()).unwrap()),
+            Arc::new(LocalStorage::new(&second.to_string_lossy()).unwrap()),
+        ];
+        let fanout = storage::FanoutStorage::new(children).unwrap();
+
+        fanout.put("videos/a.mp4", bytes::Bytes::from_static(b"a")).await.unwrap();
+        assert!(first.join("videos/a.mp4").exists() && second.join("videos/a.mp4").exists());
+        // Reads fall through to the next backend, listings merge
+        std::fs::remove_file(first.join("videos/a.mp4")).unwrap();
+        std::fs::write(first.join("videos/b.mp4"), b"bb").unwrap();
+        assert_eq!(&fanout.get("videos/a.mp4").await.unwrap()[..], b"a");
+        assert!(fanout.exists("videos/a.mp4").await.unwrap());
+        let mut keys: Vec<_> = fanout
+            .list("videos/")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|o| o.key)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["videos/a.mp4", "videos/b.mp4"]);
+
+        fanout.delete("videos/b.mp4").await.unwrap();
+        assert!(!fanout.exists("videos/b.mp4").await.unwrap());
+
+        // A stream is written once and stored on every backend
+        use futures::StreamExt;
+        let chunks = ["st", "ream"].map(|c| Ok(bytes::Bytes::from_static(c.as_bytes())));
+        let stored = fanout
+            .put_stream("videos/s.mp4", futures::stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        assert_eq!(stored.size_bytes, 6);
+        assert_eq!(std::fs::read(first.join("videos/s.mp4")).unwrap(), b"stream");
+        assert_eq!(std::fs::read(second.join("videos/s.mp4")).unwrap(), b"stream");
+
+        // With the second backend's directory gone, writes fail naming it
+        // and the backend they landed on anyway
+        std::fs::remove_dir_all(&second).unwrap();
+        std::fs::write(&second, b"not a directory").unwrap();
+        let err = fanout
+            .put("videos/c.mp4", bytes::Bytes::from_static(b"c"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("failed on 1 of 2 backends: backend 2 (local)"), "{}", err);
+        assert!(err.contains("succeeded on backend 1 (local)"), "{}", err);
+        assert!(fanout.exists("videos/c.mp4").await.unwrap());
+    }
+
+    #[cfg(feature = "azure")]
+    #[tokio::test]
+    async fn test_azure_keys_and_block_sizes() {
+        use storage::{azure_block_size, AzureBlobStorage};
+
+        let config = StorageConfig::azure("acct", "media", Some("c2VjcmV0"), None, Some("clips/"));
+        let azure = AzureBlobStorage::new(&config).unwrap();
+        let url = azure.presign_get("a.mp4", 60).await.unwrap();
+        let signed = "https://acct.blob.core.windows.net/media/clips/a.mp4?";
+        assert!(url.starts_with(signed), "{}", url);
+        assert!(url.contains("sp=r"), "{}", url);
+
+        // Signing needs the account key, not a SAS token
+        let config = StorageConfig::azure("acct", "media", None, Some("sv=2022&sig=x"), None);
+        let azure = AzureBlobStorage::new(&config).unwrap();
+        assert!(azure.presign_get("a.mp4", 60).await.is_err());
+        let config = StorageConfig::azure("acct", "media", None, None, None);
+        assert!(matches!(AzureBlobStorage::new(&config), Err(ScraperError::ConfigError(_))));
+
+        // Blocks grow past the part size only when a blob would need over 50,000
+        let mib = 1024 * 1024;
+        assert_eq!(azure_block_size(8 * mib, 100 * mib), 8 * mib);
+        assert_eq!(azure_block_size(8 * mib, 1_000_000 * mib), 20 * mib);
+        assert_eq!(azure_block_size(0, 0), 1);
+    }
+
+    /// Local storage without its `copy` and `rename` overrides, to exercise
+    /// the trait defaults
+    struct DefaultCopyStorage(LocalStorage);
+
+    #[async_trait::async_trait]
+    impl StorageBackend for DefaultCopyStorage {
+        async fn put(&self, key: &str, data: bytes::Bytes) -> Result<ObjectMetadata> {
+            self.0.put(key, data).await
+        }
+        async fn put_file(&self, key: &str, path: &std::path::Path) -> Result<ObjectMetadata> {
+            self.0.put_file(key, path).await
+        }
+        async fn get(&self, key: &str) -> Result<bytes::Bytes> {
+            self.0.get(key).await
+        }
+        async fn get_file(&self, key: &str, path: &std::path::Path) -> Result<()> {
+            self.0.get_file(key, path).await
+        }
+        async fn exists(&self, key: &str) -> Result<bool> {
+            self.0.exists(key).await
+        }
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.0.delete(key).await
+        }
+        async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>> {
+            self.0.list(prefix).await
+        }
+        async fn metadata(&self, key: &str) -> Result<ObjectMetadata> {
+            self.0.metadata(key).await
+        }
+        fn backend_type(&self) -> &str {
+            "default-copy"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_rename_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = LocalStorage::new(&dir.path().join("local").to_string_lossy()).unwrap();
+        let plain = DefaultCopyStorage(
+            LocalStorage::new(&dir.path().join("plain").to_string_lossy()).unwrap(),
+        );
+        let backends: [&dyn StorageBackend; 2] = [&local, &plain];
+
+        for storage in backends {
+            let name = storage.backend_type();
+            storage.put("a.mp4", bytes::Bytes::from_static(b"video")).await.unwrap();
+
+            let copied = storage.copy("a.mp4", "copies/b.mp4").await.unwrap();
+            assert_eq!((copied.key.as_str(), copied.size_bytes), ("copies/b.mp4", 5), "{}", name);
+            assert_eq!(&storage.get("a.mp4").await.unwrap()[..], b"video", "{}", name);
+
+            let moved = storage.rename("copies/b.mp4", "moved/c.mp4").await.unwrap();
+            assert_eq!(moved.key, "moved/c.mp4", "{}", name);
+            assert!(!storage.exists("copies/b.mp4").await.unwrap(), "{}", name);
+            assert_eq!(&storage.get("moved/c.mp4").await.unwrap()[..], b"video", "{}", name);
+
+            // A missing source is an error, not a silent copy attempt
+            assert!(storage.rename("missing.mp4", "d.mp4").await.is_err(), "{}", name);
+            assert!(!storage.exists("d.mp4").await.unwrap(), "{}", name);
+        }
+    }
 }
 
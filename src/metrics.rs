@@ -0,0 +1,94 @@
+//! Download counters and Prometheus text exposition
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Prefix for every exported metric name
+const PREFIX: &str = "videoscraper";
+
+/// Counters and gauges for a download manager, shared by its clones
+#[derive(Debug, Default)]
+pub struct DownloadMetrics {
+    /// Downloads waiting for a concurrency slot
+    pub(crate) queued: AtomicU64,
+    /// Downloads holding a slot
+    pub(crate) active: AtomicU64,
+    pub(crate) completed: AtomicU64,
+    pub(crate) failed: AtomicU64,
+    /// Bytes of completed downloads
+    pub(crate) bytes: AtomicU64,
+}
+
+impl DownloadMetrics {
+    /// Count a finished download of `bytes`, or a failure when `None`
+    pub(crate) fn record(&self, bytes: Option<u64>) {
+        match bytes {
+            Some(bytes) => {
+                self.completed.fetch_add(1, Ordering::SeqCst);
+                self.bytes.fetch_add(bytes, Ordering::SeqCst);
+            }
+            None => {
+                self.failed.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Append these metrics to `out` in Prometheus exposition format
+    pub fn render(&self, out: &mut String) {
+        let load = |value: &AtomicU64| value.load(Ordering::SeqCst);
+        write_metric(
+            out,
+            "downloads_queued",
+            Kind::Gauge,
+            "Downloads waiting for a concurrency slot",
+            load(&self.queued),
+        );
+        write_metric(
+            out,
+            "downloads_active",
+            Kind::Gauge,
+            "Downloads in progress",
+            load(&self.active),
+        );
+        write_metric(
+            out,
+            "downloads_completed_total",
+            Kind::Counter,
+            "Downloads that finished successfully",
+            load(&self.completed),
+        );
+        write_metric(
+            out,
+            "downloads_failed_total",
+            Kind::Counter,
+            "Downloads that failed or were cancelled",
+            load(&self.failed),
+        );
+        write_metric(
+            out,
+            "downloaded_bytes_total",
+            Kind::Counter,
+            "Bytes received by completed downloads",
+            load(&self.bytes),
+        );
+    }
+}
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Kind {
+    Counter,
+    Gauge,
+}
+
+/// Append one unlabelled sample with its `HELP` and `TYPE` lines
+pub(crate) fn write_metric(out: &mut String, name: &str, kind: Kind, help: &str, value: u64) {
+    let kind = match kind {
+        Kind::Counter => "counter",
+        Kind::Gauge => "gauge",
+    };
+    // Writing to a String cannot fail
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} {kind}");
+    let _ = writeln!(out, "{PREFIX}_{name} {value}");
+}
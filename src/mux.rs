@@ -0,0 +1,71 @@
+//! Merging separate video and audio streams with an external ffmpeg
+
+use crate::error::{Result, ScraperError};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Fail with a clear error unless `ffmpeg` can be run, so a merge fails
+/// before its streams are downloaded rather than after
+pub async fn check_ffmpeg(ffmpeg: &str) -> Result<()> {
+    let status = Command::new(ffmpeg)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(ScraperError::ConfigError(format!(
+            "{} -version exited with {}",
+            ffmpeg, status
+        ))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(not_found(ffmpeg)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Copy the first video stream of `video` and the first audio stream of
+/// `audio` into `output` without re-encoding. The container follows the
+/// extension of `output`.
+pub async fn mux_with_ffmpeg(
+    ffmpeg: &str,
+    video: &Path,
+    audio: &Path,
+    output: &Path,
+) -> Result<()> {
+    let result = Command::new(ffmpeg)
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-y", "-i"])
+        .arg(video)
+        .arg("-i")
+        .arg(audio)
+        .args(["-map", "0:v:0", "-map", "1:a:0", "-c", "copy"])
+        .arg(output)
+        .stdin(Stdio::null())
+        .output()
+        .await;
+    let finished = match result {
+        Ok(finished) => finished,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(not_found(ffmpeg)),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !finished.status.success() {
+        let stderr = String::from_utf8_lossy(&finished.stderr);
+        return Err(ScraperError::InvalidFormat(format!(
+            "ffmpeg could not merge {:?} and {:?}: {}",
+            video,
+            audio,
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+fn not_found(ffmpeg: &str) -> ScraperError {
+    ScraperError::ConfigError(format!(
+        "{} was not found; install ffmpeg or set ffmpeg_path to merge video and audio streams",
+        ffmpeg
+    ))
+}
@@ -1,21 +1,30 @@
 //! Pipeline orchestration for video scraping workflows
 
 use crate::client::HttpClient;
-use crate::config::{ScraperConfig, StorageConfig};
-use crate::downloader::{DownloadManager, DownloadProgress, DownloadResult};
+use crate::config::{template_problems, ScraperConfig, StorageConfig};
+use crate::dedup::{DedupEntry, DedupStore};
+use crate::downloader::{
+    DownloadManager, DownloadOptions, DownloadProgress, DownloadResult, ProgressCallback,
+};
 use crate::error::{Result, ScraperError};
-use crate::extractor::{VideoExtractor, VideoInfo};
-use crate::storage::{LocalStorage, StorageBackend};
-use async_channel::{bounded, Receiver, Sender};
-use futures::stream::{self, StreamExt};
+use crate::extractor::{codec_family, extensions_for_mime, VideoExtractor, VideoInfo};
+use crate::hls;
+use crate::metrics::{write_metric, Kind};
+use crate::storage::{ObjectMetadata, StorageManager};
+use crate::template;
+use futures::stream::{self, BoxStream, StreamExt};
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
+use url::Url;
 use uuid::Uuid;
 
 /// Job status in the pipeline
@@ -29,6 +38,8 @@ pub enum JobStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Downloaded by an earlier run, according to the dedup store
+    Skipped,
 }
 
 #[pymethods]
@@ -60,6 +71,28 @@ pub struct ScrapeJob {
     pub bytes_downloaded: u64,
     #[pyo3(get)]
     pub total_bytes: Option<u64>,
+    /// Tries at the job: one per run, plus one per stage retried after a
+    /// transient error
+    #[pyo3(get)]
+    #[serde(default)]
+    pub attempts: u32,
+    /// Links followed from a user-added URL to reach this page
+    #[pyo3(get)]
+    #[serde(default)]
+    pub depth: u32,
+    /// Queued jobs with a higher priority run first; equal priorities run in
+    /// the order they were added
+    #[pyo3(get)]
+    #[serde(default)]
+    pub priority: i32,
+    /// Caller metadata, available to key templates as `{tag:NAME}`
+    #[pyo3(get)]
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    /// Storage key template overriding `StorageConfig::key_template`
+    #[pyo3(get)]
+    #[serde(default)]
+    pub key_template: Option<String>,
     #[pyo3(get)]
     pub created_at: String,
     #[pyo3(get)]
@@ -78,6 +111,11 @@ impl ScrapeJob {
             error_message: None,
             bytes_downloaded: 0,
             total_bytes: None,
+            attempts: 0,
+            depth: 0,
+            priority: 0,
+            tags: HashMap::new(),
+            key_template: None,
             created_at: chrono::Utc::now().to_rfc3339(),
             completed_at: None,
         }
@@ -95,7 +133,10 @@ impl ScrapeJob {
 
     /// Check if job is terminal (completed or failed)
     pub fn is_terminal(&self) -> bool {
-        matches!(self.status, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+        matches!(
+            self.status,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled | JobStatus::Skipped
+        )
     }
 
     /// Get progress percentage
@@ -121,12 +162,58 @@ pub struct PipelineStats {
     pub completed_jobs: u64,
     #[pyo3(get)]
     pub failed_jobs: u64,
+    /// Jobs the dedup store showed were already downloaded
+    #[pyo3(get)]
+    pub skipped_jobs: u64,
+    /// Jobs `shutdown` interrupted after its grace period
+    #[pyo3(get)]
+    pub cancelled_jobs: u64,
     #[pyo3(get)]
     pub total_bytes_downloaded: u64,
     #[pyo3(get)]
+    pub total_bytes_uploaded: u64,
+    #[pyo3(get)]
     pub videos_extracted: u64,
+    /// Bytes per second averaged over every completed download
     #[pyo3(get)]
     pub avg_download_speed: f64,
+    /// Bytes per second averaged over the last `RECENT_SPEED_WINDOW` downloads
+    #[pyo3(get)]
+    pub recent_download_speed: f64,
+    #[pyo3(get)]
+    pub total_download_secs: f64,
+    /// Bytes from the downloads timed in `total_download_secs`, which excludes
+    /// jobs restored from a checkpoint
+    timed_bytes: u64,
+    /// `(bytes, secs)` of the most recent downloads
+    recent_downloads: VecDeque<(u64, f64)>,
+}
+
+/// How many recent downloads `recent_download_speed` averages over
+const RECENT_SPEED_WINDOW: usize = 20;
+
+impl PipelineStats {
+    /// Fold a finished download into the byte and speed totals
+    fn record_download(&mut self, bytes: u64, secs: f64) {
+        self.total_bytes_downloaded += bytes;
+        self.timed_bytes += bytes;
+        self.total_download_secs += secs;
+        if self.total_download_secs > 0.0 {
+            self.avg_download_speed = self.timed_bytes as f64 / self.total_download_secs;
+        }
+
+        if self.recent_downloads.len() == RECENT_SPEED_WINDOW {
+            self.recent_downloads.pop_front();
+        }
+        self.recent_downloads.push_back((bytes, secs));
+        let (recent_bytes, recent_secs) = self
+            .recent_downloads
+            .iter()
+            .fold((0, 0.0), |(b, s), (bytes, secs)| (b + bytes, s + secs));
+        if recent_secs > 0.0 {
+            self.recent_download_speed = recent_bytes as f64 / recent_secs;
+        }
+    }
 }
 
 #[pymethods]
@@ -153,6 +240,10 @@ pub struct VideoFilter {
     pub max_height: Option<u32>,
     #[pyo3(get, set)]
     pub allowed_formats: Vec<String>,
+    /// Codec names or RFC 6381 strings, compared by family (`avc1.64001f`
+    /// counts as `h264`)
+    #[pyo3(get, set)]
+    pub allowed_codecs: Vec<String>,
     #[pyo3(get, set)]
     pub min_duration_secs: Option<u64>,
     #[pyo3(get, set)]
@@ -162,6 +253,16 @@ pub struct VideoFilter {
     #[pyo3(get, set)]
     pub max_size_bytes: Option<u64>,
     #[pyo3(get, set)]
+    pub min_fps: Option<u32>,
+    #[pyo3(get, set)]
+    pub max_fps: Option<u32>,
+    /// Minimum total bitrate in kbit/s
+    #[pyo3(get, set)]
+    pub min_bitrate: Option<f64>,
+    /// Maximum total bitrate in kbit/s
+    #[pyo3(get, set)]
+    pub max_bitrate: Option<f64>,
+    #[pyo3(get, set)]
     pub quality_preference: Vec<String>, // e.g., ["1080p", "720p", "480p"]
 }
 
@@ -228,6 +329,16 @@ impl VideoFilter {
             }
         }
 
+        // Check codec
+        if !self.allowed_codecs.is_empty() {
+            if let Some(ref codec) = video.codec {
+                let family = codec_family(codec);
+                if !self.allowed_codecs.iter().any(|c| codec_family(c) == family) {
+                    return false;
+                }
+            }
+        }
+
         // Check duration
         if let Some(min_dur) = self.min_duration_secs {
             if video.duration_secs.map(|d| d < min_dur).unwrap_or(false) {
@@ -252,10 +363,259 @@ impl VideoFilter {
             }
         }
 
+        // Check frame rate and bitrate, known for manifest variants
+        if let Some(min_fps) = self.min_fps {
+            if video.fps.map(|f| f < min_fps).unwrap_or(false) {
+                return false;
+            }
+        }
+        if let Some(max_fps) = self.max_fps {
+            if video.fps.map(|f| f > max_fps).unwrap_or(false) {
+                return false;
+            }
+        }
+        if let Some(min_bitrate) = self.min_bitrate {
+            if video.tbr.map(|b| b < min_bitrate).unwrap_or(false) {
+                return false;
+            }
+        }
+        if let Some(max_bitrate) = self.max_bitrate {
+            if video.tbr.map(|b| b > max_bitrate).unwrap_or(false) {
+                return false;
+            }
+        }
+
         true
     }
 }
 
+/// Snapshot of a pipeline's jobs written by `ScrapingPipeline::checkpoint`
+#[derive(Serialize, Deserialize)]
+struct PipelineCheckpoint {
+    jobs: Vec<ScrapeJob>,
+    seen_urls: Vec<String>,
+}
+
+/// The error a stage fails with when its job is cancelled
+fn job_cancelled() -> ScraperError {
+    ScraperError::Cancelled("Job".to_string())
+}
+
+/// Run `fut` unless `cancel` fires first
+async fn until_cancelled<T>(
+    cancel: &CancellationToken,
+    fut: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        result = fut => result,
+        _ = cancel.cancelled() => Err(job_cancelled()),
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Extension for a downloaded file. `format` is often guessed from the URL
+/// or given as a MIME type, so a `Content-Type` naming a different container
+/// wins; one that agrees, or isn't a known media type, leaves it alone.
+fn file_extension(format: Option<&str>, content_type: Option<&str>) -> String {
+    let format = format.and_then(|format| {
+        if format.contains('/') {
+            extensions_for_mime(format).first().copied().map(str::to_string)
+        } else {
+            Some(format.to_ascii_lowercase())
+        }
+    });
+    let served = content_type.map(extensions_for_mime).unwrap_or_default();
+
+    match served.first() {
+        Some(ext) if !format.as_deref().is_some_and(|format| served.contains(&format)) => {
+            if let Some(format) = format {
+                debug!("Content-Type says .{}, not .{}", ext, format);
+            }
+            ext.to_string()
+        }
+        _ => format.unwrap_or_else(|| "mp4".to_string()),
+    }
+}
+
+/// Called with each job as it reaches a terminal state
+pub type JobCallback = Arc<dyn Fn(&ScrapeJob) + Send + Sync>;
+
+/// Most jobs the queue holds before `add_url` waits for room
+const QUEUE_CAPACITY: usize = 10000;
+
+/// Progress events buffered per `subscribe_progress` receiver
+const PROGRESS_CHANNEL_CAPACITY: usize = 1024;
+
+/// Why a job couldn't be queued
+#[derive(Debug)]
+enum PushError {
+    Full,
+    Closed,
+}
+
+impl std::fmt::Display for PushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::Full => write!(f, "queue is full"),
+            PushError::Closed => write!(f, "queue is closed"),
+        }
+    }
+}
+
+/// A queued job, ordered by priority and then by arrival
+struct QueuedJob {
+    seq: u64,
+    job: ScrapeJob,
+}
+
+impl QueuedJob {
+    fn key(&self) -> (i32, std::cmp::Reverse<u64>) {
+        (self.job.priority, std::cmp::Reverse(self.seq))
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Bounded queue of pending jobs that hands out the highest priority first
+/// and is FIFO within a priority
+struct JobQueue {
+    state: std::sync::Mutex<QueueState>,
+    /// Woken when a job is pushed or the queue closes
+    pushed: Notify,
+    /// Woken when a job is popped or the queue closes
+    popped: Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    heap: BinaryHeap<QueuedJob>,
+    next_seq: u64,
+    closed: bool,
+}
+
+impl JobQueue {
+    fn new() -> Self {
+        Self {
+            state: std::sync::Mutex::new(QueueState::default()),
+            pushed: Notify::new(),
+            popped: Notify::new(),
+        }
+    }
+
+    /// Queue a job, failing if the queue is full or closed
+    fn try_push(&self, job: &ScrapeJob) -> std::result::Result<(), PushError> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Err(PushError::Closed);
+            }
+            if state.heap.len() >= QUEUE_CAPACITY {
+                return Err(PushError::Full);
+            }
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.heap.push(QueuedJob {
+                seq,
+                job: job.clone(),
+            });
+        }
+        self.pushed.notify_waiters();
+        Ok(())
+    }
+
+    /// Queue a job even past the capacity, for jobs restored before any
+    /// worker runs; later pushes wait until the queue drains below it
+    fn seed(&self, job: &ScrapeJob) {
+        let mut state = self.state.lock().unwrap();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.heap.push(QueuedJob {
+            seq,
+            job: job.clone(),
+        });
+    }
+
+    /// Queue a job, waiting for room while the queue is full
+    async fn push(&self, job: &ScrapeJob) -> std::result::Result<(), PushError> {
+        loop {
+            // Register before trying, so a pop in between isn't missed
+            let popped = self.popped.notified();
+            tokio::pin!(popped);
+            popped.as_mut().enable();
+
+            match self.try_push(job) {
+                Err(PushError::Full) => {}
+                result => return result,
+            }
+            popped.await;
+        }
+    }
+
+    /// Take the highest-priority job. `Err(true)` means the queue is closed,
+    /// `Err(false)` that it's empty.
+    fn try_pop(&self) -> std::result::Result<ScrapeJob, bool> {
+        let job = {
+            let mut state = self.state.lock().unwrap();
+            match state.heap.pop() {
+                Some(queued) => queued.job,
+                None => return Err(state.closed),
+            }
+        };
+        self.popped.notify_waiters();
+        Ok(job)
+    }
+
+    /// Take the highest-priority job, waiting for one to arrive, or `None`
+    /// once the queue is closed and empty
+    async fn pop(&self) -> Option<ScrapeJob> {
+        loop {
+            let pushed = self.pushed.notified();
+            tokio::pin!(pushed);
+            pushed.as_mut().enable();
+
+            match self.try_pop() {
+                Ok(job) => return Some(job),
+                Err(true) => return None,
+                Err(false) => {}
+            }
+            pushed.await;
+        }
+    }
+
+    /// Refuse new jobs and wake everyone waiting on the queue
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.pushed.notify_waiters();
+        self.popped.notify_waiters();
+    }
+}
+
 /// Main scraping pipeline
 pub struct ScrapingPipeline {
     config: ScraperConfig,
@@ -263,12 +623,29 @@ pub struct ScrapingPipeline {
     client: Arc<HttpClient>,
     downloader: Arc<DownloadManager>,
     extractor: Arc<VideoExtractor>,
+    /// Created on the first upload, since connecting to a backend is async
+    storage: tokio::sync::OnceCell<StorageManager>,
     jobs: Arc<RwLock<Vec<ScrapeJob>>>,
     seen_urls: Arc<RwLock<HashSet<String>>>,
     stats: Arc<RwLock<PipelineStats>>,
-    job_sender: Sender<ScrapeJob>,
-    job_receiver: Receiver<ScrapeJob>,
+    queue: JobQueue,
     running: Arc<std::sync::atomic::AtomicBool>,
+    job_finished: Arc<Notify>,
+    /// Jobs taken off the queue but not yet finished
+    in_flight: AtomicUsize,
+    /// Set by `shutdown` to stop taking jobs off the queue
+    shutting_down: AtomicBool,
+    /// Parent of every job's cancellation token
+    shutdown_token: CancellationToken,
+    /// Rank candidate videos by resolution instead of taking them in page order
+    prefer_highest_quality: bool,
+    /// Output file names handed out so far, with the job holding each
+    output_names: std::sync::Mutex<HashMap<String, String>>,
+    job_callback: std::sync::RwLock<Option<JobCallback>>,
+    /// Live download progress of every job, for `subscribe_progress`
+    progress: broadcast::Sender<DownloadProgress>,
+    /// Downloads finished by earlier runs, when `dedup_store_path` is set
+    dedup: Option<DedupStore>,
 }
 
 impl ScrapingPipeline {
@@ -277,7 +654,11 @@ impl ScrapingPipeline {
         let client = Arc::new(HttpClient::new(config)?);
         let downloader = Arc::new(DownloadManager::new(client.clone(), config));
         let extractor = Arc::new(VideoExtractor::new(client.clone()));
-        let (sender, receiver) = bounded(10000);
+        let dedup = config
+            .dedup_store_path
+            .as_deref()
+            .map(|path| DedupStore::open(Path::new(path), config.strip_query_params.clone()))
+            .transpose()?;
 
         Ok(Self {
             config: config.clone(),
@@ -285,53 +666,162 @@ impl ScrapingPipeline {
             client,
             downloader,
             extractor,
+            storage: tokio::sync::OnceCell::new(),
             jobs: Arc::new(RwLock::new(Vec::new())),
             seen_urls: Arc::new(RwLock::new(HashSet::new())),
             stats: Arc::new(RwLock::new(PipelineStats::default())),
-            job_sender: sender,
-            job_receiver: receiver,
+            queue: JobQueue::new(),
             running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            job_finished: Arc::new(Notify::new()),
+            in_flight: AtomicUsize::new(0),
+            shutting_down: AtomicBool::new(false),
+            shutdown_token: CancellationToken::new(),
+            prefer_highest_quality: true,
+            output_names: std::sync::Mutex::new(HashMap::new()),
+            job_callback: std::sync::RwLock::new(None),
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
+            dedup,
+        })
+    }
+
+    /// Receive `DownloadProgress` events, tagged with their job's id, as
+    /// downloads run from now on. A receiver that falls more than
+    /// `PROGRESS_CHANNEL_CAPACITY` events behind skips the oldest.
+    pub fn subscribe_progress(&self) -> broadcast::Receiver<DownloadProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Forward a job's download progress to `subscribe_progress` receivers
+    fn progress_callback(&self, job_id: &str) -> ProgressCallback {
+        let sender = self.progress.clone();
+        let job_id = job_id.to_string();
+        Arc::new(move |progress: &DownloadProgress| {
+            if sender.receiver_count() > 0 {
+                let _ = sender.send(DownloadProgress {
+                    job_id: Some(job_id.clone()),
+                    ..progress.clone()
+                });
+            }
+            Ok(())
         })
     }
 
+    /// Call `callback` with every job that finishes, fails or is cancelled
+    /// from now on, replacing any callback set before. It runs on a blocking
+    /// thread before `run` or `wait` can observe the job as finished.
+    pub fn set_job_callback(&self, callback: Option<JobCallback>) {
+        *self.job_callback.write().unwrap() = callback;
+    }
+
+    /// Choose between the highest-resolution video and the first one on the page
+    pub fn set_prefer_highest_quality(&mut self, prefer: bool) {
+        self.prefer_highest_quality = prefer;
+    }
+
     /// Add a URL to the scraping queue
     pub async fn add_url(&self, url: &str) -> Result<ScrapeJob> {
-        // Check for duplicates
-        {
-            let seen = self.seen_urls.read().await;
-            if seen.contains(url) {
-                return Err(ScraperError::PipelineError(format!(
-                    "URL already in queue: {}",
-                    url
-                )));
+        self.add_url_with_priority(url, 0).await
+    }
+
+    /// Add a URL to the scraping queue, ahead of queued jobs with a lower
+    /// `priority`
+    pub async fn add_url_with_priority(&self, url: &str, priority: i32) -> Result<ScrapeJob> {
+        self.add_url_with_tags(url, priority, HashMap::new(), None).await
+    }
+
+    /// Add a URL to the scraping queue with `tags` for its key template and,
+    /// optionally, a `key_template` of its own. Links crawled from the page
+    /// inherit both.
+    pub async fn add_url_with_tags(
+        &self,
+        url: &str,
+        priority: i32,
+        tags: HashMap<String, String>,
+        key_template: Option<String>,
+    ) -> Result<ScrapeJob> {
+        if let Some(key_template) = &key_template {
+            let problems = template_problems("key_template", key_template);
+            if !problems.is_empty() {
+                return Err(ScraperError::ConfigError(problems.join("; ")));
             }
         }
 
-        let job = ScrapeJob::new(url);
-        
-        {
-            let mut seen = self.seen_urls.write().await;
-            seen.insert(url.to_string());
+        let mut job = ScrapeJob::new(url);
+        job.priority = priority;
+        job.tags = tags;
+        job.key_template = key_template;
+        self.enqueue(job, true).await
+    }
+
+    /// Record and queue a job. Without `wait`, a full queue fails instead of
+    /// blocking, which workers need since they are the queue's consumers.
+    async fn enqueue(&self, job: ScrapeJob, wait: bool) -> Result<ScrapeJob> {
+        // Check for duplicates
+        if !self.seen_urls.write().await.insert(job.source_url.clone()) {
+            return Err(ScraperError::PipelineError(format!(
+                "URL already in queue: {}",
+                job.source_url
+            )));
         }
-        
+
         {
             let mut jobs = self.jobs.write().await;
             jobs.push(job.clone());
         }
-        
+
         {
             let mut stats = self.stats.write().await;
             stats.total_jobs += 1;
             stats.pending_jobs += 1;
         }
 
-        self.job_sender.send(job.clone()).await.map_err(|e| {
-            ScraperError::PipelineError(format!("Failed to queue job: {}", e))
-        })?;
+        let sent = if wait {
+            self.queue.push(&job).await
+        } else {
+            self.queue.try_push(&job)
+        };
+
+        if let Err(e) = sent {
+            // Undo the bookkeeping so the URL can be added again later
+            self.seen_urls.write().await.remove(&job.source_url);
+            self.jobs.write().await.retain(|j| j.id != job.id);
+            let mut stats = self.stats.write().await;
+            stats.total_jobs = stats.total_jobs.saturating_sub(1);
+            stats.pending_jobs = stats.pending_jobs.saturating_sub(1);
+
+            return Err(ScraperError::PipelineError(format!("Failed to queue job: {}", e)));
+        }
 
         Ok(job)
     }
 
+    /// Queue the links of a page without videos as jobs one level deeper,
+    /// returning how many were queued
+    async fn crawl_links(&self, job: &ScrapeJob) -> Result<usize> {
+        let links = self.extractor.extract_links_from_url(&job.source_url).await?;
+        let host_of = |url: &str| Url::parse(url).ok()?.host_str().map(str::to_string);
+        let source_host = host_of(&job.source_url);
+
+        let mut queued = 0;
+        for link in links {
+            if self.config.crawl_same_host_only && host_of(&link) != source_host {
+                continue;
+            }
+
+            let mut child = ScrapeJob::new(&link);
+            child.depth = job.depth + 1;
+            child.priority = job.priority;
+            child.tags = job.tags.clone();
+            child.key_template = job.key_template.clone();
+            match self.enqueue(child, false).await {
+                Ok(_) => queued += 1,
+                Err(e) => debug!("Not crawling {}: {}", link, e),
+            }
+        }
+
+        Ok(queued)
+    }
+
     /// Add multiple URLs to the queue
     pub async fn add_urls(&self, urls: Vec<String>) -> Vec<Result<ScrapeJob>> {
         let mut results = Vec::with_capacity(urls.len());
@@ -341,8 +831,210 @@ impl ScrapingPipeline {
         results
     }
 
+    /// Save all jobs and seen URLs to a JSON file
+    pub async fn checkpoint(&self, path: &Path) -> Result<()> {
+        let checkpoint = PipelineCheckpoint {
+            jobs: self.jobs.read().await.clone(),
+            seen_urls: self.seen_urls.read().await.iter().cloned().collect(),
+        };
+        let json = serde_json::to_vec_pretty(&checkpoint)?;
+
+        // Write then rename so a crash mid-write leaves the previous checkpoint intact
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, json).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+
+        info!("Checkpointed {} jobs to {:?}", checkpoint.jobs.len(), path);
+        Ok(())
+    }
+
+    /// Write every job to a JSON array
+    pub async fn export_jobs_json(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(&*self.jobs.read().await)?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    /// Write every job as a CSV row with a header
+    pub async fn export_jobs_csv(&self, path: &Path) -> Result<()> {
+        let mut csv = String::from(
+            "id,source_url,status,video_url,output_path,storage_key,bytes_downloaded,\
+             total_bytes,attempts,depth,created_at,completed_at,error_message\n",
+        );
+
+        for job in self.jobs.read().await.iter() {
+            let fields = [
+                job.id.clone(),
+                job.source_url.clone(),
+                format!("{:?}", job.status),
+                job.video_url.clone().unwrap_or_default(),
+                job.output_path.clone().unwrap_or_default(),
+                job.storage_key.clone().unwrap_or_default(),
+                job.bytes_downloaded.to_string(),
+                job.total_bytes.map(|b| b.to_string()).unwrap_or_default(),
+                job.attempts.to_string(),
+                job.depth.to_string(),
+                job.created_at.clone(),
+                job.completed_at.clone().unwrap_or_default(),
+                job.error_message.clone().unwrap_or_default(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        tokio::fs::write(path, csv).await?;
+        Ok(())
+    }
+
+    /// Rebuild a pipeline from a checkpoint, re-queueing every job that hadn't
+    /// completed or been cancelled
+    pub async fn restore(
+        path: &Path,
+        config: &ScraperConfig,
+        storage_config: &StorageConfig,
+    ) -> Result<Self> {
+        let data = tokio::fs::read(path).await?;
+        let checkpoint: PipelineCheckpoint = serde_json::from_slice(&data)?;
+        let pipeline = Self::new(config, storage_config)?;
+
+        let mut jobs = checkpoint.jobs;
+        let mut stats = PipelineStats {
+            total_jobs: jobs.len() as u64,
+            ..Default::default()
+        };
+
+        for job in &mut jobs {
+            match job.status {
+                JobStatus::Completed => {
+                    stats.completed_jobs += 1;
+                    stats.total_bytes_downloaded += job.bytes_downloaded;
+                }
+                JobStatus::Skipped => stats.skipped_jobs += 1,
+                // Failed jobs and jobs interrupted mid-stage or by a shutdown
+                // start over
+                _ => {
+                    job.status = JobStatus::Pending;
+                    job.error_message = None;
+                    job.completed_at = None;
+                    stats.pending_jobs += 1;
+
+                    // No worker runs yet to make room, so don't wait for it
+                    pipeline.queue.seed(job);
+                }
+            }
+        }
+
+        info!(
+            "Restored {} jobs from {:?} ({} re-queued)",
+            jobs.len(),
+            path,
+            stats.pending_jobs
+        );
+
+        *pipeline.jobs.write().await = jobs;
+        *pipeline.seen_urls.write().await = checkpoint.seen_urls.into_iter().collect();
+        *pipeline.stats.write().await = stats;
+
+        Ok(pipeline)
+    }
+
+    /// Re-queue every failed job, returning how many were re-queued
+    pub async fn retry_failed(&self) -> Result<usize> {
+        // Reset under the lock but push after releasing it: a full queue only
+        // drains once a worker can record its finished job in `jobs`
+        let failed: Vec<ScrapeJob> = {
+            let mut jobs = self.jobs.write().await;
+            jobs.iter_mut()
+                .filter(|j| j.status == JobStatus::Failed)
+                .map(|job| {
+                    let failed = job.clone();
+                    job.status = JobStatus::Pending;
+                    job.error_message = None;
+                    job.completed_at = None;
+                    failed
+                })
+                .collect()
+        };
+        {
+            let mut stats = self.stats.write().await;
+            stats.failed_jobs = stats.failed_jobs.saturating_sub(failed.len() as u64);
+            stats.pending_jobs += failed.len() as u64;
+        }
+
+        for (requeued, job) in failed.iter().enumerate() {
+            let mut retry = job.clone();
+            retry.status = JobStatus::Pending;
+            retry.error_message = None;
+            retry.completed_at = None;
+
+            if let Err(e) = self.queue.push(&retry).await {
+                // Put back this job and the ones after it, which were never queued
+                let unqueued = &failed[requeued..];
+                {
+                    let mut jobs = self.jobs.write().await;
+                    for failed in unqueued {
+                        if let Some(job) = jobs.iter_mut().find(|j| j.id == failed.id) {
+                            *job = failed.clone();
+                        }
+                    }
+                }
+                let mut stats = self.stats.write().await;
+                stats.failed_jobs += unqueued.len() as u64;
+                stats.pending_jobs = stats.pending_jobs.saturating_sub(unqueued.len() as u64);
+
+                return Err(ScraperError::PipelineError(format!("Failed to queue job: {}", e)));
+            }
+        }
+
+        info!("Re-queued {} failed jobs", failed.len());
+        Ok(failed.len())
+    }
+
+    /// Process a job, failing it once it runs past `job_timeout_secs`
+    async fn run_job(&self, job: ScrapeJob, filter: Option<&VideoFilter>) -> ScrapeJob {
+        // Cancelled by the job timeout below or by `shutdown`
+        let cancel = self.shutdown_token.child_token();
+        let timeout_secs = self.config.job_timeout_secs;
+        let process = self.process_job(job, filter, &cancel);
+        tokio::pin!(process);
+
+        let mut timed_out = false;
+        if timeout_secs > 0 {
+            tokio::select! {
+                job = &mut process => return job,
+                _ = tokio::time::sleep(Duration::from_secs(timeout_secs)) => {}
+            }
+            // Stop the job cooperatively rather than dropping it, so an
+            // interrupted download keeps its `.part` file and resume state
+            timed_out = true;
+            cancel.cancel();
+        }
+
+        let mut job = process.await;
+        if job.status == JobStatus::Failed {
+            if timed_out {
+                warn!("Job {} timed out after {} seconds", job.id, timeout_secs);
+                job.error_message = Some(format!("Job timeout after {} seconds", timeout_secs));
+            } else if self.shutdown_token.is_cancelled() {
+                job.status = JobStatus::Cancelled;
+                job.error_message = Some("Interrupted by shutdown".to_string());
+
+                let mut stats = self.stats.write().await;
+                stats.failed_jobs = stats.failed_jobs.saturating_sub(1);
+                stats.cancelled_jobs += 1;
+            }
+        }
+        job
+    }
+
     /// Process a single job
-    async fn process_job(&self, mut job: ScrapeJob, filter: Option<&VideoFilter>) -> ScrapeJob {
+    async fn process_job(
+        &self,
+        mut job: ScrapeJob,
+        filter: Option<&VideoFilter>,
+        cancel: &CancellationToken,
+    ) -> ScrapeJob {
         info!("Processing job {}: {}", job.id, job.source_url);
 
         // Update stats
@@ -352,9 +1044,20 @@ impl ScrapingPipeline {
             stats.active_jobs += 1;
         }
 
+        if let Some(entry) = self.find_downloaded(&job.source_url).await {
+            return self.skip_job(job, entry).await;
+        }
+
         // Step 1: Extract video URLs
+        job.attempts += 1;
         job.status = JobStatus::Extracting;
-        let videos = match self.extractor.extract_from_url(&job.source_url).await {
+        let source_url = job.source_url.clone();
+        let videos = match self
+            .retry_stage(&mut job, "Extraction", cancel, || {
+                until_cancelled(cancel, self.extractor.extract_from_url(&source_url))
+            })
+            .await
+        {
             Ok(v) => v,
             Err(e) => {
                 error!("Extraction failed for {}: {}", job.source_url, e);
@@ -371,6 +1074,24 @@ impl ScrapingPipeline {
         };
 
         if videos.is_empty() {
+            if job.depth < self.config.max_crawl_depth {
+                match until_cancelled(cancel, self.crawl_links(&job)).await {
+                    Ok(queued) if queued > 0 => {
+                        info!("Queued {} linked pages from {}", queued, job.source_url);
+                        job.status = JobStatus::Completed;
+                        job.completed_at = Some(chrono::Utc::now().to_rfc3339());
+
+                        let mut stats = self.stats.write().await;
+                        stats.active_jobs = stats.active_jobs.saturating_sub(1);
+                        stats.completed_jobs += 1;
+
+                        return job;
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Crawling {} failed: {}", job.source_url, e),
+                }
+            }
+
             warn!("No videos found at {}", job.source_url);
             job.status = JobStatus::Failed;
             job.error_message = Some("No videos found".to_string());
@@ -389,13 +1110,7 @@ impl ScrapingPipeline {
         }
 
         // Step 2: Filter and select best video
-        let selected_video = if let Some(filter) = filter {
-            videos.into_iter().find(|v| filter.matches(v))
-        } else {
-            videos.into_iter().next()
-        };
-
-        let video = match selected_video {
+        let video = match self.select_video(videos, filter) {
             Some(v) => v,
             None => {
                 job.status = JobStatus::Failed;
@@ -411,28 +1126,68 @@ impl ScrapingPipeline {
         };
 
         job.video_url = Some(video.url.clone());
+        if let Some(entry) = self.find_downloaded(&video.url).await {
+            return self.skip_job(job, entry).await;
+        }
 
         // Step 3: Download video
         job.status = JobStatus::Downloading;
         
+        // The job's only HEAD request: its content length and type are passed
+        // on to the download
+        let probe = until_cancelled(cancel, self.client.probe(&video.url)).await.ok();
+        let content_type = probe.as_ref().and_then(|p| p.content_type.as_deref());
+        if let Some(size) = probe.as_ref().and_then(|p| p.content_length) {
+            job.total_bytes = Some(size);
+        }
+
         // Generate output path
-        let file_ext = video.format.as_deref().unwrap_or("mp4");
-        let file_name = format!("{}.{}", job.id, file_ext);
+        let file_ext = file_extension(video.format.as_deref(), content_type);
+        let (file_name, key) = self.claim_output(&job, &video, &file_ext).await;
         let output_path = PathBuf::from(&self.storage_config.local_path).join(&file_name);
         job.output_path = Some(output_path.to_string_lossy().to_string());
 
-        // Get content length
-        if let Ok(Some(size)) = self.client.get_content_length(&video.url).await {
-            job.total_bytes = Some(size);
-        }
-
-        match self.downloader.download(&video.url, &output_path).await {
-            Ok(result) => {
+        // Remote backends can take the bytes directly, skipping local disk
+        let streamed = !self.storage_config.use_local_staging
+            && self.storage_config.backend != "local"
+            && probe.is_some()
+            && !hls::is_hls(&video.url, content_type);
+
+        // The downloader handles cancellation itself so it can save resume state
+        let progress = self.progress_callback(&job.id);
+        let options = DownloadOptions {
+            progress: Some(progress.clone()),
+            cancel: Some(cancel.clone()),
+            probe: probe.clone(),
+            ..Default::default()
+        };
+        let download = if streamed {
+            self.retry_stage(&mut job, "Download", cancel, || {
+                let download = self.download_to_storage(&video.url, &key, &progress);
+                until_cancelled(cancel, download)
+            })
+            .await
+            .map(|(result, metadata)| (result, Some(metadata)))
+        } else {
+            self.retry_stage(&mut job, "Download", cancel, || {
+                self.downloader.download_with_options(&video.url, &output_path, &options)
+            })
+            .await
+            .map(|result| (result, None))
+        };
+        let content_hash = match download {
+            Ok((result, uploaded)) => {
                 job.bytes_downloaded = result.size_bytes;
-                job.storage_key = Some(format!("{}{}", self.storage_config.key_prefix, file_name));
+                job.storage_key = Some(key.clone());
                 
                 let mut stats = self.stats.write().await;
-                stats.total_bytes_downloaded += result.size_bytes;
+                stats.record_download(result.size_bytes, result.duration_secs);
+                if let Some(metadata) = uploaded {
+                    job.storage_key = Some(metadata.key);
+                    job.output_path = None;
+                    stats.total_bytes_uploaded += metadata.size_bytes;
+                }
+                result.sha256_hash
             }
             Err(e) => {
                 error!("Download failed for {}: {}", video.url, e);
@@ -446,11 +1201,71 @@ impl ScrapingPipeline {
                 
                 return job;
             }
+        };
+
+        // Step 4: Upload to remote storage; local storage already holds the download
+        if self.storage_config.backend != "local" && !streamed {
+            // The same content found at another URL is already stored
+            let stored = match &self.dedup {
+                Some(dedup) => dedup.find_content(&content_hash).await,
+                None => None,
+            };
+            if let Some(stored_key) = stored.and_then(|entry| entry.storage_key) {
+                info!("{} is already stored as {}, not uploading it", video.url, stored_key);
+                job.storage_key = Some(stored_key);
+            } else {
+                job.status = JobStatus::Uploading;
+
+                let upload = self
+                    .retry_stage(&mut job, "Upload", cancel, || {
+                        until_cancelled(cancel, self.upload(&key, &output_path))
+                    })
+                    .await;
+                match upload {
+                    Ok(metadata) => {
+                        job.storage_key = Some(metadata.key);
+
+                        let mut stats = self.stats.write().await;
+                        stats.total_bytes_uploaded += metadata.size_bytes;
+                    }
+                    Err(e) => {
+                        error!("Upload failed for {}: {}", output_path.display(), e);
+                        job.status = JobStatus::Failed;
+                        job.error_message = Some(format!("Upload failed: {}", e));
+                        job.completed_at = Some(chrono::Utc::now().to_rfc3339());
+
+                        let mut stats = self.stats.write().await;
+                        stats.active_jobs = stats.active_jobs.saturating_sub(1);
+                        stats.failed_jobs += 1;
+
+                        return job;
+                    }
+                }
+            }
+
+            if self.storage_config.delete_local_after_upload {
+                match tokio::fs::remove_file(&output_path).await {
+                    Ok(()) => job.output_path = None,
+                    Err(e) => warn!("Failed to remove {}: {}", output_path.display(), e),
+                }
+            }
         }
 
-        // Step 4: Mark as completed (storage upload happens separately if needed)
+        // Step 5: Mark as completed
         job.status = JobStatus::Completed;
         job.completed_at = Some(chrono::Utc::now().to_rfc3339());
+        if let Some(dedup) = &self.dedup {
+            let entry = DedupEntry {
+                source_url: job.source_url.clone(),
+                video_url: video.url.clone(),
+                sha256: content_hash,
+                storage_key: job.storage_key.clone(),
+                completed_at: job.completed_at.clone().unwrap_or_default(),
+            };
+            if let Err(e) = dedup.record(entry).await {
+                warn!("Failed to record {} in the dedup store: {}", job.source_url, e);
+            }
+        }
         
         {
             let mut stats = self.stats.write().await;
@@ -462,37 +1277,392 @@ impl ScrapingPipeline {
         job
     }
 
-    /// Run the pipeline with given concurrency
+    /// The dedup store's entry for a page or video URL downloaded before
+    async fn find_downloaded(&self, url: &str) -> Option<DedupEntry> {
+        self.dedup.as_ref()?.find_url(url).await
+    }
+
+    /// Finish a job as skipped, pointing it at the earlier download
+    async fn skip_job(&self, mut job: ScrapeJob, entry: DedupEntry) -> ScrapeJob {
+        info!("Skipping {}: downloaded at {}", job.source_url, entry.completed_at);
+        job.status = JobStatus::Skipped;
+        job.video_url = Some(entry.video_url);
+        job.storage_key = entry.storage_key;
+        job.completed_at = Some(chrono::Utc::now().to_rfc3339());
+
+        let mut stats = self.stats.write().await;
+        stats.active_jobs = stats.active_jobs.saturating_sub(1);
+        stats.skipped_jobs += 1;
+
+        job
+    }
+
+    /// The dedup store, when `dedup_store_path` is set
+    pub fn dedup_store(&self) -> Option<&DedupStore> {
+        self.dedup.as_ref()
+    }
+
+    /// Pick the video to download. Candidates passing the filter are ranked by
+    /// its `quality_preference` order, then by resolution and size when
+    /// `prefer_highest_quality` is set, and otherwise by page order.
+    pub(crate) fn select_video(
+        &self,
+        videos: Vec<VideoInfo>,
+        filter: Option<&VideoFilter>,
+    ) -> Option<VideoInfo> {
+        let mut candidates: Vec<VideoInfo> = match filter {
+            Some(filter) => videos.into_iter().filter(|v| filter.matches(v)).collect(),
+            None => videos,
+        };
+        let preferences = filter.map(|f| f.quality_preference.as_slice()).unwrap_or_default();
+
+        for preferred in preferences {
+            let found = candidates.iter().position(|v| {
+                self.video_quality(v)
+                    .is_some_and(|q| q.eq_ignore_ascii_case(preferred))
+            });
+            if let Some(i) = found {
+                return Some(candidates.swap_remove(i));
+            }
+        }
+
+        if !self.prefer_highest_quality {
+            return candidates.into_iter().next();
+        }
+
+        // `max_by_key` keeps the last of equal elements, so reverse to prefer page order
+        candidates.into_iter().rev().max_by_key(|v| {
+            (
+                v.height.unwrap_or(0),
+                v.width.unwrap_or(0),
+                v.file_size_bytes.unwrap_or(0),
+            )
+        })
+    }
+
+    /// A video's quality label, e.g. `720p`, from its metadata or else its URL
+    fn video_quality(&self, video: &VideoInfo) -> Option<String> {
+        video
+            .quality
+            .clone()
+            .or_else(|| video.height.map(|h| format!("{}p", h)))
+            .or_else(|| self.extractor.extract_quality(&video.url))
+    }
+
+    /// Run one stage of a job, retrying transient errors with exponential backoff
+    async fn retry_stage<T, F, Fut>(
+        &self,
+        job: &mut ScrapeJob,
+        stage: &str,
+        cancel: &CancellationToken,
+        mut op: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let max_retries = self.config.max_job_retries;
+        let mut retries = 0;
+
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && retries < max_retries && !cancel.is_cancelled() => {
+                    retries += 1;
+                    job.attempts += 1;
+                    let delay = self.client.retry_delay(retries);
+                    warn!(
+                        "{} failed for job {}: {}, retry {}/{} in {:?}",
+                        stage, job.id, e, retries, max_retries, delay
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cancel.cancelled() => return Err(job_cancelled()),
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// The configured storage backend, connecting on first use. Keys are
+    /// prefixed by `claim_output`, since a key template replaces `key_prefix`.
+    async fn storage(&self) -> Result<&StorageManager> {
+        self.storage
+            .get_or_try_init(|| {
+                let config = StorageConfig {
+                    key_prefix: String::new(),
+                    ..self.storage_config.clone()
+                };
+                async move { StorageManager::new(&config).await }
+            })
+            .await
+    }
+
+    /// Pick the file name and storage key for a job's download and reserve
+    /// them. With a key template the rendered key doubles as the file name,
+    /// otherwise `output_template` names the file under `key_prefix`.
+    /// `_1`, `_2`, ... is appended while either belongs to another job of
+    /// this pipeline or the download already exists locally or in storage.
+    async fn claim_output(
+        &self,
+        job: &ScrapeJob,
+        video: &VideoInfo,
+        ext: &str,
+    ) -> (String, String) {
+        let value = self.template_values(job, video, ext);
+        let key_template = job
+            .key_template
+            .as_deref()
+            .or(Some(self.storage_config.key_template.as_str()).filter(|t| !t.is_empty()));
+        let (name, prefix) = match key_template {
+            Some(key_template) => (template::render(key_template, &value), ""),
+            // Fanout backends apply their own prefixes
+            None if self.storage_config.backend == "fanout" => {
+                (template::render(&self.storage_config.output_template, &value), "")
+            }
+            None => (
+                template::render(&self.storage_config.output_template, &value),
+                self.storage_config.key_prefix.as_str(),
+            ),
+        };
+
+        let mut suffix = 0;
+        loop {
+            let file_name = match suffix {
+                0 => name.clone(),
+                n => template::with_suffix(&name, n),
+            };
+            let key = format!("{}{}", prefix, file_name);
+            suffix += 1;
+
+            let claimed = {
+                let mut names = self.output_names.lock().unwrap();
+                let taken = |name: &String| names.get(name).is_some_and(|owner| *owner != job.id);
+                if taken(&file_name) || taken(&key) {
+                    false
+                } else {
+                    names.insert(file_name.clone(), job.id.clone());
+                    names.insert(key.clone(), job.id.clone());
+                    true
+                }
+            };
+            // A file left by the job's own earlier run isn't a collision
+            let local = Path::new(&self.storage_config.local_path).join(&file_name);
+            let own = job.output_path.as_deref().is_some_and(|path| Path::new(path) == local);
+            if claimed && (own || !self.output_exists(&file_name, &key).await) {
+                return (file_name, key);
+            }
+        }
+    }
+
+    /// Values for the placeholders of output and key templates
+    fn template_values<'a>(
+        &self,
+        job: &'a ScrapeJob,
+        video: &'a VideoInfo,
+        ext: &'a str,
+    ) -> impl Fn(&str) -> Option<String> + 'a {
+        let host = Url::parse(&job.source_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        // Pages rarely label plain video links, so fall back to the height or
+        // a resolution in the URL
+        let quality = video
+            .quality
+            .clone()
+            .or_else(|| video.height.map(|height| format!("{}p", height)))
+            .or_else(|| self.extractor.extract_quality(&video.url));
+        let created = chrono::DateTime::parse_from_rfc3339(&job.created_at)
+            .map(|time| time.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+        let or_unknown = |value: Option<String>, fallback: &str| {
+            Some(value.unwrap_or_else(|| fallback.to_string()))
+        };
+        move |field| match field {
+            "id" => Some(job.id.clone()),
+            "title" => or_unknown(video.title.clone(), "untitled"),
+            "quality" => or_unknown(quality.clone(), "unknown"),
+            "host" => or_unknown(host.clone(), "unknown"),
+            "ext" => Some(ext.to_string()),
+            "format" => or_unknown(video.format.clone(), ext),
+            "width" => or_unknown(video.width.map(|width| width.to_string()), "unknown"),
+            "height" => or_unknown(video.height.map(|height| height.to_string()), "unknown"),
+            "year" => Some(created.format("%Y").to_string()),
+            "month" => Some(created.format("%m").to_string()),
+            "day" => Some(created.format("%d").to_string()),
+            field => match field.strip_prefix("tag:") {
+                Some(tag) => or_unknown(job.tags.get(tag).cloned(), "unknown"),
+                None => None,
+            },
+        }
+    }
+
+    /// Whether a finished download named `file_name`, stored as `key`, exists
+    async fn output_exists(&self, file_name: &str, key: &str) -> bool {
+        let local = Path::new(&self.storage_config.local_path).join(file_name);
+        if tokio::fs::try_exists(&local).await.unwrap_or(false) {
+            return true;
+        }
+        if self.storage_config.backend == "local" {
+            return false;
+        }
+        match self.storage().await {
+            Ok(storage) => storage.backend().exists(key).await.unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// Upload a downloaded file to the configured storage backend
+    async fn upload(&self, key: &str, path: &Path) -> Result<ObjectMetadata> {
+        self.storage().await?.backend().put_file(key, path).await
+    }
+
+    /// Download a video straight into the configured storage backend
+    async fn download_to_storage(
+        &self,
+        url: &str,
+        key: &str,
+        progress: &ProgressCallback,
+    ) -> Result<(DownloadResult, ObjectMetadata)> {
+        let storage = self.storage().await?;
+        self.downloader
+            .download_to_storage(url, key, storage.backend(), Some(progress))
+            .await
+    }
+
+    /// Process queued jobs as a batch, returning once the queue is empty and
+    /// no job is in flight (including pages queued by crawling)
     pub async fn run(&self, concurrency: usize, filter: Option<VideoFilter>) {
+        self.process_queue(concurrency, filter, false).await;
+    }
+
+    /// Process jobs as a long-lived service: keep waiting for new URLs while
+    /// the queue is empty, until `stop` is called
+    pub async fn serve(&self, concurrency: usize, filter: Option<VideoFilter>) {
+        self.process_queue(concurrency, filter, true).await;
+    }
+
+    async fn process_queue(
+        &self,
+        concurrency: usize,
+        filter: Option<VideoFilter>,
+        until_stopped: bool,
+    ) {
         self.running.store(true, Ordering::SeqCst);
         let filter = Arc::new(filter);
 
-        let results: Vec<_> = stream::unfold(self.job_receiver.clone(), |receiver| async move {
-            match receiver.recv().await {
-                Ok(job) => Some((job, receiver)),
-                Err(_) => None,
-            }
+        self.queued_jobs(until_stopped)
+            .map(|job| {
+                let pipeline = self;
+                let filter = filter.clone();
+                async move {
+                    let result = pipeline.run_job(job, filter.as_ref().as_ref()).await;
+                    pipeline.finish_job(result).await;
+                }
+            })
+            .buffer_unordered(concurrency)
+            .for_each(|_| async {})
+            .await;
+
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Jobs as workers free up to take them. Boxed here, outside any async fn,
+    /// because rustc can't otherwise prove the futures awaiting it `Send`.
+    fn queued_jobs(&self, until_stopped: bool) -> BoxStream<'_, ScrapeJob> {
+        stream::unfold((), move |()| async move {
+            self.next_job(until_stopped).await.map(|job| (job, ()))
         })
-        .map(|job| {
-            let pipeline = self;
-            let filter = filter.clone();
-            async move {
-                pipeline.process_job(job, filter.as_ref().as_ref()).await
+        .boxed()
+    }
+
+    /// The next queued job, or `None` once the pipeline is stopped or, for a
+    /// batch run, once the queue is empty with nothing in flight to refill it
+    async fn next_job(&self, until_stopped: bool) -> Option<ScrapeJob> {
+        loop {
+            // Jobs left in the queue stay pending for a checkpoint to pick up
+            if self.shutting_down.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            let finished = self.job_finished.notified();
+            tokio::pin!(finished);
+            finished.as_mut().enable();
+
+            match self.queue.try_pop() {
+                Ok(job) => {
+                    self.in_flight.fetch_add(1, Ordering::SeqCst);
+                    return Some(job);
+                }
+                Err(true) => return None,
+                Err(false) => {}
             }
-        })
-        .buffer_unordered(concurrency)
-        .collect()
-        .await;
 
-        // Update job states
-        let mut jobs = self.jobs.write().await;
-        for result in results {
+            if !until_stopped && self.in_flight.load(Ordering::SeqCst) == 0 {
+                return None;
+            }
+
+            tokio::select! {
+                job = self.queue.pop() => {
+                    let job = job.filter(|_| !self.shutting_down.load(Ordering::SeqCst))?;
+                    self.in_flight.fetch_add(1, Ordering::SeqCst);
+                    return Some(job);
+                }
+                // An in-flight job finished; check again whether the batch is done
+                _ = finished => {}
+            }
+        }
+    }
+
+    /// Record a processed job's final state, report it to the job callback
+    /// and wake anyone in `wait`
+    async fn finish_job(&self, result: ScrapeJob) {
+        let callback = self.job_callback.read().unwrap().clone();
+        if let Some(callback) = callback {
+            let job = result.clone();
+            // The callback may block, e.g. on the GIL, so keep it off the workers
+            let called = tokio::task::spawn_blocking(move || callback(&job)).await;
+            if let Err(e) = called {
+                error!("Job callback panicked for {}: {}", result.id, e);
+            }
+        }
+
+        {
+            let mut jobs = self.jobs.write().await;
             if let Some(job) = jobs.iter_mut().find(|j| j.id == result.id) {
                 *job = result;
             }
         }
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.job_finished.notify_waiters();
+    }
 
-        self.running.store(false, Ordering::SeqCst);
+    /// Wait until every job is terminal, or the timeout elapses. Returns
+    /// whether all jobs finished.
+    pub async fn wait(&self, timeout: Option<Duration>) -> bool {
+        let all_terminal = async {
+            loop {
+                // Register for the notification before checking, so a job
+                // finishing in between isn't missed
+                let notified = self.job_finished.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+
+                if self.jobs.read().await.iter().all(|j| j.is_terminal()) {
+                    return;
+                }
+                notified.await;
+            }
+        };
+
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, all_terminal).await.is_ok(),
+            None => {
+                all_terminal.await;
+                true
+            }
+        }
     }
 
     /// Get current statistics
@@ -500,6 +1670,52 @@ impl ScrapingPipeline {
         self.stats.read().await.clone()
     }
 
+    /// Job and download metrics in Prometheus exposition format
+    pub async fn metrics_text(&self) -> String {
+        let stats = self.stats().await;
+        let mut out = String::new();
+        let pipeline_metrics = [
+            ("jobs_pending", Kind::Gauge, "Jobs waiting in the queue", stats.pending_jobs),
+            ("jobs_active", Kind::Gauge, "Jobs being processed", stats.active_jobs),
+            (
+                "jobs_completed_total",
+                Kind::Counter,
+                "Jobs that finished successfully",
+                stats.completed_jobs,
+            ),
+            ("jobs_failed_total", Kind::Counter, "Jobs that failed", stats.failed_jobs),
+            (
+                "jobs_skipped_total",
+                Kind::Counter,
+                "Jobs skipped as downloaded by an earlier run",
+                stats.skipped_jobs,
+            ),
+            (
+                "jobs_cancelled_total",
+                Kind::Counter,
+                "Jobs interrupted by a shutdown",
+                stats.cancelled_jobs,
+            ),
+            (
+                "videos_extracted_total",
+                Kind::Counter,
+                "Videos found on scraped pages",
+                stats.videos_extracted,
+            ),
+            (
+                "uploaded_bytes_total",
+                Kind::Counter,
+                "Bytes stored in the storage backend",
+                stats.total_bytes_uploaded,
+            ),
+        ];
+        for (name, kind, help, value) in pipeline_metrics {
+            write_metric(&mut out, name, kind, help, value);
+        }
+        out.push_str(&self.downloader.metrics_text());
+        out
+    }
+
     /// Get all jobs
     pub async fn jobs(&self) -> Vec<ScrapeJob> {
         self.jobs.read().await.clone()
@@ -518,138 +1734,473 @@ impl ScrapingPipeline {
     /// Stop the pipeline
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
-        self.job_sender.close();
+        self.queue.close();
+    }
+
+    /// Stop accepting and starting jobs, give in-flight jobs up to `grace` to
+    /// finish, then cancel the rest. Cancelled jobs are marked cancelled and
+    /// keep their resume state, so a checkpoint restore picks them up.
+    /// Returns whether every in-flight job finished within the grace period.
+    pub async fn shutdown(&self, grace: Duration) -> bool {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        self.stop();
+
+        if tokio::time::timeout(grace, self.wait_in_flight()).await.is_ok() {
+            return true;
+        }
+
+        warn!(
+            "Cancelling {} in-flight jobs after the shutdown grace period",
+            self.in_flight.load(Ordering::SeqCst)
+        );
+        self.shutdown_token.cancel();
+        self.wait_in_flight().await;
+        false
+    }
+
+    async fn wait_in_flight(&self) {
+        loop {
+            let finished = self.job_finished.notified();
+            tokio::pin!(finished);
+            finished.as_mut().enable();
+
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            finished.await;
+        }
+    }
+}
+
+/// `(url, job, error)` for one URL passed to `PyPipeline::add_urls`
+type AddUrlOutcome = (String, Option<ScrapeJob>, Option<String>);
+
+/// Fail fast with every problem in both configurations
+fn validate_configs(config: &ScraperConfig, storage_config: &StorageConfig) -> PyResult<()> {
+    let mut problems = config.validate().err().unwrap_or_default();
+    problems.extend(storage_config.validate().err().unwrap_or_default());
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(crate::config::invalid_config(problems).into())
+    }
+}
+
+/// Iterator over live `DownloadProgress` events, returned by
+/// `Pipeline.progress_stream`
+#[pyclass]
+pub struct ProgressStream {
+    pipeline: Arc<ScrapingPipeline>,
+    receiver: broadcast::Receiver<DownloadProgress>,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+#[pymethods]
+impl ProgressStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Wait for the next event, ending once every job is terminal and the
+    /// events sent before then have been read
+    fn __next__(&mut self, py: Python<'_>) -> Option<DownloadProgress> {
+        let pipeline = &self.pipeline;
+        let receiver = &mut self.receiver;
+
+        py.allow_threads(|| {
+            self.runtime.block_on(async {
+                loop {
+                    tokio::select! {
+                        biased;
+                        event = receiver.recv() => match event {
+                            Ok(progress) => return Some(progress),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Progress stream skipped {} events", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        },
+                        _ = pipeline.wait(None) => {
+                            // A job's last events are sent before it's marked done
+                            return receiver.try_recv().ok();
+                        }
+                    }
+                }
+            })
+        })
     }
 }
 
 /// Python-exposed pipeline
 #[pyclass]
 pub struct PyPipeline {
-    inner: Arc<tokio::sync::Mutex<ScrapingPipeline>>,
+    inner: Arc<ScrapingPipeline>,
     runtime: Arc<tokio::runtime::Runtime>,
 }
 
+impl PyPipeline {
+    fn dedup_store(&self) -> PyResult<&DedupStore> {
+        self.inner.dedup_store().ok_or_else(|| {
+            ScraperError::ConfigError("dedup_store_path is not set".to_string()).into()
+        })
+    }
+}
+
 #[pymethods]
 impl PyPipeline {
     #[new]
-    #[pyo3(signature = (config=None, storage_config=None))]
+    #[pyo3(signature = (config=None, storage_config=None, prefer_highest_quality=true))]
     pub fn new(
         config: Option<&ScraperConfig>,
         storage_config: Option<&StorageConfig>,
+        prefer_highest_quality: bool,
     ) -> PyResult<Self> {
         let config = config.cloned().unwrap_or_default();
         let storage_config = storage_config.cloned().unwrap_or_default();
+        validate_configs(&config, &storage_config)?;
 
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+        let runtime = crate::runtime::shared_runtime(config.worker_threads)?;
 
-        let pipeline = ScrapingPipeline::new(&config, &storage_config).map_err(|e| {
+        let mut pipeline = ScrapingPipeline::new(&config, &storage_config).map_err(|e| {
             pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create pipeline: {}", e))
         })?;
+        pipeline.set_prefer_highest_quality(prefer_highest_quality);
 
         Ok(Self {
-            inner: Arc::new(tokio::sync::Mutex::new(pipeline)),
-            runtime: Arc::new(runtime),
+            inner: Arc::new(pipeline),
+            runtime,
         })
     }
 
-    /// Add a URL to the pipeline
-    pub fn add_url(&self, url: &str) -> PyResult<ScrapeJob> {
-        let inner = self.inner.clone();
-        let url = url.to_string();
+    /// Rebuild a pipeline from a checkpoint file
+    #[staticmethod]
+    #[pyo3(signature = (path, config=None, storage_config=None))]
+    pub fn restore(
+        path: &str,
+        config: Option<&ScraperConfig>,
+        storage_config: Option<&StorageConfig>,
+    ) -> PyResult<Self> {
+        let config = config.cloned().unwrap_or_default();
+        let storage_config = storage_config.cloned().unwrap_or_default();
+        validate_configs(&config, &storage_config)?;
+
+        let runtime = crate::runtime::shared_runtime(config.worker_threads)?;
+
+        let pipeline = runtime
+            .block_on(ScrapingPipeline::restore(Path::new(path), &config, &storage_config))
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to restore pipeline: {}",
+                    e
+                ))
+            })?;
+
+        Ok(Self {
+            inner: Arc::new(pipeline),
+            runtime,
+        })
+    }
+
+    /// Save all jobs to a checkpoint file
+    pub fn checkpoint(&self, path: &str) -> PyResult<()> {
+        let pipeline = self.inner.clone();
+        let path = PathBuf::from(path);
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
-            pipeline.add_url(&url).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            pipeline.checkpoint(&path).await.map_err(PyErr::from)
         })
     }
 
-    /// Add multiple URLs to the pipeline
-    pub fn add_urls(&self, urls: Vec<String>) -> PyResult<Vec<ScrapeJob>> {
-        let inner = self.inner.clone();
+    /// Write every job to a JSON file
+    pub fn export_jobs_json(&self, path: &str) -> PyResult<()> {
+        let pipeline = self.inner.clone();
+        let path = PathBuf::from(path);
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
-            let results = pipeline.add_urls(urls).await;
-            
-            let mut jobs = Vec::new();
-            for result in results {
-                match result {
-                    Ok(job) => jobs.push(job),
-                    Err(e) => warn!("Failed to add URL: {}", e),
-                }
-            }
-            Ok(jobs)
+            pipeline.export_jobs_json(&path).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Write every job to a CSV file
+    pub fn export_jobs_csv(&self, path: &str) -> PyResult<()> {
+        let pipeline = self.inner.clone();
+        let path = PathBuf::from(path);
+
+        self.runtime.block_on(async move {
+            pipeline.export_jobs_csv(&path).await.map_err(PyErr::from)
         })
     }
 
-    /// Run the pipeline (blocking)
+    /// Iterate over `DownloadProgress` events, each carrying its `job_id`, as
+    /// downloads run. Iteration ends once every job has finished, so call
+    /// this after adding URLs and consume it while `run` works in another
+    /// thread.
+    pub fn progress_stream(&self) -> ProgressStream {
+        ProgressStream {
+            pipeline: self.inner.clone(),
+            receiver: self.inner.subscribe_progress(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Call `callback(job)` as each job completes, fails or is cancelled;
+    /// `None` removes it. Exceptions it raises are logged and otherwise ignored.
+    pub fn set_job_callback(&self, callback: Option<PyObject>) {
+        let callback = callback.map(|callback| -> JobCallback {
+            Arc::new(move |job: &ScrapeJob| {
+                Python::with_gil(|py| {
+                    if let Err(e) = callback.call1(py, (job.clone(),)) {
+                        warn!("Job callback raised for {}: {}", job.id, e);
+                    }
+                })
+            })
+        });
+        self.inner.set_job_callback(callback);
+    }
+
+    /// Add a URL to the pipeline. Jobs with a higher `priority` are started
+    /// before queued jobs with a lower one. `tags` fill `{tag:NAME}` in key
+    /// templates, and `key_template` overrides the storage config's.
+    #[pyo3(signature = (url, priority=0, tags=None, key_template=None))]
+    pub fn add_url(
+        &self,
+        py: Python<'_>,
+        url: &str,
+        priority: i32,
+        tags: Option<HashMap<String, String>>,
+        key_template: Option<String>,
+    ) -> PyResult<ScrapeJob> {
+        let pipeline = self.inner.clone();
+        let url = url.to_string();
+        let tags = tags.unwrap_or_default();
+
+        // Release the GIL while waiting for queue room: workers need it to
+        // run callbacks and Python extractors
+        py.allow_threads(|| {
+            self.runtime.block_on(async move {
+                pipeline
+                    .add_url_with_tags(&url, priority, tags, key_template)
+                    .await
+                    .map_err(PyErr::from)
+            })
+        })
+    }
+
+    /// Async variant of `add_url` for use inside an asyncio event loop
+    #[pyo3(signature = (url, priority=0, tags=None, key_template=None))]
+    pub fn add_url_async<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        priority: i32,
+        tags: Option<HashMap<String, String>>,
+        key_template: Option<String>,
+    ) -> PyResult<&'py PyAny> {
+        let pipeline = self.inner.clone();
+        let url = url.to_string();
+        let tags = tags.unwrap_or_default();
+
+        crate::runtime::into_awaitable(py, async move {
+            // Once recorded, a job must also be queued
+            let added =
+                async move { pipeline.add_url_with_tags(&url, priority, tags, key_template).await };
+            crate::runtime::detached(added).await?.map_err(PyErr::from)
+        })
+    }
+
+    /// Add multiple URLs to the pipeline. Returns a `(url, job, error)` tuple
+    /// per URL, in order, with exactly one of `job` and `error` set.
+    pub fn add_urls(&self, py: Python<'_>, urls: Vec<String>) -> PyResult<Vec<AddUrlOutcome>> {
+        let pipeline = self.inner.clone();
+
+        py.allow_threads(|| {
+            self.runtime.block_on(async move {
+                let results = pipeline.add_urls(urls.clone()).await;
+
+                Ok(urls
+                    .into_iter()
+                    .zip(results)
+                    .map(|(url, result)| match result {
+                        Ok(job) => (url, Some(job), None),
+                        Err(e) => (url, None, Some(e.to_string())),
+                    })
+                    .collect())
+            })
+        })
+    }
+
+    /// Re-queue all failed jobs, returning how many were re-queued
+    pub fn retry_failed(&self, py: Python<'_>) -> PyResult<usize> {
+        let pipeline = self.inner.clone();
+
+        py.allow_threads(|| {
+            self.runtime.block_on(async move {
+                pipeline.retry_failed().await.map_err(PyErr::from)
+            })
+        })
+    }
+
+    /// Downloads remembered by the dedup store, oldest first
+    pub fn dedup_entries(&self) -> PyResult<Vec<DedupEntry>> {
+        let store = self.dedup_store()?;
+        Ok(self.runtime.block_on(store.entries()))
+    }
+
+    /// Forget every download in the dedup store
+    pub fn clear_dedup_store(&self) -> PyResult<()> {
+        let store = self.dedup_store()?;
+        self.runtime.block_on(store.clear()).map_err(PyErr::from)
+    }
+
+    /// Forget downloads in the dedup store finished more than `max_age_secs`
+    /// ago, returning how many were forgotten
+    pub fn prune_dedup_store(&self, max_age_secs: f64) -> PyResult<usize> {
+        let store = self.dedup_store()?;
+        let max_age = Duration::try_from_secs_f64(max_age_secs)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.runtime.block_on(store.prune(max_age)).map_err(PyErr::from)
+    }
+
+    /// Process the queued URLs as a batch, returning once they (and any pages
+    /// they lead to) are done. The GIL is released, so other threads can add
+    /// URLs or `wait` meanwhile.
     #[pyo3(signature = (concurrency=None, filter=None))]
-    pub fn run(&self, concurrency: Option<usize>, filter: Option<&VideoFilter>) -> PyResult<()> {
-        let inner = self.inner.clone();
+    pub fn run(
+        &self,
+        py: Python<'_>,
+        concurrency: Option<usize>,
+        filter: Option<&VideoFilter>,
+    ) -> PyResult<()> {
+        let pipeline = self.inner.clone();
         let concurrency = concurrency.unwrap_or(16);
         let filter = filter.cloned();
 
-        self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
-            pipeline.run(concurrency, filter).await;
-            Ok(())
+        py.allow_threads(|| {
+            self.runtime.block_on(async move {
+                pipeline.run(concurrency, filter).await;
+                Ok(())
+            })
+        })
+    }
+
+    /// Async variant of `run`; awaiting it does not block the event loop
+    #[pyo3(signature = (concurrency=None, filter=None))]
+    pub fn run_async<'py>(
+        &self,
+        py: Python<'py>,
+        concurrency: Option<usize>,
+        filter: Option<&VideoFilter>,
+    ) -> PyResult<&'py PyAny> {
+        let pipeline = self.inner.clone();
+        let concurrency = concurrency.unwrap_or(16);
+        let filter = filter.cloned();
+
+        // Cancelling the awaitable stops waiting on the run, not the run
+        // itself, so no job is dropped part way; `stop` ends it
+        crate::runtime::into_awaitable(py, async move {
+            crate::runtime::detached(async move { pipeline.run(concurrency, filter).await }).await
         })
     }
 
+    /// Process URLs as a long-lived service, waiting for more while the queue is
+    /// empty, until `stop` is called from another thread
+    #[pyo3(signature = (concurrency=None, filter=None))]
+    pub fn serve(
+        &self,
+        py: Python<'_>,
+        concurrency: Option<usize>,
+        filter: Option<&VideoFilter>,
+    ) -> PyResult<()> {
+        let pipeline = self.inner.clone();
+        let concurrency = concurrency.unwrap_or(16);
+        let filter = filter.cloned();
+
+        py.allow_threads(|| {
+            self.runtime.block_on(async move {
+                pipeline.serve(concurrency, filter).await;
+                Ok(())
+            })
+        })
+    }
+
+    /// Block until every job is terminal or `timeout_secs` elapses. Returns
+    /// whether all jobs finished.
+    #[pyo3(signature = (timeout_secs=None))]
+    pub fn wait(&self, py: Python<'_>, timeout_secs: Option<f64>) -> PyResult<bool> {
+        let pipeline = self.inner.clone();
+        let timeout = timeout_secs
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs).map_err(|e| {
+                    pyo3::exceptions::PyValueError::new_err(format!("Invalid timeout: {}", e))
+                })
+            })
+            .transpose()?;
+
+        Ok(py.allow_threads(|| self.runtime.block_on(pipeline.wait(timeout))))
+    }
+
     /// Get pipeline statistics
     pub fn stats(&self) -> PyResult<PipelineStats> {
-        let inner = self.inner.clone();
+        let pipeline = self.inner.clone();
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
             Ok(pipeline.stats().await)
         })
     }
 
+    /// Job and download metrics in Prometheus exposition format, for serving
+    /// from a `/metrics` endpoint
+    pub fn metrics_text(&self) -> String {
+        self.runtime.block_on(self.inner.metrics_text())
+    }
+
     /// Get all jobs
     pub fn jobs(&self) -> PyResult<Vec<ScrapeJob>> {
-        let inner = self.inner.clone();
+        let pipeline = self.inner.clone();
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
             Ok(pipeline.jobs().await)
         })
     }
 
     /// Get a specific job
     pub fn get_job(&self, id: &str) -> PyResult<Option<ScrapeJob>> {
-        let inner = self.inner.clone();
+        let pipeline = self.inner.clone();
         let id = id.to_string();
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
             Ok(pipeline.get_job(&id).await)
         })
     }
 
     /// Check if pipeline is running
     pub fn is_running(&self) -> PyResult<bool> {
-        let inner = self.inner.clone();
+        let pipeline = self.inner.clone();
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
             Ok(pipeline.is_running())
         })
     }
 
     /// Stop the pipeline
     pub fn stop(&self) -> PyResult<()> {
-        let inner = self.inner.clone();
+        let pipeline = self.inner.clone();
 
         self.runtime.block_on(async move {
-            let pipeline = inner.lock().await;
             pipeline.stop();
             Ok(())
         })
     }
+
+    /// Stop taking new jobs and wait up to `grace_secs` for in-flight jobs
+    /// before cancelling them. Returns whether they all finished in time.
+    #[pyo3(signature = (grace_secs=30.0))]
+    pub fn shutdown(&self, py: Python<'_>, grace_secs: f64) -> PyResult<bool> {
+        let pipeline = self.inner.clone();
+        let grace = Duration::try_from_secs_f64(grace_secs).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid grace period: {}", e))
+        })?;
+
+        Ok(py.allow_threads(|| self.runtime.block_on(pipeline.shutdown(grace))))
+    }
 }
 
@@ -0,0 +1,148 @@
+//! robots.txt parsing and matching, following RFC 9309
+
+use std::time::Duration;
+
+/// An `Allow` or `Disallow` line
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// A group of rules and the user agents it applies to
+#[derive(Debug, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+/// The rules from one robots.txt that apply to our user agent
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Rules that allow every path, used when a site has no robots.txt
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Parse a robots.txt body, keeping the groups that name our user agent's
+    /// product token (e.g. `videoscraper` for `VideoScraper/0.1.0 (...)`), or
+    /// the `*` groups when none do
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        let token = product_token(user_agent);
+        let mut groups: Vec<Group> = Vec::new();
+        let mut current = Group::default();
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts the next group
+                    if !current.rules.is_empty() || current.crawl_delay.is_some() {
+                        groups.push(std::mem::take(&mut current));
+                    }
+                    current.agents.push(value.to_ascii_lowercase());
+                }
+                // An empty `Disallow:` places no restriction
+                "allow" | "disallow" if !current.agents.is_empty() && !value.is_empty() => {
+                    current.rules.push(Rule {
+                        allow: key.trim().eq_ignore_ascii_case("allow"),
+                        pattern: value.to_string(),
+                    });
+                }
+                "crawl-delay" if !current.agents.is_empty() => {
+                    current.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+                }
+                _ => {}
+            }
+        }
+        groups.push(current);
+
+        let named: Vec<&Group> = groups
+            .iter()
+            .filter(|g| g.agents.contains(&token))
+            .collect();
+        let matching = if named.is_empty() {
+            groups.iter().filter(|g| g.agents.iter().any(|a| a == "*")).collect()
+        } else {
+            named
+        };
+
+        // Groups for the same agent are combined
+        Self {
+            rules: matching.iter().flat_map(|g| g.rules.iter().cloned()).collect(),
+            crawl_delay: matching.iter().filter_map(|g| g.crawl_delay).max(),
+        }
+    }
+
+    /// Whether `path` (with any query string) may be fetched. The longest
+    /// matching pattern wins, and `Allow` wins a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    /// Minimum time between requests asked for with `Crawl-delay`
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// The lowercased name before the version, e.g. `videoscraper`
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Match a path against a rule pattern, where `*` matches any sequence of
+/// characters and a trailing `$` anchors the end of the path
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    if parts.is_empty() {
+        return !anchored || rest.is_empty();
+    }
+
+    for (i, part) in parts.iter().enumerate() {
+        // The `*` before the last part can absorb everything up to it
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
@@ -0,0 +1,69 @@
+//! Process-wide tokio runtime shared by the Python wrappers
+
+use pyo3::prelude::*;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::Runtime;
+use tracing::warn;
+
+static SHARED_RUNTIME: OnceLock<Arc<Runtime>> = OnceLock::new();
+
+/// Build a multi-threaded runtime with `worker_threads` workers, or one per
+/// core when it is 0
+pub fn build_runtime(worker_threads: usize) -> std::io::Result<Runtime> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    if worker_threads > 0 {
+        builder.worker_threads(worker_threads);
+    }
+    builder.enable_all().build()
+}
+
+/// The runtime every Python object runs on, created on first use. Only the
+/// `worker_threads` of the call that creates it takes effect; later callers
+/// asking for a different count get a warning.
+pub(crate) fn shared_runtime(worker_threads: usize) -> PyResult<Arc<Runtime>> {
+    if let Some(runtime) = SHARED_RUNTIME.get() {
+        let workers = runtime.metrics().num_workers();
+        if worker_threads > 0 && worker_threads != workers {
+            warn!(
+                "Ignoring worker_threads={}, the shared runtime already has {} workers",
+                worker_threads, workers
+            );
+        }
+        return Ok(runtime.clone());
+    }
+
+    let runtime = build_runtime(worker_threads).map_err(|e| {
+        pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
+    })?;
+    // If another thread won the race, its runtime is kept and ours is dropped
+    let runtime = SHARED_RUNTIME.get_or_init(|| Arc::new(runtime));
+    // Awaitables run here too; this only fails if an earlier call set it
+    let _ = pyo3_asyncio::tokio::init_with_runtime(runtime);
+    Ok(runtime.clone())
+}
+
+/// Run `future` on the shared runtime and return an asyncio awaitable, bound
+/// to the running event loop, that resolves with its output. Cancelling the
+/// awaitable drops `future`.
+pub(crate) fn into_awaitable<F, T>(py: Python<'_>, future: F) -> PyResult<&PyAny>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: IntoPy<PyObject>,
+{
+    // Hands the shared runtime to pyo3-asyncio before it can build its own
+    shared_runtime(0)?;
+    pyo3_asyncio::tokio::future_into_py(py, future)
+}
+
+/// Run `future` as a task of its own and wait for it, so that cancelling the
+/// awaitable waiting on it leaves it to finish rather than dropping it part way
+pub(crate) async fn detached<F>(future: F) -> PyResult<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future)
+        .await
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Task failed: {}", e)))
+}
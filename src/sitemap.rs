@@ -0,0 +1,98 @@
+//! XML sitemap parsing, including the video sitemap extension
+
+use crate::error::{Result, ScraperError};
+use roxmltree::{Document, Node};
+use url::Url;
+
+/// A `<video:video>` entry of a sitemap
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapVideo {
+    /// The page the video is on, from the enclosing `<url><loc>`
+    pub page_url: String,
+    /// The media file itself, from `<video:content_loc>`
+    pub content_url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub duration_secs: Option<u64>,
+    pub thumbnail_url: Option<String>,
+}
+
+/// A parsed sitemap file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// A `<sitemapindex>`, listing the URLs of further sitemaps
+    Index(Vec<String>),
+    /// A `<urlset>`, reduced to the videos it lists
+    Urls(Vec<SitemapVideo>),
+}
+
+/// Parse a sitemap or sitemap index, resolving URLs against `base_url`.
+/// Videos that only name a player page (`<video:player_loc>`) are skipped,
+/// since there is no file to download.
+pub fn parse_sitemap(text: &str, base_url: &str) -> Result<Sitemap> {
+    let doc = Document::parse(text)
+        .map_err(|e| ScraperError::InvalidFormat(format!("Invalid sitemap: {}", e)))?;
+    let root = doc.root_element();
+    let base = Url::parse(base_url)?;
+    let resolve = |location: &str| {
+        base.join(location)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .map(String::from)
+    };
+
+    match root.tag_name().name() {
+        "sitemapindex" => Ok(Sitemap::Index(
+            children(root, "sitemap")
+                .filter_map(|sitemap| text_of(sitemap, "loc"))
+                .filter_map(resolve)
+                .collect(),
+        )),
+        "urlset" => {
+            let mut videos = Vec::new();
+            for entry in children(root, "url") {
+                let page_url = text_of(entry, "loc")
+                    .and_then(resolve)
+                    .unwrap_or_else(|| base.to_string());
+
+                for video in children(entry, "video") {
+                    let Some(content_url) = text_of(video, "content_loc").and_then(resolve) else {
+                        continue;
+                    };
+                    videos.push(SitemapVideo {
+                        page_url: page_url.clone(),
+                        content_url,
+                        title: text_of(video, "title").map(str::to_string),
+                        description: text_of(video, "description").map(str::to_string),
+                        duration_secs: text_of(video, "duration").and_then(|d| d.parse().ok()),
+                        thumbnail_url: text_of(video, "thumbnail_loc").and_then(resolve),
+                    });
+                }
+            }
+            Ok(Sitemap::Urls(videos))
+        }
+        other => Err(ScraperError::InvalidFormat(format!(
+            "Unexpected sitemap root element <{}>",
+            other
+        ))),
+    }
+}
+
+/// Child elements with the local name `name`, in any namespace, since
+/// sitemaps prefix the video extension inconsistently
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |c| c.is_element() && c.tag_name().name() == name)
+}
+
+/// Trimmed text of the first `name` child, if not empty
+fn text_of<'a>(node: Node<'a, '_>, name: &'static str) -> Option<&'a str> {
+    children(node, name)
+        .next()
+        .and_then(|child| child.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
@@ -3,12 +3,21 @@
 use crate::config::StorageConfig;
 use crate::error::{Result, ScraperError};
 use async_trait::async_trait;
+#[cfg(feature = "s3")]
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+#[cfg(feature = "s3")]
+use tracing::warn;
+#[cfg(feature = "s3")]
+use aws_sdk_s3::primitives::ByteStream;
+#[cfg(feature = "s3")]
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
 use bytes::Bytes;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
 use pyo3::prelude::*;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs::{self, File};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tracing::info;
 
 /// Metadata for stored objects
@@ -43,9 +52,31 @@ pub trait StorageBackend: Send + Sync {
     /// Store a local file at the given key
     async fn put_file(&self, key: &str, local_path: &Path) -> Result<ObjectMetadata>;
 
+    /// Store bytes as they arrive from a stream, without staging them on
+    /// disk. The default collects the stream in memory and calls `put`;
+    /// backends override it to write or upload incrementally.
+    async fn put_stream<'a>(
+        &self,
+        key: &str,
+        data: BoxStream<'a, Result<Bytes>>,
+    ) -> Result<ObjectMetadata> {
+        let chunks: Vec<Bytes> = data.try_collect().await?;
+        self.put(key, Bytes::from(chunks.concat())).await
+    }
+
     /// Get bytes for the given key
     async fn get(&self, key: &str) -> Result<Bytes>;
 
+    /// Get bytes `start..end` of an object, e.g. to probe a container header
+    /// without downloading the whole file. A range running past the end of
+    /// the object is cut short.
+    async fn get_range(&self, _key: &str, _start: u64, _end: u64) -> Result<Bytes> {
+        Err(ScraperError::StorageError(format!(
+            "Range reads are not supported by {} storage",
+            self.backend_type()
+        )))
+    }
+
     /// Download to a local file
     async fn get_file(&self, key: &str, local_path: &Path) -> Result<()>;
 
@@ -55,17 +86,111 @@ pub trait StorageBackend: Send + Sync {
     /// Delete an object
     async fn delete(&self, key: &str) -> Result<()>;
 
+    /// Delete many objects. The default deletes them one at a time; backends
+    /// override it with a bulk or concurrent delete.
+    async fn delete_batch(&self, keys: &[String]) -> Result<()> {
+        for key in keys {
+            self.delete(key).await?;
+        }
+        Ok(())
+    }
+
     /// List objects with a prefix
     async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>>;
 
     /// Get metadata for an object
     async fn metadata(&self, key: &str) -> Result<ObjectMetadata>;
 
+    /// Copy an object to a new key. The default round-trips the bytes through
+    /// memory; backends override it with a native copy.
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let data = self.get(src_key).await?;
+        self.put(dst_key, data).await
+    }
+
+    /// Move an object to a new key
+    async fn rename(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let metadata = self.copy(src_key, dst_key).await?;
+        self.delete(src_key).await?;
+        Ok(metadata)
+    }
+
+    /// Time-limited URL for downloading an object without credentials
+    async fn presign_get(&self, _key: &str, _expires_secs: u64) -> Result<String> {
+        Err(self.presign_unsupported())
+    }
+
+    /// Time-limited URL for uploading an object without credentials
+    async fn presign_put(&self, _key: &str, _expires_secs: u64) -> Result<String> {
+        Err(self.presign_unsupported())
+    }
+
     /// Get the backend type name
     fn backend_type(&self) -> &str;
+
+    fn presign_unsupported(&self) -> ScraperError {
+        ScraperError::StorageError(format!(
+            "Presigned URLs are not supported by {} storage",
+            self.backend_type()
+        ))
+    }
+}
+
+/// Reject a backwards `start..end` range; `Ok(true)` means it's empty and
+/// nothing needs to be read
+fn check_range(start: u64, end: u64) -> Result<bool> {
+    if start > end {
+        return Err(ScraperError::StorageError(format!(
+            "Invalid range: start {} is past end {}",
+            start, end
+        )));
+    }
+    Ok(start == end)
+}
+
+/// MIME types for the extensions we expect to store, keyed by lowercase extension
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("mp4", "video/mp4"),
+    ("m4v", "video/x-m4v"),
+    ("m4s", "video/iso.segment"),
+    ("webm", "video/webm"),
+    ("mkv", "video/x-matroska"),
+    ("mov", "video/quicktime"),
+    ("avi", "video/x-msvideo"),
+    ("flv", "video/x-flv"),
+    ("ts", "video/mp2t"),
+    ("m3u8", "application/vnd.apple.mpegurl"),
+    ("mpd", "application/dash+xml"),
+    ("m4a", "audio/mp4"),
+    ("mp3", "audio/mpeg"),
+    ("aac", "audio/aac"),
+    ("ogg", "audio/ogg"),
+    ("opus", "audio/opus"),
+    ("vtt", "text/vtt"),
+    ("srt", "application/x-subrip"),
+    ("json", "application/json"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("webp", "image/webp"),
+];
+
+/// Guess a MIME type from a path's extension
+fn guess_content_type(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    CONTENT_TYPES
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, t)| t.to_string())
 }
 
+/// Files removed at once by `LocalStorage::delete_batch`
+const LOCAL_DELETE_CONCURRENCY: usize = 32;
+
 /// Local filesystem storage backend
+///
+/// Presigning is unsupported: a `file://` path would grant no access control
+/// or expiry, so `presign_get` and `presign_put` return an error.
 pub struct LocalStorage {
     base_path: PathBuf,
 }
@@ -79,6 +204,16 @@ impl LocalStorage {
     fn get_full_path(&self, key: &str) -> PathBuf {
         self.base_path.join(key)
     }
+
+    /// Key for a path under `base_path`, `/`-separated to match what `put` accepts
+    fn key_for_path(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.base_path).unwrap_or(path);
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
 }
 
 #[async_trait]
@@ -100,7 +235,47 @@ impl StorageBackend for LocalStorage {
         Ok(ObjectMetadata {
             key: key.to_string(),
             size_bytes: size,
-            content_type: None,
+            content_type: guess_content_type(&path),
+            etag: None,
+            last_modified: Some(chrono::Utc::now().to_rfc3339()),
+        })
+    }
+
+    async fn put_stream<'a>(
+        &self,
+        key: &str,
+        mut data: BoxStream<'a, Result<Bytes>>,
+    ) -> Result<ObjectMetadata> {
+        let path = self.get_full_path(key);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut file = File::create(&path).await?;
+        let mut size = 0u64;
+        let written = async {
+            while let Some(chunk) = data.try_next().await? {
+                file.write_all(&chunk).await?;
+                size += chunk.len() as u64;
+            }
+            file.flush().await?;
+            Ok::<_, ScraperError>(())
+        }
+        .await;
+
+        // Don't leave a truncated object behind
+        if let Err(e) = written {
+            drop(file);
+            let _ = fs::remove_file(&path).await;
+            return Err(e);
+        }
+        info!("Stored {} bytes to local path: {:?}", size, path);
+
+        Ok(ObjectMetadata {
+            key: key.to_string(),
+            size_bytes: size,
+            content_type: guess_content_type(&path),
             etag: None,
             last_modified: Some(chrono::Utc::now().to_rfc3339()),
         })
@@ -113,14 +288,20 @@ impl StorageBackend for LocalStorage {
             fs::create_dir_all(parent).await?;
         }
 
-        fs::copy(local_path, &dest_path).await?;
+        // Copying a file onto itself would truncate it
+        let src = fs::canonicalize(local_path).await;
+        let dest = fs::canonicalize(&dest_path).await;
+        let same_file = matches!((src, dest), (Ok(src), Ok(dest)) if src == dest);
+        if !same_file {
+            fs::copy(local_path, &dest_path).await?;
+        }
 
         let metadata = fs::metadata(&dest_path).await?;
         
         Ok(ObjectMetadata {
             key: key.to_string(),
             size_bytes: metadata.len(),
-            content_type: None,
+            content_type: guess_content_type(&dest_path),
             etag: None,
             last_modified: Some(chrono::Utc::now().to_rfc3339()),
         })
@@ -134,6 +315,18 @@ impl StorageBackend for LocalStorage {
         Ok(Bytes::from(data))
     }
 
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        if check_range(start, end)? {
+            return Ok(Bytes::new());
+        }
+
+        let mut file = File::open(self.get_full_path(key)).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let mut data = Vec::new();
+        file.take(end - start).read_to_end(&mut data).await?;
+        Ok(Bytes::from(data))
+    }
+
     async fn get_file(&self, key: &str, local_path: &Path) -> Result<()> {
         let src_path = self.get_full_path(key);
 
@@ -158,25 +351,79 @@ impl StorageBackend for LocalStorage {
         Ok(())
     }
 
+    async fn delete_batch(&self, keys: &[String]) -> Result<()> {
+        let deletes: Vec<_> = keys.iter().map(|key| self.delete(key)).collect();
+        futures::stream::iter(deletes)
+            .buffer_unordered(LOCAL_DELETE_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let dst_path = self.get_full_path(dst_key);
+        if let Some(parent) = dst_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        fs::copy(self.get_full_path(src_key), &dst_path).await?;
+        self.metadata(dst_key).await
+    }
+
+    async fn rename(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let src_path = self.get_full_path(src_key);
+        let dst_path = self.get_full_path(dst_key);
+        if let Some(parent) = dst_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        match fs::rename(&src_path, &dst_path).await {
+            Ok(()) => {}
+            // Renames fail across filesystems; fall back to copy and delete
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                fs::copy(&src_path, &dst_path).await?;
+                fs::remove_file(&src_path).await?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+        self.metadata(dst_key).await
+    }
+
+    /// List every file whose key starts with `prefix`, like an S3 prefix listing
     async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>> {
-        let path = self.get_full_path(prefix);
         let mut results = Vec::new();
 
-        if !path.exists() {
+        // Start from the deepest directory the prefix fully names
+        let start = match prefix.rfind('/') {
+            Some(i) => self.get_full_path(&prefix[..i]),
+            None => self.base_path.clone(),
+        };
+        if !start.is_dir() {
             return Ok(results);
         }
 
-        let mut entries = fs::read_dir(&path).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            let metadata = entry.metadata().await?;
-            if metadata.is_file() {
-                results.push(ObjectMetadata {
-                    key: entry.path().to_string_lossy().to_string(),
-                    size_bytes: metadata.len(),
-                    content_type: None,
-                    etag: None,
-                    last_modified: None,
-                });
+        // Walk with an explicit stack, holding one directory handle open at a time
+        let mut pending = vec![start];
+        while let Some(dir) = pending.pop() {
+            let mut entries = fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                let key = self.key_for_path(&path);
+                // Doesn't follow symlinks, so linked directories can't cause cycles
+                let metadata = entry.metadata().await?;
+
+                if metadata.is_dir() {
+                    if format!("{}/", key).starts_with(prefix) {
+                        pending.push(path);
+                    }
+                } else if metadata.is_file() && key.starts_with(prefix) {
+                    results.push(ObjectMetadata {
+                        key,
+                        size_bytes: metadata.len(),
+                        content_type: guess_content_type(&path),
+                        etag: None,
+                        last_modified: None,
+                    });
+                }
             }
         }
 
@@ -190,7 +437,7 @@ impl StorageBackend for LocalStorage {
         Ok(ObjectMetadata {
             key: key.to_string(),
             size_bytes: metadata.len(),
-            content_type: None,
+            content_type: guess_content_type(&path),
             etag: None,
             last_modified: None,
         })
@@ -207,8 +454,32 @@ pub struct S3Storage {
     client: aws_sdk_s3::Client,
     bucket: String,
     key_prefix: String,
+    enable_multipart: bool,
+    multipart_threshold_bytes: u64,
+    multipart_part_size_bytes: u64,
+    max_retries: u32,
 }
 
+/// Most keys S3 accepts in one `DeleteObjects` request
+#[cfg(feature = "s3")]
+const S3_DELETE_BATCH_SIZE: usize = 1000;
+
+/// Delay before the first retry of a failed S3 request, doubling after each
+#[cfg(feature = "s3")]
+const S3_RETRY_BASE_DELAY_MS: u64 = 200;
+
+/// Longest wait between retries of a failed S3 request
+#[cfg(feature = "s3")]
+const S3_RETRY_MAX_DELAY_MS: u64 = 20_000;
+
+/// S3 rejects parts smaller than 5 MiB (other than the last one)
+#[cfg(feature = "s3")]
+const S3_MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// S3 allows at most this many parts per upload
+#[cfg(feature = "s3")]
+const S3_MAX_PARTS: u64 = 10_000;
+
 #[cfg(feature = "s3")]
 impl S3Storage {
     pub async fn new(config: &StorageConfig) -> Result<Self> {
@@ -236,29 +507,69 @@ impl S3Storage {
             client,
             bucket,
             key_prefix: config.key_prefix.clone(),
+            enable_multipart: config.enable_multipart,
+            multipart_threshold_bytes: config.multipart_threshold_bytes,
+            multipart_part_size_bytes: config.multipart_part_size_bytes,
+            max_retries: config.storage_max_retries,
         })
     }
 
     fn full_key(&self, key: &str) -> String {
         format!("{}{}", self.key_prefix, key)
     }
-}
 
-#[cfg(feature = "s3")]
-#[async_trait]
-impl StorageBackend for S3Storage {
-    async fn put(&self, key: &str, data: Bytes) -> Result<ObjectMetadata> {
+    /// Send the request `send` builds, building and sending it again after
+    /// throttling, 5xx responses and connection failures, up to
+    /// `max_retries` times with exponential backoff and full jitter
+    async fn send_with_retries<T, E, F, Fut>(&self, action: &str, send: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<T, SdkError<E>>>,
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match send().await {
+                Ok(output) => return Ok(output),
+                Err(e) if attempt <= self.max_retries && is_transient_s3_error(&e) => {
+                    let max_ms = S3_RETRY_BASE_DELAY_MS
+                        .saturating_mul(1 << (attempt - 1).min(16))
+                        .min(S3_RETRY_MAX_DELAY_MS);
+                    let delay_ms = rand::random::<u64>() % (max_ms + 1);
+                    let delay = std::time::Duration::from_millis(delay_ms);
+                    warn!(
+                        "S3 {} failed (attempt {}/{}), retrying in {:?}: {}",
+                        action,
+                        attempt,
+                        self.max_retries + 1,
+                        delay,
+                        DisplayErrorContext(&e)
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(ScraperError::S3Error(e.to_string())),
+            }
+        }
+    }
+
+    /// Single-request upload of an in-memory or file body
+    async fn put_object(&self, key: &str, body: ByteStream, size: u64) -> Result<ObjectMetadata> {
         let full_key = self.full_key(key);
-        let size = data.len() as u64;
+        let body = body.into_inner();
 
-        self.client
-            .put_object()
-            .bucket(&self.bucket)
-            .key(&full_key)
-            .body(data.into())
-            .send()
-            .await
-            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+        self.send_with_retries("put_object", || {
+            // Bytes and file bodies are rebuilt for each attempt
+            let body = body.try_clone().expect("S3 upload bodies are replayable");
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&full_key)
+                .content_length(size as i64)
+                .body(ByteStream::new(body))
+                .send()
+        })
+        .await?;
 
         info!("Stored {} bytes to S3: s3://{}/{}", size, self.bucket, full_key);
 
@@ -271,21 +582,217 @@ impl StorageBackend for S3Storage {
         })
     }
 
+    /// Upload a large body one part at a time, aborting the upload if any
+    /// step fails
+    async fn put_multipart(
+        &self,
+        key: &str,
+        parts: impl futures::Stream<Item = Result<Bytes>> + Send,
+    ) -> Result<ObjectMetadata> {
+        let full_key = self.full_key(key);
+
+        let upload = self
+            .send_with_retries("create_multipart_upload", || {
+                self.client
+                    .create_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&full_key)
+                    .send()
+            })
+            .await?;
+        let upload_id = upload.upload_id.ok_or_else(|| {
+            ScraperError::S3Error("create_multipart_upload returned no upload id".to_string())
+        })?;
+
+        let mut size = 0;
+        let result = match self.upload_parts(&full_key, &upload_id, parts, &mut size).await {
+            Ok(parts) => {
+                let completed = aws_sdk_s3::types::CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build();
+                self.send_with_retries("complete_multipart_upload", || {
+                    self.client
+                        .complete_multipart_upload()
+                        .bucket(&self.bucket)
+                        .key(&full_key)
+                        .upload_id(&upload_id)
+                        .multipart_upload(completed.clone())
+                        .send()
+                })
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let completed = match result {
+            Ok(completed) => completed,
+            Err(e) => {
+                // Abandoned uploads keep billing for their parts until aborted
+                if let Err(abort_err) = self.client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&full_key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await
+                {
+                    tracing::warn!("Failed to abort multipart upload {}: {}", upload_id, abort_err);
+                }
+                return Err(e);
+            }
+        };
+
+        info!(
+            "Stored {} bytes to S3 via multipart upload: s3://{}/{}",
+            size, self.bucket, full_key
+        );
+
+        Ok(ObjectMetadata {
+            key: full_key,
+            size_bytes: size,
+            content_type: None,
+            etag: completed.e_tag,
+            last_modified: Some(chrono::Utc::now().to_rfc3339()),
+        })
+    }
+
+    /// Upload each part as it is produced, so memory stays at a single part,
+    /// counting the bytes sent into `size`
+    async fn upload_parts(
+        &self,
+        full_key: &str,
+        upload_id: &str,
+        parts: impl futures::Stream<Item = Result<Bytes>> + Send,
+        size: &mut u64,
+    ) -> Result<Vec<aws_sdk_s3::types::CompletedPart>> {
+        let mut parts = std::pin::pin!(parts);
+        let mut completed = Vec::new();
+        let mut part_number = 1;
+
+        while let Some(buf) = parts.try_next().await? {
+            *size += buf.len() as u64;
+            let response = self
+                .send_with_retries("upload_part", || {
+                    self.client
+                        .upload_part()
+                        .bucket(&self.bucket)
+                        .key(full_key)
+                        .upload_id(upload_id)
+                        .part_number(part_number)
+                        .body(buf.clone().into())
+                        .send()
+                })
+                .await?;
+
+            completed.push(
+                aws_sdk_s3::types::CompletedPart::builder()
+                    .set_e_tag(response.e_tag)
+                    .part_number(part_number)
+                    .build(),
+            );
+            part_number += 1;
+        }
+
+        Ok(completed)
+    }
+}
+
+/// Read a file as a stream of `part_size`-byte parts
+#[cfg(any(feature = "s3", feature = "azure"))]
+async fn file_parts(
+    local_path: &Path,
+    part_size: u64,
+) -> Result<impl futures::Stream<Item = Result<Bytes>> + Send> {
+    let file = File::open(local_path).await?;
+    Ok(futures::stream::try_unfold(file, move |mut file| async move {
+        let mut buf = Vec::new();
+        (&mut file).take(part_size).read_to_end(&mut buf).await?;
+        Ok((!buf.is_empty()).then(|| (Bytes::from(buf), file)))
+    }))
+}
+
+/// Regroup a byte stream into parts of at least `part_size` bytes (the last
+/// may be shorter)
+#[cfg(any(feature = "s3", feature = "azure"))]
+fn regroup(
+    data: BoxStream<'_, Result<Bytes>>,
+    part_size: u64,
+) -> impl futures::Stream<Item = Result<Bytes>> + Send + '_ {
+    futures::stream::try_unfold(Some(data), move |data| async move {
+        let Some(mut data) = data else {
+            return Ok(None);
+        };
+        let mut part = bytes::BytesMut::new();
+        while (part.len() as u64) < part_size {
+            match data.try_next().await? {
+                Some(chunk) => part.extend_from_slice(&chunk),
+                None if part.is_empty() => return Ok(None),
+                None => return Ok(Some((part.freeze(), None))),
+            }
+        }
+        Ok(Some((part.freeze(), Some(data))))
+    })
+}
+
+#[cfg(feature = "s3")]
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, data: Bytes) -> Result<ObjectMetadata> {
+        let size = data.len() as u64;
+        self.put_object(key, data.into(), size).await
+    }
+
     async fn put_file(&self, key: &str, local_path: &Path) -> Result<ObjectMetadata> {
-        let data = fs::read(local_path).await?;
-        self.put(key, Bytes::from(data)).await
+        let size = fs::metadata(local_path).await?.len();
+        if self.enable_multipart && size > self.multipart_threshold_bytes {
+            let part_size = self
+                .multipart_part_size_bytes
+                .max(S3_MIN_PART_SIZE)
+                .max(size.div_ceil(S3_MAX_PARTS));
+            return self
+                .put_multipart(key, file_parts(local_path, part_size).await?)
+                .await;
+        }
+
+        // Streams from disk rather than reading the file into memory
+        let body = ByteStream::from_path(local_path)
+            .await
+            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+        self.put_object(key, body, size).await
+    }
+
+    /// Uploads part by part once the body outgrows one part, so at most one
+    /// part is held in memory. The total size isn't known up front, so the
+    /// part size isn't scaled up for huge bodies the way `put_file` does.
+    async fn put_stream<'a>(
+        &self,
+        key: &str,
+        data: BoxStream<'a, Result<Bytes>>,
+    ) -> Result<ObjectMetadata> {
+        let part_size = self.multipart_part_size_bytes.max(S3_MIN_PART_SIZE);
+        if !self.enable_multipart {
+            let chunks: Vec<Bytes> = data.try_collect().await?;
+            return self.put(key, Bytes::from(chunks.concat())).await;
+        }
+
+        let mut parts = std::pin::pin!(regroup(data, part_size));
+        let first = parts.try_next().await?.unwrap_or_default();
+        if (first.len() as u64) < part_size {
+            // The whole body fit in one part
+            return self.put(key, first).await;
+        }
+        let parts = futures::StreamExt::chain(futures::stream::iter([Ok(first)]), parts);
+        self.put_multipart(key, parts).await
     }
 
     async fn get(&self, key: &str) -> Result<Bytes> {
         let full_key = self.full_key(key);
 
-        let response = self.client
-            .get_object()
-            .bucket(&self.bucket)
-            .key(&full_key)
-            .send()
-            .await
-            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+        let response = self
+            .send_with_retries("get_object", || {
+                self.client.get_object().bucket(&self.bucket).key(&full_key).send()
+            })
+            .await?;
 
         let data = response.body.collect().await
             .map_err(|e| ScraperError::S3Error(e.to_string()))?;
@@ -293,15 +800,65 @@ impl StorageBackend for S3Storage {
         Ok(data.into_bytes())
     }
 
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        if check_range(start, end)? {
+            return Ok(Bytes::new());
+        }
+
+        let full_key = self.full_key(key);
+        let range = format!("bytes={}-{}", start, end - 1);
+
+        let response = self
+            .send_with_retries("get_object", || {
+                self.client
+                    .get_object()
+                    .bucket(&self.bucket)
+                    .key(&full_key)
+                    .range(&range)
+                    .send()
+            })
+            .await?;
+
+        let data = response.body.collect().await
+            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+
+        Ok(data.into_bytes())
+    }
+
     async fn get_file(&self, key: &str, local_path: &Path) -> Result<()> {
-        let data = self.get(key).await?;
-        
+        let full_key = self.full_key(key);
+
+        let response = self
+            .send_with_retries("get_object", || {
+                self.client.get_object().bucket(&self.bucket).key(&full_key).send()
+            })
+            .await?;
+
         if let Some(parent) = local_path.parent() {
             fs::create_dir_all(parent).await?;
         }
 
-        fs::write(local_path, data).await?;
-        Ok(())
+        // Write each chunk as it arrives so memory stays flat for large objects
+        let mut body = response.body;
+        let mut file = File::create(local_path).await?;
+        let written: Result<()> = async {
+            while let Some(chunk) = body
+                .try_next()
+                .await
+                .map_err(|e| ScraperError::S3Error(e.to_string()))?
+            {
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        if written.is_err() {
+            drop(file);
+            let _ = fs::remove_file(local_path).await;
+        }
+        written
     }
 
     async fn exists(&self, key: &str) -> Result<bool> {
@@ -322,17 +879,80 @@ impl StorageBackend for S3Storage {
     async fn delete(&self, key: &str) -> Result<()> {
         let full_key = self.full_key(key);
 
-        self.client
-            .delete_object()
-            .bucket(&self.bucket)
-            .key(&full_key)
-            .send()
-            .await
-            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+        self.send_with_retries("delete_object", || {
+            self.client.delete_object().bucket(&self.bucket).key(&full_key).send()
+        })
+        .await?;
 
         Ok(())
     }
 
+    /// `DeleteObjects` in batches of up to 1000 keys. Fails listing the keys
+    /// S3 couldn't delete once every batch has been sent.
+    async fn delete_batch(&self, keys: &[String]) -> Result<()> {
+        let mut failures = Vec::new();
+
+        for batch in keys.chunks(S3_DELETE_BATCH_SIZE) {
+            let objects = batch
+                .iter()
+                .map(|key| ObjectIdentifier::builder().key(self.full_key(key)).build())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()
+                .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+
+            let response = self
+                .send_with_retries("delete_objects", || {
+                    self.client
+                        .delete_objects()
+                        .bucket(&self.bucket)
+                        .delete(delete.clone())
+                        .send()
+                })
+                .await?;
+
+            failures.extend(response.errors().iter().map(|error| {
+                format!(
+                    "{} ({})",
+                    error.key().unwrap_or_default(),
+                    error.message().or(error.code()).unwrap_or("unknown error")
+                )
+            }));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(ScraperError::S3Error(format!(
+                "Failed to delete {} of {} objects: {}",
+                failures.len(),
+                keys.len(),
+                failures.join(", ")
+            )))
+        }
+    }
+
+    /// Server-side copy; S3 limits a single `CopyObject` to 5 GiB
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let source_key = encode_copy_source(&self.full_key(src_key));
+        let copy_source = format!("{}/{}", self.bucket, source_key);
+
+        self.send_with_retries("copy_object", || {
+            self.client
+                .copy_object()
+                .bucket(&self.bucket)
+                .key(self.full_key(dst_key))
+                .copy_source(&copy_source)
+                .send()
+        })
+        .await?;
+
+        self.metadata(dst_key).await
+    }
+
     async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>> {
         let full_prefix = self.full_key(prefix);
         let mut results = Vec::new();
@@ -393,60 +1013,675 @@ impl StorageBackend for S3Storage {
         })
     }
 
-    fn backend_type(&self) -> &str {
-        "s3"
+    async fn presign_get(&self, key: &str, expires_secs: u64) -> Result<String> {
+        let request = self.client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .presigned(presigning_config(expires_secs)?)
+            .await
+            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
+
+        Ok(request.uri().to_string())
     }
-}
 
-/// Storage manager that abstracts over different backends
-pub struct StorageManager {
-    backend: Arc<dyn StorageBackend>,
-}
+    async fn presign_put(&self, key: &str, expires_secs: u64) -> Result<String> {
+        let request = self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .presigned(presigning_config(expires_secs)?)
+            .await
+            .map_err(|e| ScraperError::S3Error(e.to_string()))?;
 
-impl StorageManager {
-    /// Create a new storage manager with the given configuration
-    pub async fn new(config: &StorageConfig) -> Result<Self> {
-        let backend: Arc<dyn StorageBackend> = match config.backend.as_str() {
-            "local" => Arc::new(LocalStorage::new(&config.local_path)?),
-            #[cfg(feature = "s3")]
-            "s3" => Arc::new(S3Storage::new(config).await?),
-            #[cfg(not(feature = "s3"))]
-            "s3" => {
-                return Err(ScraperError::ConfigError(
-                    "S3 storage requires the 's3' feature to be enabled".to_string()
-                ))
-            }
-            #[cfg(feature = "gcs")]
-            "gcs" => {
-                return Err(ScraperError::ConfigError(
-                    "GCS storage not yet implemented".to_string()
-                ))
-            }
-            #[cfg(not(feature = "gcs"))]
-            "gcs" => {
-                return Err(ScraperError::ConfigError(
-                    "GCS storage requires the 'gcs' feature to be enabled".to_string()
-                ))
-            }
-            _ => {
-                return Err(ScraperError::ConfigError(format!(
-                    "Unknown storage backend: {}",
-                    config.backend
-                )))
-            }
-        };
+        Ok(request.uri().to_string())
+    }
 
-        Ok(Self { backend })
+    fn backend_type(&self) -> &str {
+        "s3"
     }
+}
 
-    /// Get the underlying storage backend
-    pub fn backend(&self) -> &dyn StorageBackend {
-        self.backend.as_ref()
+/// Whether a failed S3 request is worth retrying: throttling (`SlowDown`,
+/// 503, 429), other 5xx responses, timeouts and connection failures
+#[cfg(feature = "s3")]
+fn is_transient_s3_error<E: ProvideErrorMetadata>(err: &SdkError<E>) -> bool {
+    match err {
+        SdkError::TimeoutError(_) | SdkError::ResponseError(_) => true,
+        SdkError::DispatchFailure(failure) => failure.is_io() || failure.is_timeout(),
+        SdkError::ServiceError(e) => {
+            let status = e.raw().status().as_u16();
+            status >= 500
+                || status == 429
+                || matches!(
+                    e.err().code(),
+                    Some("SlowDown" | "Throttling" | "RequestTimeout" | "InternalError")
+                )
+        }
+        _ => false,
     }
 }
 
-/// Python-exposed storage client
-#[pyclass]
+/// Percent-encode a key for `x-amz-copy-source`, keeping `/` separators
+#[cfg(feature = "s3")]
+fn encode_copy_source(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(feature = "s3")]
+fn presigning_config(expires_secs: u64) -> Result<aws_sdk_s3::presigning::PresigningConfig> {
+    aws_sdk_s3::presigning::PresigningConfig::expires_in(std::time::Duration::from_secs(
+        expires_secs,
+    ))
+    .map_err(|e| ScraperError::S3Error(e.to_string()))
+}
+
+/// Azure Blob Storage backend (requires 'azure' feature)
+///
+/// Large uploads are staged as blocks and committed with a block list.
+/// Presigned URLs are SAS URLs, which need an account access key.
+#[cfg(feature = "azure")]
+pub struct AzureBlobStorage {
+    container: azure_storage_blobs::prelude::ContainerClient,
+    key_prefix: String,
+    enable_multipart: bool,
+    multipart_threshold_bytes: u64,
+    multipart_part_size_bytes: u64,
+}
+
+/// Azure allows at most this many blocks per blob
+#[cfg(feature = "azure")]
+const AZURE_MAX_BLOCKS: u64 = 50_000;
+
+/// Size of each ranged request when downloading a blob to a file
+#[cfg(feature = "azure")]
+const AZURE_DOWNLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+#[cfg(feature = "azure")]
+fn azure_error(e: impl std::fmt::Display) -> ScraperError {
+    ScraperError::AzureError(e.to_string())
+}
+
+/// Block size for staging `size` bytes: the configured part size, raised
+/// so the blob fits in `AZURE_MAX_BLOCKS` blocks
+#[cfg(feature = "azure")]
+pub(crate) fn azure_block_size(part_size: u64, size: u64) -> u64 {
+    part_size.max(1).max(size.div_ceil(AZURE_MAX_BLOCKS))
+}
+
+#[cfg(feature = "azure")]
+impl AzureBlobStorage {
+    pub fn new(config: &StorageConfig) -> Result<Self> {
+        use azure_storage::StorageCredentials;
+        use azure_storage_blobs::prelude::ClientBuilder;
+
+        let required = |value: &Option<String>, name: &str| {
+            value.clone().filter(|v| !v.is_empty()).ok_or_else(|| {
+                ScraperError::ConfigError(format!("Azure {} required", name))
+            })
+        };
+        let account = required(&config.azure_account, "storage account")?;
+        let container = required(&config.azure_container, "container name")?;
+
+        let credentials = match (&config.azure_access_key, &config.azure_sas_token) {
+            (Some(key), _) if !key.is_empty() => {
+                StorageCredentials::access_key(account.clone(), key.clone())
+            }
+            (_, Some(token)) if !token.is_empty() => {
+                StorageCredentials::sas_token(token.as_str()).map_err(azure_error)?
+            }
+            _ => {
+                return Err(ScraperError::ConfigError(
+                    "Azure access key or SAS token required".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            container: ClientBuilder::new(account, credentials).container_client(container),
+            key_prefix: config.key_prefix.clone(),
+            enable_multipart: config.enable_multipart,
+            multipart_threshold_bytes: config.multipart_threshold_bytes,
+            multipart_part_size_bytes: config.multipart_part_size_bytes,
+        })
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix, key)
+    }
+
+    fn blob(&self, key: &str) -> azure_storage_blobs::prelude::BlobClient {
+        self.container.blob_client(self.full_key(key))
+    }
+
+    /// Single-request upload of an in-memory body
+    async fn put_blob(&self, key: &str, data: Bytes) -> Result<ObjectMetadata> {
+        let full_key = self.full_key(key);
+        let size = data.len() as u64;
+        let content_type = guess_content_type(Path::new(key));
+
+        let blob = self.blob(key);
+        let mut request = blob.put_block_blob(data);
+        if let Some(ref content_type) = content_type {
+            request = request.content_type(content_type.clone());
+        }
+        let response = request.await.map_err(azure_error)?;
+
+        info!(
+            "Stored {} bytes to Azure: {}/{}",
+            size,
+            self.container.container_name(),
+            full_key
+        );
+
+        Ok(ObjectMetadata {
+            key: full_key,
+            size_bytes: size,
+            content_type,
+            etag: Some(response.etag),
+            last_modified: Some(azure_core::date::to_rfc3339(&response.last_modified)),
+        })
+    }
+
+    /// Stage each part as a block as it is produced, then commit them all.
+    /// Blocks of a failed upload are never committed and Azure discards them.
+    async fn put_blocks(
+        &self,
+        key: &str,
+        parts: impl futures::Stream<Item = Result<Bytes>> + Send,
+    ) -> Result<ObjectMetadata> {
+        use azure_storage_blobs::prelude::*;
+
+        let full_key = self.full_key(key);
+        let blob = self.blob(key);
+        let mut parts = std::pin::pin!(parts);
+        let mut block_list = BlockList::default();
+        let mut size = 0;
+
+        while let Some(part) = parts.try_next().await? {
+            size += part.len() as u64;
+            // Block ids must all have the same length
+            let block_id = BlockId::new(format!("{:08}", block_list.blocks.len()));
+            blob.put_block(block_id.clone(), part).await.map_err(azure_error)?;
+            block_list.blocks.push(BlobBlockType::new_uncommitted(block_id));
+        }
+
+        let content_type = guess_content_type(Path::new(key));
+        let blocks = block_list.blocks.len();
+        let mut request = blob.put_block_list(block_list);
+        if let Some(ref content_type) = content_type {
+            request = request.content_type(content_type.clone());
+        }
+        let response = request.await.map_err(azure_error)?;
+
+        info!(
+            "Stored {} bytes to Azure in {} blocks: {}/{}",
+            size,
+            blocks,
+            self.container.container_name(),
+            full_key
+        );
+
+        Ok(ObjectMetadata {
+            key: full_key,
+            size_bytes: size,
+            content_type,
+            etag: Some(response.etag),
+            last_modified: Some(azure_core::date::to_rfc3339(&response.last_modified)),
+        })
+    }
+
+    /// SAS URL for `key` with the given permissions
+    async fn presign(
+        &self,
+        key: &str,
+        expires_secs: u64,
+        permissions: azure_storage::prelude::BlobSasPermissions,
+    ) -> Result<String> {
+        let blob = self.blob(key);
+        let expiry = chrono::Utc::now()
+            + chrono::Duration::seconds(i64::try_from(expires_secs).unwrap_or(i64::MAX));
+        let expiry = azure_core::date::parse_rfc3339(&expiry.to_rfc3339()).map_err(azure_error)?;
+        let sas = blob
+            .shared_access_signature(permissions, expiry)
+            .await
+            .map_err(azure_error)?;
+        Ok(blob.generate_signed_blob_url(&sas).map_err(azure_error)?.to_string())
+    }
+}
+
+#[cfg(feature = "azure")]
+fn blob_metadata(blob: &azure_storage_blobs::prelude::Blob) -> ObjectMetadata {
+    ObjectMetadata {
+        key: blob.name.clone(),
+        size_bytes: blob.properties.content_length,
+        content_type: Some(blob.properties.content_type.clone()),
+        etag: Some(blob.properties.etag.to_string()),
+        last_modified: Some(azure_core::date::to_rfc3339(&blob.properties.last_modified)),
+    }
+}
+
+#[cfg(feature = "azure")]
+#[async_trait]
+impl StorageBackend for AzureBlobStorage {
+    async fn put(&self, key: &str, data: Bytes) -> Result<ObjectMetadata> {
+        self.put_blob(key, data).await
+    }
+
+    async fn put_file(&self, key: &str, local_path: &Path) -> Result<ObjectMetadata> {
+        let size = fs::metadata(local_path).await?.len();
+        if self.enable_multipart && size > self.multipart_threshold_bytes {
+            let part_size = azure_block_size(self.multipart_part_size_bytes, size);
+            return self
+                .put_blocks(key, file_parts(local_path, part_size).await?)
+                .await;
+        }
+
+        self.put_blob(key, Bytes::from(fs::read(local_path).await?)).await
+    }
+
+    /// Stages blocks once the body outgrows one part, so at most one part is
+    /// held in memory
+    async fn put_stream<'a>(
+        &self,
+        key: &str,
+        data: BoxStream<'a, Result<Bytes>>,
+    ) -> Result<ObjectMetadata> {
+        let part_size = azure_block_size(self.multipart_part_size_bytes, 0);
+        if !self.enable_multipart {
+            let chunks: Vec<Bytes> = data.try_collect().await?;
+            return self.put_blob(key, Bytes::from(chunks.concat())).await;
+        }
+
+        let mut parts = std::pin::pin!(regroup(data, part_size));
+        let first = parts.try_next().await?.unwrap_or_default();
+        if (first.len() as u64) < part_size {
+            return self.put_blob(key, first).await;
+        }
+        let parts = futures::StreamExt::chain(futures::stream::iter([Ok(first)]), parts);
+        self.put_blocks(key, parts).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        let data = self.blob(key).get_content().await.map_err(azure_error)?;
+        Ok(Bytes::from(data))
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        if check_range(start, end)? {
+            return Ok(Bytes::new());
+        }
+
+        // Ranges beyond one chunk come back in several responses
+        let mut chunks = self.blob(key).get().range(start..end).into_stream();
+        let mut data = Vec::with_capacity((end - start) as usize);
+        while let Some(chunk) = chunks.try_next().await.map_err(azure_error)? {
+            data.extend_from_slice(&chunk.data.collect().await.map_err(azure_error)?);
+        }
+        Ok(Bytes::from(data))
+    }
+
+    async fn get_file(&self, key: &str, local_path: &Path) -> Result<()> {
+        if let Some(parent) = local_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Fetch in ranges so memory stays flat for large blobs
+        let blob = self.blob(key);
+        let mut chunks = blob.get().chunk_size(AZURE_DOWNLOAD_CHUNK_SIZE).into_stream();
+        let mut file = File::create(local_path).await?;
+        let written: Result<()> = async {
+            while let Some(chunk) = chunks.try_next().await.map_err(azure_error)? {
+                let data = chunk.data.collect().await.map_err(azure_error)?;
+                file.write_all(&data).await?;
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        if written.is_err() {
+            drop(file);
+            let _ = fs::remove_file(local_path).await;
+        }
+        written
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        self.blob(key).exists().await.map_err(azure_error)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.blob(key).delete().await.map_err(azure_error)?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>> {
+        let full_prefix = self.full_key(prefix);
+        let mut results = Vec::new();
+
+        // The stream follows continuation markers page by page
+        let mut pages = self.container.list_blobs().prefix(full_prefix).into_stream();
+        while let Some(page) = pages.try_next().await.map_err(azure_error)? {
+            results.extend(page.blobs.blobs().map(blob_metadata));
+        }
+
+        Ok(results)
+    }
+
+    async fn metadata(&self, key: &str) -> Result<ObjectMetadata> {
+        let response = self.blob(key).get_properties().await.map_err(azure_error)?;
+        Ok(ObjectMetadata {
+            key: self.full_key(key),
+            ..blob_metadata(&response.blob)
+        })
+    }
+
+    async fn presign_get(&self, key: &str, expires_secs: u64) -> Result<String> {
+        let permissions = azure_storage::prelude::BlobSasPermissions {
+            read: true,
+            ..Default::default()
+        };
+        self.presign(key, expires_secs, permissions).await
+    }
+
+    async fn presign_put(&self, key: &str, expires_secs: u64) -> Result<String> {
+        let permissions = azure_storage::prelude::BlobSasPermissions {
+            create: true,
+            write: true,
+            ..Default::default()
+        };
+        self.presign(key, expires_secs, permissions).await
+    }
+
+    fn backend_type(&self) -> &str {
+        "azure"
+    }
+}
+
+/// Writes to several backends at once and reads from the first that has a key
+///
+/// `presign_get` uses the first backend that supports it. `presign_put` is
+/// unsupported, since an upload through one URL would reach only one backend.
+pub struct FanoutStorage {
+    backends: Vec<Arc<dyn StorageBackend>>,
+}
+
+impl FanoutStorage {
+    pub fn new(backends: Vec<Arc<dyn StorageBackend>>) -> Result<Self> {
+        if backends.is_empty() {
+            return Err(ScraperError::ConfigError(
+                "Fanout storage needs at least one backend".to_string(),
+            ));
+        }
+        Ok(Self { backends })
+    }
+
+    /// Run `op` against every backend concurrently. Fails naming each backend
+    /// that failed, even if others succeeded, and those that succeeded, since
+    /// a failed write stays applied on them.
+    async fn on_all<'a, T, F, Fut>(&'a self, action: &str, op: F) -> Result<Vec<T>>
+    where
+        F: Fn(&'a dyn StorageBackend) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let results = futures::future::join_all(
+            self.backends.iter().map(|backend| op(backend.as_ref())),
+        )
+        .await;
+
+        let mut values = Vec::with_capacity(results.len());
+        let mut succeeded = Vec::new();
+        let mut failures = Vec::new();
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(value) => {
+                    values.push(value);
+                    succeeded.push(self.label(i));
+                }
+                Err(e) => failures.push(format!("{}: {}", self.label(i), e)),
+            }
+        }
+
+        if failures.is_empty() {
+            return Ok(values);
+        }
+        let mut message = format!(
+            "{} failed on {} of {} backends: {}",
+            action,
+            failures.len(),
+            self.backends.len(),
+            failures.join("; ")
+        );
+        if !succeeded.is_empty() {
+            message.push_str(&format!(" (succeeded on {})", succeeded.join(", ")));
+        }
+        Err(ScraperError::StorageError(message))
+    }
+
+    /// A backend's position and type, e.g. `backend 2 (s3)`, as backends of
+    /// one type are otherwise indistinguishable
+    fn label(&self, index: usize) -> String {
+        format!("backend {} ({})", index + 1, self.backends[index].backend_type())
+    }
+
+    /// Return the first backend's successful result, or the last error
+    async fn first_ok<'a, T, F, Fut>(&'a self, op: F) -> Result<T>
+    where
+        F: Fn(&'a dyn StorageBackend) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut last_err = None;
+        for backend in &self.backends {
+            match op(backend.as_ref()).await {
+                Ok(value) => return Ok(value),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.expect("fanout storage has at least one backend"))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for FanoutStorage {
+    async fn put(&self, key: &str, data: Bytes) -> Result<ObjectMetadata> {
+        let mut stored = self
+            .on_all("put", |backend| backend.put(key, data.clone()))
+            .await?;
+        Ok(stored.swap_remove(0))
+    }
+
+    async fn put_file(&self, key: &str, local_path: &Path) -> Result<ObjectMetadata> {
+        let mut stored = self
+            .on_all("put_file", |backend| backend.put_file(key, local_path))
+            .await?;
+        Ok(stored.swap_remove(0))
+    }
+
+    /// Stages the stream in a temporary file for every backend to read,
+    /// since one stream can't feed several uploads
+    async fn put_stream<'a>(
+        &self,
+        key: &str,
+        mut data: BoxStream<'a, Result<Bytes>>,
+    ) -> Result<ObjectMetadata> {
+        let staged = std::env::temp_dir()
+            .join(format!("videoscraper-fanout-{}.part", uuid::Uuid::new_v4()));
+        let stored = async {
+            let mut file = File::create(&staged).await?;
+            while let Some(chunk) = data.try_next().await? {
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            drop(file);
+            self.put_file(key, &staged).await
+        }
+        .await;
+
+        let _ = fs::remove_file(&staged).await;
+        stored
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes> {
+        self.first_ok(|backend| backend.get(key)).await
+    }
+
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<Bytes> {
+        self.first_ok(|backend| backend.get_range(key, start, end)).await
+    }
+
+    async fn get_file(&self, key: &str, local_path: &Path) -> Result<()> {
+        self.first_ok(|backend| backend.get_file(key, local_path)).await
+    }
+
+    /// Whether any backend has the key. A backend that errors is skipped;
+    /// the last error is returned only if no other backend has the key.
+    async fn exists(&self, key: &str) -> Result<bool> {
+        let mut last_err = None;
+        for backend in &self.backends {
+            match backend.exists(key).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.on_all("delete", |backend| backend.delete(key)).await?;
+        Ok(())
+    }
+
+    async fn delete_batch(&self, keys: &[String]) -> Result<()> {
+        self.on_all("delete_batch", |backend| backend.delete_batch(keys)).await?;
+        Ok(())
+    }
+
+    async fn copy(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let mut copied = self
+            .on_all("copy", |backend| backend.copy(src_key, dst_key))
+            .await?;
+        Ok(copied.swap_remove(0))
+    }
+
+    async fn rename(&self, src_key: &str, dst_key: &str) -> Result<ObjectMetadata> {
+        let mut renamed = self
+            .on_all("rename", |backend| backend.rename(src_key, dst_key))
+            .await?;
+        Ok(renamed.swap_remove(0))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectMetadata>> {
+        let listings = self.on_all("list", |backend| backend.list(prefix)).await?;
+
+        let mut seen = std::collections::HashSet::new();
+        Ok(listings
+            .into_iter()
+            .flatten()
+            .filter(|object| seen.insert(object.key.clone()))
+            .collect())
+    }
+
+    async fn metadata(&self, key: &str) -> Result<ObjectMetadata> {
+        self.first_ok(|backend| backend.metadata(key)).await
+    }
+
+    async fn presign_get(&self, key: &str, expires_secs: u64) -> Result<String> {
+        self.first_ok(|backend| backend.presign_get(key, expires_secs)).await
+    }
+
+    fn backend_type(&self) -> &str {
+        "fanout"
+    }
+}
+
+/// Storage manager that abstracts over different backends
+pub struct StorageManager {
+    backend: Arc<dyn StorageBackend>,
+}
+
+impl StorageManager {
+    /// Create a new storage manager with the given configuration
+    pub async fn new(config: &StorageConfig) -> Result<Self> {
+        Ok(Self {
+            backend: Self::build_backend(config).await?,
+        })
+    }
+
+    fn build_backend(
+        config: &StorageConfig,
+    ) -> futures::future::BoxFuture<'_, Result<Arc<dyn StorageBackend>>> {
+        // Boxed because fanout configs build their children recursively
+        Box::pin(async move {
+            let backend: Arc<dyn StorageBackend> = match config.backend.as_str() {
+                "local" => Arc::new(LocalStorage::new(&config.local_path)?),
+                "fanout" => {
+                    let mut backends = Vec::with_capacity(config.backends.len());
+                    for child in &config.backends {
+                        backends.push(Self::build_backend(child).await?);
+                    }
+                    Arc::new(FanoutStorage::new(backends)?)
+                }
+                #[cfg(feature = "s3")]
+                "s3" => Arc::new(S3Storage::new(config).await?),
+                #[cfg(not(feature = "s3"))]
+                "s3" => {
+                    return Err(ScraperError::ConfigError(
+                        "S3 storage requires the 's3' feature to be enabled".to_string()
+                    ))
+                }
+                #[cfg(feature = "azure")]
+                "azure" => Arc::new(AzureBlobStorage::new(config)?),
+                #[cfg(not(feature = "azure"))]
+                "azure" => {
+                    return Err(ScraperError::ConfigError(
+                        "Azure storage requires the 'azure' feature to be enabled".to_string()
+                    ))
+                }
+                #[cfg(feature = "gcs")]
+                "gcs" => {
+                    return Err(ScraperError::ConfigError(
+                        "GCS storage not yet implemented".to_string()
+                    ))
+                }
+                #[cfg(not(feature = "gcs"))]
+                "gcs" => {
+                    return Err(ScraperError::ConfigError(
+                        "GCS storage requires the 'gcs' feature to be enabled".to_string()
+                    ))
+                }
+                _ => {
+                    return Err(ScraperError::ConfigError(format!(
+                        "Unknown storage backend: {}",
+                        config.backend
+                    )))
+                }
+            };
+
+            Ok(backend)
+        })
+    }
+
+    /// Get the underlying storage backend
+    pub fn backend(&self) -> &dyn StorageBackend {
+        self.backend.as_ref()
+    }
+}
+
+/// Python-exposed storage client
+#[pyclass]
 pub struct PyStorage {
     manager: Arc<tokio::sync::Mutex<Option<StorageManager>>>,
     config: StorageConfig,
@@ -459,14 +1694,12 @@ impl PyStorage {
     #[pyo3(signature = (config=None))]
     pub fn new(config: Option<&StorageConfig>) -> PyResult<Self> {
         let config = config.cloned().unwrap_or_default();
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to create runtime: {}", e))
-        })?;
+        let runtime = crate::runtime::shared_runtime(0)?;
 
         Ok(Self {
             manager: Arc::new(tokio::sync::Mutex::new(None)),
             config,
-            runtime: Arc::new(runtime),
+            runtime,
         })
     }
 
@@ -476,9 +1709,7 @@ impl PyStorage {
         let manager = self.manager.clone();
 
         self.runtime.block_on(async move {
-            let storage = StorageManager::new(&config).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })?;
+            let storage = StorageManager::new(&config).await.map_err(PyErr::from)?;
             
             let mut guard = manager.lock().await;
             *guard = Some(storage);
@@ -497,9 +1728,7 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().put(&key, Bytes::from(data)).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().put(&key, Bytes::from(data)).await.map_err(PyErr::from)
         })
     }
 
@@ -515,9 +1744,7 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().put_file(&key, &path).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().put_file(&key, &path).await.map_err(PyErr::from)
         })
     }
 
@@ -534,9 +1761,24 @@ impl PyStorage {
 
             storage.backend().get(&key).await
                 .map(|b| b.to_vec())
-                .map_err(|e| {
-                    pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-                })
+                .map_err(PyErr::from)
+        })
+    }
+
+    /// Get bytes `start..end` of an object (end exclusive)
+    pub fn get_range(&self, key: &str, start: u64, end: u64) -> PyResult<Vec<u8>> {
+        let manager = self.manager.clone();
+        let key = key.to_string();
+
+        self.runtime.block_on(async move {
+            let guard = manager.lock().await;
+            let storage = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
+            })?;
+
+            storage.backend().get_range(&key, start, end).await
+                .map(|b| b.to_vec())
+                .map_err(PyErr::from)
         })
     }
 
@@ -552,9 +1794,7 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().get_file(&key, &path).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().get_file(&key, &path).await.map_err(PyErr::from)
         })
     }
 
@@ -569,9 +1809,7 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().exists(&key).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().exists(&key).await.map_err(PyErr::from)
         })
     }
 
@@ -586,9 +1824,53 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().delete(&key).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().delete(&key).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Delete many objects, in bulk where the backend supports it
+    pub fn delete_batch(&self, keys: Vec<String>) -> PyResult<()> {
+        let manager = self.manager.clone();
+
+        self.runtime.block_on(async move {
+            let guard = manager.lock().await;
+            let storage = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
+            })?;
+
+            storage.backend().delete_batch(&keys).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Copy an object to a new key, server-side where the backend supports it
+    pub fn copy(&self, src_key: &str, dst_key: &str) -> PyResult<ObjectMetadata> {
+        let manager = self.manager.clone();
+        let src_key = src_key.to_string();
+        let dst_key = dst_key.to_string();
+
+        self.runtime.block_on(async move {
+            let guard = manager.lock().await;
+            let storage = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
+            })?;
+
+            storage.backend().copy(&src_key, &dst_key).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Move an object to a new key
+    pub fn rename(&self, src_key: &str, dst_key: &str) -> PyResult<ObjectMetadata> {
+        let manager = self.manager.clone();
+        let src_key = src_key.to_string();
+        let dst_key = dst_key.to_string();
+
+        self.runtime.block_on(async move {
+            let guard = manager.lock().await;
+            let storage = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
+            })?;
+
+            storage.backend().rename(&src_key, &dst_key).await.map_err(PyErr::from)
         })
     }
 
@@ -603,9 +1885,23 @@ impl PyStorage {
                 pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
             })?;
 
-            storage.backend().list(&prefix).await.map_err(|e| {
-                pyo3::exceptions::PyRuntimeError::new_err(e.to_string())
-            })
+            storage.backend().list(&prefix).await.map_err(PyErr::from)
+        })
+    }
+
+    /// Time-limited download URL for a key (S3 only)
+    #[pyo3(signature = (key, expires_secs=3600))]
+    pub fn presign_get(&self, key: &str, expires_secs: u64) -> PyResult<String> {
+        let manager = self.manager.clone();
+        let key = key.to_string();
+
+        self.runtime.block_on(async move {
+            let guard = manager.lock().await;
+            let storage = guard.as_ref().ok_or_else(|| {
+                pyo3::exceptions::PyRuntimeError::new_err("Storage not initialized")
+            })?;
+
+            storage.backend().presign_get(&key, expires_secs).await.map_err(PyErr::from)
         })
     }
 }
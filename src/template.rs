@@ -0,0 +1,74 @@
+//! `{placeholder}` templates for naming downloaded files
+
+/// Longest a single substituted value may be, in characters, so one long
+/// title can't push a file name past filesystem limits
+const MAX_VALUE_CHARS: usize = 100;
+
+/// The placeholder names used in `template`, in order
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        names.push(&rest[..end]);
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// Fill each `{name}` in `template` with `value(name)`, made safe to use
+/// inside a single path component. Names without a value are left as is.
+pub fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let name = &rest[start + 1..start + 1 + len];
+        match value(name) {
+            Some(value) => match sanitize(&value) {
+                value if value.is_empty() => out.push('_'),
+                value => out.push_str(&value),
+            },
+            None => out.push_str(&rest[start..start + 2 + len]),
+        }
+        rest = &rest[start + 2 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replace whitespace and characters that aren't allowed in file names on
+/// common filesystems with `_`, and drop leading dots so a value can't form
+/// a hidden file or `..`
+pub fn sanitize(value: &str) -> String {
+    let sanitized: String = value
+        .trim()
+        .chars()
+        .take(MAX_VALUE_CHARS)
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_whitespace() || c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    sanitized.trim_start_matches('.').to_string()
+}
+
+/// Insert `_{n}` before the extension of the last path component, e.g.
+/// `clips/a.mp4` -> `clips/a_2.mp4`
+pub fn with_suffix(path: &str, n: u32) -> String {
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    match path[name_start..].rfind('.').filter(|&dot| dot > 0) {
+        Some(dot) => {
+            let (stem, ext) = path.split_at(name_start + dot);
+            format!("{}_{}{}", stem, n, ext)
+        }
+        None => format!("{}_{}", path, n),
+    }
+}
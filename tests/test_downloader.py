@@ -0,0 +1,93 @@
+"""Tests for DownloadManager's asyncio API."""
+
+import asyncio
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+FAST_BODY = bytes(range(256)) * 40
+SLOW_SIZE = 10_000_000
+
+
+class _Handler(BaseHTTPRequestHandler):
+    """Serves /fast.mp4 at once and trickles /slow.mp4 until the client leaves."""
+
+    def log_message(self, *args):
+        pass
+
+    def _headers(self):
+        if self.path not in ("/fast.mp4", "/slow.mp4"):
+            self.send_error(404)
+            return False
+        size = len(FAST_BODY) if self.path == "/fast.mp4" else SLOW_SIZE
+        self.send_response(200)
+        self.send_header("Content-Type", "video/mp4")
+        self.send_header("Content-Length", str(size))
+        self.end_headers()
+        return True
+
+    def do_HEAD(self):
+        self._headers()
+
+    def do_GET(self):
+        if not self._headers():
+            return
+        if self.path == "/fast.mp4":
+            self.wfile.write(FAST_BODY)
+            return
+
+        self.server.slow_started.set()
+        try:
+            for _ in range(SLOW_SIZE // 1000):
+                self.wfile.write(b"\0" * 1000)
+                self.wfile.flush()
+                time.sleep(0.05)
+        except (BrokenPipeError, ConnectionResetError):
+            self.server.slow_closed.set()
+
+
+@pytest.fixture
+def server():
+    httpd = ThreadingHTTPServer(("127.0.0.1", 0), _Handler)
+    httpd.daemon_threads = True
+    httpd.slow_started = threading.Event()
+    httpd.slow_closed = threading.Event()
+    thread = threading.Thread(target=httpd.serve_forever, daemon=True)
+    thread.start()
+    yield httpd
+    httpd.shutdown()
+
+
+@pytest.mark.asyncio
+async def test_cancelling_one_async_download_stops_its_transfer(server, tmp_path):
+    """Two downloads run at once, and cancelling one ends only its transfer."""
+    from videoscraper import DownloadManager, ScraperConfig
+
+    config = ScraperConfig()
+    config.rate_limit_per_second = 100.0
+    config.respect_robots_txt = False
+    manager = DownloadManager(config)
+    base = "http://127.0.0.1:%d" % server.server_port
+    loop = asyncio.get_running_loop()
+
+    slow = asyncio.ensure_future(
+        manager.download_async(base + "/slow.mp4", str(tmp_path / "slow.mp4"))
+    )
+    assert await loop.run_in_executor(None, server.slow_started.wait, 5)
+
+    # The other download finishes while the slow one is still transferring
+    result = await manager.download_async(base + "/fast.mp4", str(tmp_path / "fast.mp4"))
+    assert result.size_bytes == len(FAST_BODY)
+    assert (tmp_path / "fast.mp4").read_bytes() == FAST_BODY
+    assert not slow.done()
+
+    slow.cancel()
+    with pytest.raises(asyncio.CancelledError):
+        await slow
+    assert await loop.run_in_executor(None, server.slow_closed.wait, 5)
+
+    # The cancelled download keeps what it fetched for a later resume
+    assert (tmp_path / "slow.mp4.part").exists()
+    assert not (tmp_path / "slow.mp4").exists()